@@ -51,10 +51,10 @@ async fn main() -> Result<(), Box<dyn Error>> {
         // Essentially here we're executing a new task to run concurrently,
         // which will allow all of our clients to be processed concurrently.
 
-        let private_key = keypair.clone();
+        let private_key = keypair;
         tokio::spawn(async move {
             // ADNL: handle handshake
-            let mut adnl_server = AdnlPeer::handle_handshake(socket, |_| Some(private_key.clone()))
+            let mut adnl_server = AdnlPeer::handle_handshake(socket, |_| Some(private_key))
                 .await
                 .expect("handshake failed");
 
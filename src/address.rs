@@ -0,0 +1,207 @@
+//! Addresses of smart-contracts in raw `workchain:hex` form and in user-friendly base64 form
+//! with flags and checksum.
+//!
+//! ```
+//! use adnl::address::{AccountAddress, AddressFlags};
+//!
+//! let (address, flags) =
+//!     AccountAddress::from_user_friendly("EQDtFpEwcFAEcRe5mLVh2N6C0x-_hJEM7W61_JLnSF74p4q2")
+//!         .unwrap();
+//! assert_eq!(
+//!     address.to_string(),
+//!     "0:ed1691307050047117b998b561d8de82d31fbf84910ced6eb5fc92e7485ef8a7"
+//! );
+//! assert!(flags.bounceable && !flags.testnet);
+//! let flags = AddressFlags { bounceable: false, testnet: false };
+//! assert_eq!(
+//!     address.to_user_friendly(flags).unwrap(),
+//!     "UQDtFpEwcFAEcRe5mLVh2N6C0x-_hJEM7W61_JLnSF74p9dz"
+//! );
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use base64::Engine as _;
+use thiserror::Error;
+
+use crate::helper_types::CRC16;
+use crate::tl::ton::lite_server::AccountId;
+use crate::tvm::{StackError, StackValue};
+
+const BOUNCEABLE_TAG: u8 = 0x11;
+const NON_BOUNCEABLE_TAG: u8 = 0x51;
+const TESTNET_FLAG: u8 = 0x80;
+
+/// Length of decoded user-friendly address: tag, workchain, account id and CRC16
+const USER_FRIENDLY_LEN: usize = 36;
+
+/// Error of address parsing or formatting
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AddressError {
+    #[error("Invalid address format")]
+    InvalidFormat,
+    #[error("Invalid address checksum")]
+    InvalidChecksum,
+    #[error("Invalid user-friendly address tag {0:#04x}")]
+    InvalidTag(u8),
+    #[error("Workchain {0} does not fit into user-friendly address")]
+    WorkchainOutOfRange(i32),
+}
+
+/// Flags of user-friendly address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AddressFlags {
+    /// Messages to the address should bounce if it does not exist, which is the case for
+    /// addresses of deployed contracts, as opposed to wallets which may be not deployed yet
+    pub bounceable: bool,
+    /// Address is meant for testnet only
+    pub testnet: bool,
+}
+
+/// Address of smart-contract: workchain and 256-bit account id. [`fmt::Display`] uses raw form,
+/// [`FromStr`] accepts both raw and user-friendly forms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccountAddress {
+    pub workchain: i32,
+    pub id: [u8; 32],
+}
+
+impl AccountAddress {
+    pub fn new(workchain: i32, id: [u8; 32]) -> Self {
+        Self { workchain, id }
+    }
+
+    /// Parse raw address, such as `0:ed16…f8a7` with 64 hex digits of account id
+    pub fn from_raw(address: &str) -> Result<Self, AddressError> {
+        let (workchain, id) = address.split_once(':').ok_or(AddressError::InvalidFormat)?;
+        let mut bytes = [0; 32];
+        hex::decode_to_slice(id, &mut bytes).map_err(|_| AddressError::InvalidFormat)?;
+        Ok(Self {
+            workchain: workchain.parse().map_err(|_| AddressError::InvalidFormat)?,
+            id: bytes,
+        })
+    }
+
+    /// Format address in raw form with lowercase hex digits
+    pub fn to_raw(&self) -> String {
+        alloc::format!("{self}")
+    }
+
+    /// Parse user-friendly address of 48 characters in URL-safe or standard base64 and
+    /// return it with its flags
+    pub fn from_user_friendly(address: &str) -> Result<(Self, AddressFlags), AddressError> {
+        if address.len() != 48 {
+            return Err(AddressError::InvalidFormat);
+        }
+        let engine = match address.contains(['-', '_']) {
+            true => &base64::engine::general_purpose::URL_SAFE,
+            false => &base64::engine::general_purpose::STANDARD,
+        };
+        let mut data = [0; USER_FRIENDLY_LEN];
+        match engine.decode_slice(address, &mut data) {
+            Ok(USER_FRIENDLY_LEN) => {}
+            _ => return Err(AddressError::InvalidFormat),
+        }
+        let (payload, crc) = data.split_at(34);
+        if CRC16.checksum(payload).to_be_bytes() != crc {
+            return Err(AddressError::InvalidChecksum);
+        }
+        let flags = AddressFlags {
+            bounceable: match data[0] & !TESTNET_FLAG {
+                BOUNCEABLE_TAG => true,
+                NON_BOUNCEABLE_TAG => false,
+                _ => return Err(AddressError::InvalidTag(data[0])),
+            },
+            testnet: data[0] & TESTNET_FLAG != 0,
+        };
+        let address = Self {
+            workchain: data[1] as i8 as i32,
+            id: data[2..34].try_into().unwrap(),
+        };
+        Ok((address, flags))
+    }
+
+    /// Format address in user-friendly form with URL-safe base64, which is possible for
+    /// workchains which fit into `i8`
+    pub fn to_user_friendly(&self, flags: AddressFlags) -> Result<String, AddressError> {
+        let workchain = i8::try_from(self.workchain)
+            .map_err(|_| AddressError::WorkchainOutOfRange(self.workchain))?;
+        let mut data = [0; USER_FRIENDLY_LEN];
+        data[0] = match flags.bounceable {
+            true => BOUNCEABLE_TAG,
+            false => NON_BOUNCEABLE_TAG,
+        };
+        if flags.testnet {
+            data[0] |= TESTNET_FLAG;
+        }
+        data[1] = workchain as u8;
+        data[2..34].copy_from_slice(&self.id);
+        let crc = CRC16.checksum(&data[..34]);
+        data[34..].copy_from_slice(&crc.to_be_bytes());
+        Ok(base64::engine::general_purpose::URL_SAFE.encode(data))
+    }
+}
+
+impl fmt::Display for AccountAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.workchain, hex::encode(self.id))
+    }
+}
+
+impl FromStr for AccountAddress {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.contains(':') {
+            true => Self::from_raw(s),
+            false => Ok(Self::from_user_friendly(s)?.0),
+        }
+    }
+}
+
+impl From<AccountId> for AccountAddress {
+    fn from(value: AccountId) -> Self {
+        Self::new(value.workchain, value.id)
+    }
+}
+
+impl From<AccountAddress> for AccountId {
+    fn from(value: AccountAddress) -> Self {
+        Self {
+            workchain: value.workchain,
+            id: value.id,
+        }
+    }
+}
+
+impl From<AccountAddress> for StackValue {
+    fn from(value: AccountAddress) -> Self {
+        AccountId::from(value).into()
+    }
+}
+
+impl TryFrom<StackValue> for AccountAddress {
+    type Error = StackError;
+
+    fn try_from(value: StackValue) -> Result<Self, Self::Error> {
+        Ok(AccountId::try_from(value)?.into())
+    }
+}
+
+/// Serialized as string in raw form, deserialized from either form
+#[cfg(feature = "serde")]
+impl serde::Serialize for AccountAddress {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AccountAddress {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        address.parse().map_err(serde::de::Error::custom)
+    }
+}
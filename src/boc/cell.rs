@@ -0,0 +1,332 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use sha2::{Digest, Sha256};
+
+use super::BocError;
+
+/// Maximum number of data bits in a cell
+pub const MAX_BITS: usize = 1023;
+/// Maximum number of references of a cell
+pub const MAX_REFS: usize = 4;
+/// Maximum level of a cell
+pub const MAX_LEVEL: u8 = 3;
+/// Maximum depth of a cell
+const MAX_DEPTH: u16 = 1024;
+
+/// Type of a cell, exotic cells store their type in the first data byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellType {
+    Ordinary,
+    PrunedBranch,
+    Library,
+    MerkleProof,
+    MerkleUpdate,
+}
+
+impl CellType {
+    fn from_exotic_tag(tag: u8) -> Result<Self, BocError> {
+        match tag {
+            1 => Ok(Self::PrunedBranch),
+            2 => Ok(Self::Library),
+            3 => Ok(Self::MerkleProof),
+            4 => Ok(Self::MerkleUpdate),
+            _ => Err(BocError::InvalidCell("unknown exotic cell type")),
+        }
+    }
+
+    fn is_merkle(self) -> bool {
+        matches!(self, Self::MerkleProof | Self::MerkleUpdate)
+    }
+}
+
+/// Set of levels at which hashes of a cell differ, bit `i` stands for level `i + 1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LevelMask(u8);
+
+impl LevelMask {
+    fn level(self) -> u8 {
+        8 - self.0.leading_zeros() as u8
+    }
+
+    /// Number of distinct hashes of a cell
+    fn hash_count(self) -> usize {
+        self.0.count_ones() as usize + 1
+    }
+
+    /// Mask of levels up to `level`
+    fn apply(self, level: u8) -> Self {
+        Self(self.0 & ((1 << level) - 1))
+    }
+
+    fn is_significant(self, level: u8) -> bool {
+        level == 0 || (self.0 >> (level - 1)) & 1 != 0
+    }
+}
+
+/// Immutable cell: up to 1023 bits of data and up to 4 references to other cells.
+/// Cloning is cheap, cells are shared between clones.
+#[derive(Clone)]
+pub struct Cell(Arc<CellData>);
+
+struct CellData {
+    cell_type: CellType,
+    data: Vec<u8>,
+    bit_len: usize,
+    references: Vec<Cell>,
+    level_mask: LevelMask,
+    /// Computed hashes and depths: for all significant levels, or only the representation
+    /// one for pruned branches, which store lower ones in data
+    hashes: Vec<[u8; 32]>,
+    depths: Vec<u16>,
+}
+
+impl Cell {
+    /// Create cell from `bit_len` bits of `data` and `references`. `data` must contain
+    /// exactly `bit_len` bits rounded up to bytes, extra bits of the last byte are ignored.
+    /// Exotic cell type is read from the first byte of data.
+    pub fn new(
+        mut data: Vec<u8>,
+        bit_len: usize,
+        references: Vec<Cell>,
+        exotic: bool,
+    ) -> Result<Self, BocError> {
+        if bit_len > MAX_BITS || data.len() != bit_len.div_ceil(8) {
+            return Err(BocError::InvalidCell("invalid data length"));
+        }
+        if references.len() > MAX_REFS {
+            return Err(BocError::InvalidCell("too many references"));
+        }
+        if !bit_len.is_multiple_of(8) {
+            data[bit_len / 8] &= 0xff << (8 - bit_len % 8);
+        }
+
+        let cell_type = if exotic {
+            CellType::from_exotic_tag(*data.first().unwrap_or(&0xff))?
+        } else {
+            CellType::Ordinary
+        };
+        let level_mask = match cell_type {
+            CellType::Ordinary => LevelMask(
+                references
+                    .iter()
+                    .fold(0, |mask, cell| mask | cell.0.level_mask.0),
+            ),
+            CellType::PrunedBranch => {
+                let mask = LevelMask(*data.get(1).unwrap_or(&0));
+                let expected_bits = 16 + (mask.hash_count() - 1) * (256 + 16);
+                if mask.0 == 0 || mask.level() > MAX_LEVEL || bit_len != expected_bits {
+                    return Err(BocError::InvalidCell("invalid pruned branch"));
+                }
+                if !references.is_empty() {
+                    return Err(BocError::InvalidCell("pruned branch with references"));
+                }
+                mask
+            }
+            CellType::Library => {
+                if bit_len != 8 + 256 || !references.is_empty() {
+                    return Err(BocError::InvalidCell("invalid library cell"));
+                }
+                LevelMask(0)
+            }
+            CellType::MerkleProof | CellType::MerkleUpdate => {
+                let count = if cell_type == CellType::MerkleProof {
+                    1
+                } else {
+                    2
+                };
+                if bit_len != 8 + count * (256 + 16) || references.len() != count {
+                    return Err(BocError::InvalidCell("invalid merkle cell"));
+                }
+                for (i, cell) in references.iter().enumerate() {
+                    let hash_offset = 1 + i * 32;
+                    let depth_offset = 1 + count * 32 + i * 2;
+                    let depth = u16::from_be_bytes([data[depth_offset], data[depth_offset + 1]]);
+                    if data[hash_offset..hash_offset + 32] != cell.hash_at(0)
+                        || depth != cell.depth_at(0)
+                    {
+                        return Err(BocError::InvalidCell("merkle hash mismatch"));
+                    }
+                }
+                LevelMask(
+                    references
+                        .iter()
+                        .fold(0, |mask, cell| mask | (cell.0.level_mask.0 >> 1)),
+                )
+            }
+        };
+
+        let mut cell = CellData {
+            cell_type,
+            data,
+            bit_len,
+            references,
+            level_mask,
+            hashes: Vec::new(),
+            depths: Vec::new(),
+        };
+        cell.compute_hashes()?;
+        Ok(Self(Arc::new(cell)))
+    }
+
+    pub fn cell_type(&self) -> CellType {
+        self.0.cell_type
+    }
+
+    pub fn is_exotic(&self) -> bool {
+        self.0.cell_type != CellType::Ordinary
+    }
+
+    /// Data bytes, bits after [`Self::bit_len`] in the last byte are zero
+    pub fn data(&self) -> &[u8] {
+        &self.0.data
+    }
+
+    pub fn bit_len(&self) -> usize {
+        self.0.bit_len
+    }
+
+    pub fn references(&self) -> &[Cell] {
+        &self.0.references
+    }
+
+    pub fn level(&self) -> u8 {
+        self.0.level_mask.level()
+    }
+
+    pub fn level_mask(&self) -> u8 {
+        self.0.level_mask.0
+    }
+
+    /// Representation hash of the cell
+    pub fn hash(&self) -> [u8; 32] {
+        self.hash_at(MAX_LEVEL)
+    }
+
+    pub fn depth(&self) -> u16 {
+        self.depth_at(MAX_LEVEL)
+    }
+
+    /// Hash of the cell at `level`. Hash at level 0 does not depend on pruning, so it matches
+    /// hash of the original cell for cells of Merkle proofs.
+    pub fn hash_at(&self, level: u8) -> [u8; 32] {
+        let (i, stored) = self.0.hash_index(level);
+        match stored {
+            Some(offset) => self.0.data[offset + i * 32..offset + (i + 1) * 32]
+                .try_into()
+                .unwrap(),
+            None => self.0.hashes[i],
+        }
+    }
+
+    pub fn depth_at(&self, level: u8) -> u16 {
+        let (i, stored) = self.0.hash_index(level);
+        match stored {
+            Some(_) => {
+                let offset = 2 + self.0.level_mask.0.count_ones() as usize * 32 + i * 2;
+                u16::from_be_bytes([self.0.data[offset], self.0.data[offset + 1]])
+            }
+            None => self.0.depths[i],
+        }
+    }
+
+    /// Descriptor bytes and data with completion tag of serialized cell
+    pub(crate) fn serialized_data(&self) -> ([u8; 2], Vec<u8>) {
+        (self.0.descriptors(self.0.level_mask), self.0.tagged_data())
+    }
+}
+
+impl CellData {
+    fn descriptors(&self, level_mask: LevelMask) -> [u8; 2] {
+        let exotic = (self.cell_type != CellType::Ordinary) as u8;
+        let d1 = self.references.len() as u8 + 8 * exotic + 32 * level_mask.0;
+        let d2 = (self.bit_len / 8 + self.bit_len.div_ceil(8)) as u8;
+        [d1, d2]
+    }
+
+    /// Data with completion tag: bit `1` after the last data bit if data is not byte-aligned
+    fn tagged_data(&self) -> Vec<u8> {
+        let mut data = self.data.clone();
+        if !self.bit_len.is_multiple_of(8) {
+            data[self.bit_len / 8] |= 0x80 >> (self.bit_len % 8);
+        }
+        data
+    }
+
+    /// Index in hashes for `level`, and offset of stored hashes if it is a lower hash of
+    /// pruned branch
+    fn hash_index(&self, level: u8) -> (usize, Option<usize>) {
+        let i = self.level_mask.apply(level.min(MAX_LEVEL)).hash_count() - 1;
+        if self.cell_type == CellType::PrunedBranch {
+            if i < self.level_mask.hash_count() - 1 {
+                return (i, Some(2));
+            }
+            return (0, None);
+        }
+        (i, None)
+    }
+
+    fn compute_hashes(&mut self) -> Result<(), BocError> {
+        let hash_count = if self.cell_type == CellType::PrunedBranch {
+            1
+        } else {
+            self.level_mask.hash_count()
+        };
+        let offset = self.level_mask.hash_count() - hash_count;
+        let child_level_offset = self.cell_type.is_merkle() as u8;
+
+        let mut hash_i = 0;
+        for level in 0..=self.level_mask.level() {
+            if !self.level_mask.is_significant(level) {
+                continue;
+            }
+            if hash_i < offset {
+                hash_i += 1;
+                continue;
+            }
+            let mut hasher = Sha256::new();
+            hasher.update(self.descriptors(self.level_mask.apply(level)));
+            if hash_i == offset {
+                hasher.update(self.tagged_data());
+            } else {
+                hasher.update(self.hashes[hash_i - offset - 1]);
+            }
+            let mut depth = 0;
+            for cell in &self.references {
+                let child_depth = cell.depth_at(level + child_level_offset);
+                hasher.update(child_depth.to_be_bytes());
+                depth = depth.max(child_depth + 1);
+            }
+            for cell in &self.references {
+                hasher.update(cell.hash_at(level + child_level_offset));
+            }
+            if depth > MAX_DEPTH {
+                return Err(BocError::InvalidCell("cell is too deep"));
+            }
+            self.hashes.push(hasher.finalize().into());
+            self.depths.push(depth);
+            hash_i += 1;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash() == other.hash()
+    }
+}
+
+impl Eq for Cell {}
+
+impl fmt::Debug for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cell")
+            .field("type", &self.0.cell_type)
+            .field("bits", &self.0.bit_len)
+            .field("data", &hex::encode(&self.0.data))
+            .field("references", &self.0.references)
+            .finish()
+    }
+}
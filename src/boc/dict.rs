@@ -0,0 +1,104 @@
+use alloc::vec::Vec;
+
+use super::{BocError, CellSlice};
+
+fn key_bit(key: &[u8], index: usize) -> bool {
+    (key[index / 8] >> (7 - index % 8)) & 1 != 0
+}
+
+fn set_key_bit(key: &mut [u8], index: usize, bit: bool) {
+    if bit {
+        key[index / 8] |= 1 << (7 - index % 8);
+    }
+}
+
+/// Load label of dictionary node with `remaining` key bits, returns its length and the bit
+/// for labels of same bits
+fn load_label(node: &mut CellSlice, remaining: usize) -> Result<(usize, Option<bool>), BocError> {
+    // length of `#<= remaining` field
+    let length_bits = (usize::BITS - remaining.leading_zeros()) as usize;
+    // label is stored as hml_short$0, hml_long$10 or hml_same$11
+    let (label_len, same) = if !node.load_bit()? {
+        let mut length = 0;
+        while node.load_bit()? {
+            length += 1;
+        }
+        (length, None)
+    } else if !node.load_bit()? {
+        (node.load_uint(length_bits)? as usize, None)
+    } else {
+        let bit = node.load_bit()?;
+        (node.load_uint(length_bits)? as usize, Some(bit))
+    };
+    if label_len > remaining {
+        return Err(BocError::InvalidCell("invalid dictionary label"));
+    }
+    Ok((label_len, same))
+}
+
+/// Find `key` of `key_bits` bits in dictionary (`Hashmap` or `HashmapAug` of TL-B) which root
+/// node starts at `root`. Returns slice of the leaf after the key label: the value, which is
+/// preceded by extra for augmented dictionaries.
+pub fn dict_get<'a>(
+    root: CellSlice<'a>,
+    key: &[u8],
+    key_bits: usize,
+) -> Result<Option<CellSlice<'a>>, BocError> {
+    assert!(key_bits <= key.len() * 8, "key is too short");
+    let mut node = root;
+    let mut offset = 0;
+    loop {
+        let (label_len, same) = load_label(&mut node, key_bits - offset)?;
+        for i in offset..offset + label_len {
+            let bit = match same {
+                Some(bit) => bit,
+                None => node.load_bit()?,
+            };
+            if bit != key_bit(key, i) {
+                return Ok(None);
+            }
+        }
+        offset += label_len;
+        if offset == key_bits {
+            return Ok(Some(node));
+        }
+        let left = node.load_reference()?;
+        let right = node.load_reference()?;
+        node = CellSlice::new(if key_bit(key, offset) { right } else { left })?;
+        offset += 1;
+    }
+}
+
+/// Get all entries of dictionary with keys of `key_bits` bits in ascending order of keys, as
+/// for unsigned integers. Keys are padded with zero bits to whole bytes, values are the same as
+/// in [`dict_get`].
+pub fn dict_entries<'a>(
+    root: CellSlice<'a>,
+    key_bits: usize,
+) -> Result<Vec<(Vec<u8>, CellSlice<'a>)>, BocError> {
+    let mut entries = Vec::new();
+    let mut stack = alloc::vec![(root, alloc::vec![0; key_bits.div_ceil(8)], 0)];
+    while let Some((mut node, mut key, mut offset)) = stack.pop() {
+        let (label_len, same) = load_label(&mut node, key_bits - offset)?;
+        for i in offset..offset + label_len {
+            let bit = match same {
+                Some(bit) => bit,
+                None => node.load_bit()?,
+            };
+            set_key_bit(&mut key, i, bit);
+        }
+        offset += label_len;
+        if offset == key_bits {
+            entries.push((key, node));
+            continue;
+        }
+        let left = CellSlice::new(node.load_reference()?)?;
+        let right = CellSlice::new(node.load_reference()?)?;
+        let mut right_key = key.clone();
+        set_key_bit(&mut right_key, offset, true);
+        // the left branch is popped first
+        stack.push((right, right_key, offset + 1));
+        stack.push((left, key, offset + 1));
+    }
+    Ok(entries)
+}
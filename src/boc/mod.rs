@@ -0,0 +1,55 @@
+//! Bag of cells (BoC): serialization of cell trees in `b5ee9c72` format, used for blocks,
+//! account states and proofs in liteserver answers, along with reading and writing of cells.
+//!
+//! ```
+//! use adnl::boc;
+//!
+//! let root = boc::parse_single(&hex::decode("b5ee9c72010101010002000000").unwrap()).unwrap();
+//! assert_eq!((root.bit_len(), root.references().len()), (0, 0));
+//! assert_eq!(
+//!     hex::encode(root.hash()),
+//!     "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7"
+//! );
+//! assert_eq!(boc::serialize(&root), hex::decode("b5ee9c724101010100020000004cacb9cd").unwrap());
+//! ```
+
+mod cell;
+mod dict;
+mod serialization;
+mod slice;
+
+use thiserror::Error;
+
+pub use cell::{Cell, CellType, MAX_BITS, MAX_LEVEL, MAX_REFS};
+pub use dict::{dict_entries, dict_get};
+pub use serialization::{parse, parse_single, serialize, BocSerializer};
+pub use slice::{CellBuilder, CellSlice};
+
+/// Error of BoC parsing or cell creation
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BocError {
+    #[error("Invalid BoC magic")]
+    InvalidMagic,
+    #[error("Unexpected end of BoC")]
+    UnexpectedEof,
+    #[error("Invalid BoC header")]
+    InvalidHeader,
+    #[error("BoC CRC32C mismatch")]
+    InvalidChecksum,
+    #[error("Invalid cell reference")]
+    InvalidReference,
+    #[error("Invalid cell: {0}")]
+    InvalidCell(&'static str),
+    #[error("Expected single root, got {0}")]
+    NotSingleRoot(usize),
+    #[error("Trailing bytes after BoC")]
+    TrailingBytes,
+    #[error("Unexpected {0:?} exotic cell")]
+    ExoticCell(CellType),
+    #[error("Not enough data or references in cell")]
+    CellUnderflow,
+    #[error("Too much data or references for cell")]
+    CellOverflow,
+    #[error("Integer does not fit into its bit length")]
+    IntegerOverflow,
+}
@@ -0,0 +1,329 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use crc::{Crc, CRC_32_ISCSI};
+
+use super::{BocError, Cell, MAX_REFS};
+
+/// Magic of generic BoC format
+const BOC_MAGIC: [u8; 4] = [0xb5, 0xee, 0x9c, 0x72];
+
+static CRC32C: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+
+fn take<'a>(packet: &mut &'a [u8], length: usize) -> Result<&'a [u8], BocError> {
+    if packet.len() < length {
+        return Err(BocError::UnexpectedEof);
+    }
+    let (head, tail) = packet.split_at(length);
+    *packet = tail;
+    Ok(head)
+}
+
+/// Read big-endian unsigned integer of `size` bytes
+fn take_uint(packet: &mut &[u8], size: usize) -> Result<usize, BocError> {
+    Ok(take(packet, size)?
+        .iter()
+        .fold(0, |value, byte| (value << 8) | *byte as usize))
+}
+
+fn write_uint(value: usize, size: usize, packet: &mut Vec<u8>) {
+    packet.extend_from_slice(&(value as u64).to_be_bytes()[8 - size..]);
+}
+
+/// Number of bytes needed to store `value`
+fn byte_size(value: usize) -> usize {
+    (usize::BITS - value.leading_zeros()).div_ceil(8).max(1) as usize
+}
+
+/// Cell as it is stored in BoC, with references as indices
+struct RawCell<'a> {
+    exotic: bool,
+    level_mask: u8,
+    data: &'a [u8],
+    bit_len: usize,
+    references: Vec<usize>,
+}
+
+fn parse_cell<'a>(
+    packet: &mut &'a [u8],
+    index: usize,
+    cell_count: usize,
+    size: usize,
+) -> Result<RawCell<'a>, BocError> {
+    let [d1, d2] = take(packet, 2)?.try_into().unwrap();
+    let reference_count = (d1 & 7) as usize;
+    let exotic = d1 & 8 != 0;
+    let with_hashes = d1 & 16 != 0;
+    let level_mask = d1 >> 5;
+    if reference_count > MAX_REFS {
+        return Err(BocError::InvalidCell("too many references"));
+    }
+    if with_hashes {
+        // stored hashes and depths are recomputed anyway
+        let hash_count = level_mask.count_ones() as usize + 1;
+        take(packet, hash_count * (32 + 2))?;
+    }
+    let data = take(packet, (d2 as usize).div_ceil(2))?;
+    let bit_len = if d2 % 2 == 0 {
+        data.len() * 8
+    } else {
+        let last = data[data.len() - 1];
+        if last == 0 {
+            return Err(BocError::InvalidCell("missing completion tag"));
+        }
+        data.len() * 8 - last.trailing_zeros() as usize - 1
+    };
+    let references = (0..reference_count)
+        .map(|_| match take_uint(packet, size)? {
+            reference if reference > index && reference < cell_count => Ok(reference),
+            _ => Err(BocError::InvalidReference),
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(RawCell {
+        exotic,
+        level_mask,
+        data,
+        bit_len,
+        references,
+    })
+}
+
+/// Parse BoC, returns its root cells
+pub fn parse(boc: &[u8]) -> Result<Vec<Cell>, BocError> {
+    let mut packet = boc;
+    if take(&mut packet, 4)? != BOC_MAGIC {
+        return Err(BocError::InvalidMagic);
+    }
+    let flags = take(&mut packet, 1)?[0];
+    let has_index = flags & 0x80 != 0;
+    let has_crc32c = flags & 0x40 != 0;
+    let has_cache_bits = flags & 0x20 != 0;
+    let size = (flags & 7) as usize;
+    let offset_size = take(&mut packet, 1)?[0] as usize;
+    if flags & 0x18 != 0
+        || (has_cache_bits && !has_index)
+        || !(1..=4).contains(&size)
+        || !(1..=8).contains(&offset_size)
+    {
+        return Err(BocError::InvalidHeader);
+    }
+
+    let cell_count = take_uint(&mut packet, size)?;
+    let root_count = take_uint(&mut packet, size)?;
+    let absent_count = take_uint(&mut packet, size)?;
+    let data_size = take_uint(&mut packet, offset_size)?;
+    // every cell takes at least 2 bytes, check it before allocating
+    if root_count == 0
+        || root_count > cell_count
+        || absent_count != 0
+        || cell_count > data_size / 2
+        || data_size > packet.len()
+    {
+        return Err(BocError::InvalidHeader);
+    }
+    let roots = (0..root_count)
+        .map(|_| match take_uint(&mut packet, size)? {
+            root if root < cell_count => Ok(root),
+            _ => Err(BocError::InvalidReference),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if has_index {
+        take(&mut packet, cell_count * offset_size)?;
+    }
+    let mut cells_data = take(&mut packet, data_size)?;
+    if has_crc32c {
+        let checksum = u32::from_le_bytes(take(&mut packet, 4)?.try_into().unwrap());
+        if checksum != CRC32C.checksum(&boc[..boc.len() - packet.len() - 4]) {
+            return Err(BocError::InvalidChecksum);
+        }
+    }
+    if !packet.is_empty() {
+        return Err(BocError::TrailingBytes);
+    }
+
+    let raw_cells = (0..cell_count)
+        .map(|index| parse_cell(&mut cells_data, index, cell_count, size))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !cells_data.is_empty() {
+        return Err(BocError::InvalidHeader);
+    }
+
+    // references always point forward, so cells are built from the end
+    let mut cells = Vec::<Cell>::with_capacity(cell_count);
+    for raw in raw_cells.iter().rev() {
+        let references = raw
+            .references
+            .iter()
+            .map(|reference| cells[cell_count - 1 - reference].clone())
+            .collect();
+        let cell = Cell::new(raw.data.to_vec(), raw.bit_len, references, raw.exotic)?;
+        if cell.level_mask() != raw.level_mask {
+            return Err(BocError::InvalidCell("level mask mismatch"));
+        }
+        cells.push(cell);
+    }
+    Ok(roots
+        .into_iter()
+        .map(|root| cells[cell_count - 1 - root].clone())
+        .collect())
+}
+
+/// Parse BoC which must have exactly one root
+pub fn parse_single(boc: &[u8]) -> Result<Cell, BocError> {
+    let mut roots = parse(boc)?;
+    match roots.len() {
+        1 => Ok(roots.remove(0)),
+        count => Err(BocError::NotSingleRoot(count)),
+    }
+}
+
+/// Serialize BoC with single `root` and CRC32C
+pub fn serialize(root: &Cell) -> Vec<u8> {
+    BocSerializer::new().serialize(core::slice::from_ref(root))
+}
+
+/// Serializer of BoC with configurable optional parts. Identical cells are stored once.
+#[derive(Debug, Clone)]
+pub struct BocSerializer {
+    index: bool,
+    crc32c: bool,
+    cache_bits: bool,
+}
+
+impl Default for BocSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BocSerializer {
+    /// Serializer which adds CRC32C, but not index
+    pub fn new() -> Self {
+        Self {
+            index: false,
+            crc32c: true,
+            cache_bits: false,
+        }
+    }
+
+    /// Whether to store index of cell offsets
+    pub fn with_index(mut self, index: bool) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Whether to append CRC32C of BoC
+    pub fn with_crc32c(mut self, crc32c: bool) -> Self {
+        self.crc32c = crc32c;
+        self
+    }
+
+    /// Whether to mark cells with several parents in index, implies index
+    pub fn with_cache_bits(mut self, cache_bits: bool) -> Self {
+        self.cache_bits = cache_bits;
+        self
+    }
+
+    /// Serialize BoC with `roots`
+    pub fn serialize(&self, roots: &[Cell]) -> Vec<u8> {
+        let order = sort_cells(roots);
+        let cell_count = order.len();
+        let indices = order
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (cell.hash(), i))
+            .collect::<BTreeMap<_, _>>();
+        let index_of = |cell: &Cell| indices[&cell.hash()];
+
+        let size = byte_size(cell_count);
+        let mut parents = alloc::vec![0usize; cell_count];
+        let mut cells_data = Vec::new();
+        let mut offsets = Vec::with_capacity(cell_count);
+        for cell in &order {
+            let (descriptors, data) = cell.serialized_data();
+            cells_data.extend_from_slice(&descriptors);
+            cells_data.extend_from_slice(&data);
+            for reference in cell.references() {
+                let index = index_of(reference);
+                parents[index] += 1;
+                write_uint(index, size, &mut cells_data);
+            }
+            offsets.push(cells_data.len());
+        }
+
+        let index = self.index || self.cache_bits;
+        let offset_size = byte_size(cells_data.len() << self.cache_bits as u8);
+        let flags = (index as u8) << 7
+            | (self.crc32c as u8) << 6
+            | (self.cache_bits as u8) << 5
+            | size as u8;
+        let mut boc = Vec::new();
+        boc.extend_from_slice(&BOC_MAGIC);
+        boc.extend_from_slice(&[flags, offset_size as u8]);
+        write_uint(cell_count, size, &mut boc);
+        write_uint(roots.len(), size, &mut boc);
+        write_uint(0, size, &mut boc);
+        write_uint(cells_data.len(), offset_size, &mut boc);
+        for root in roots {
+            write_uint(index_of(root), size, &mut boc);
+        }
+        if index {
+            for (offset, parents) in offsets.into_iter().zip(parents) {
+                let offset = if self.cache_bits {
+                    offset << 1 | (parents > 1) as usize
+                } else {
+                    offset
+                };
+                write_uint(offset, offset_size, &mut boc);
+            }
+        }
+        boc.extend_from_slice(&cells_data);
+        if self.crc32c {
+            let checksum = CRC32C.checksum(&boc);
+            boc.extend_from_slice(&checksum.to_le_bytes());
+        }
+        boc
+    }
+}
+
+/// Order unique cells so that every cell goes before its references, in the same order as
+/// TON node uses for trees without large subtrees
+fn sort_cells(roots: &[Cell]) -> Vec<Cell> {
+    let mut sorter = CellSorter::default();
+    for root in roots {
+        sorter.visit(root);
+    }
+    for root in roots {
+        sorter.allocate(root);
+    }
+    sorter.order.reverse();
+    sorter.order
+}
+
+#[derive(Default)]
+struct CellSorter {
+    visited: BTreeSet<[u8; 32]>,
+    allocated: BTreeSet<[u8; 32]>,
+    /// Cells in reverse order, each one after all its references
+    order: Vec<Cell>,
+}
+
+impl CellSorter {
+    fn visit(&mut self, cell: &Cell) {
+        if !self.visited.insert(cell.hash()) {
+            return;
+        }
+        for reference in cell.references().iter().rev() {
+            self.visit(reference);
+        }
+        for reference in cell.references().iter().rev() {
+            self.allocate(reference);
+        }
+    }
+
+    fn allocate(&mut self, cell: &Cell) {
+        if self.allocated.insert(cell.hash()) {
+            self.order.push(cell.clone());
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use alloc::vec::Vec;
+
+use super::{BocError, Cell, MAX_BITS, MAX_REFS};
+
+/// Reader of data bits and references of an ordinary cell, used to parse TL-B structures
+#[derive(Debug, Clone)]
+pub struct CellSlice<'a> {
+    cell: &'a Cell,
+    bit_offset: usize,
+    reference_offset: usize,
+}
+
+impl<'a> CellSlice<'a> {
+    /// Start reading `cell`, exotic cells such as pruned branches can't be read
+    pub fn new(cell: &'a Cell) -> Result<Self, BocError> {
+        if cell.is_exotic() {
+            return Err(BocError::ExoticCell(cell.cell_type()));
+        }
+        Ok(Self {
+            cell,
+            bit_offset: 0,
+            reference_offset: 0,
+        })
+    }
+
+    pub fn remaining_bits(&self) -> usize {
+        self.cell.bit_len() - self.bit_offset
+    }
+
+    pub fn remaining_references(&self) -> usize {
+        self.cell.references().len() - self.reference_offset
+    }
+
+    pub fn skip_bits(&mut self, bits: usize) -> Result<(), BocError> {
+        if bits > self.remaining_bits() {
+            return Err(BocError::CellUnderflow);
+        }
+        self.bit_offset += bits;
+        Ok(())
+    }
+
+    pub fn load_bit(&mut self) -> Result<bool, BocError> {
+        if self.remaining_bits() == 0 {
+            return Err(BocError::CellUnderflow);
+        }
+        let byte = self.cell.data()[self.bit_offset / 8];
+        let bit = (byte >> (7 - self.bit_offset % 8)) & 1 != 0;
+        self.bit_offset += 1;
+        Ok(bit)
+    }
+
+    /// Load big-endian unsigned integer of up to 64 `bits`
+    pub fn load_uint(&mut self, bits: usize) -> Result<u64, BocError> {
+        assert!(bits <= 64, "integer is too long");
+        if bits > self.remaining_bits() {
+            return Err(BocError::CellUnderflow);
+        }
+        (0..bits).try_fold(0, |value, _| Ok((value << 1) | self.load_bit()? as u64))
+    }
+
+    /// Load big-endian signed integer of up to 64 `bits`
+    pub fn load_int(&mut self, bits: usize) -> Result<i64, BocError> {
+        let value = self.load_uint(bits)?;
+        match bits {
+            0 => Ok(0),
+            _ => Ok(((value << (64 - bits)) as i64) >> (64 - bits)),
+        }
+    }
+
+    /// Load `bits` into bytes, the last byte is padded with zero bits
+    pub fn load_bits(&mut self, bits: usize) -> Result<Vec<u8>, BocError> {
+        if bits > self.remaining_bits() {
+            return Err(BocError::CellUnderflow);
+        }
+        let mut data = alloc::vec![0; bits.div_ceil(8)];
+        for i in 0..bits {
+            data[i / 8] |= (self.load_bit()? as u8) << (7 - i % 8);
+        }
+        Ok(data)
+    }
+
+    pub fn load_u256(&mut self) -> Result<[u8; 32], BocError> {
+        Ok(self.load_bits(256)?.try_into().unwrap())
+    }
+
+    pub fn load_reference(&mut self) -> Result<&'a Cell, BocError> {
+        let reference = self
+            .cell
+            .references()
+            .get(self.reference_offset)
+            .ok_or(BocError::CellUnderflow)?;
+        self.reference_offset += 1;
+        Ok(reference)
+    }
+}
+
+/// Writer of a new ordinary cell
+#[derive(Debug, Clone, Default)]
+pub struct CellBuilder {
+    data: Vec<u8>,
+    bit_len: usize,
+    references: Vec<Cell>,
+}
+
+impl CellBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bit_len(&self) -> usize {
+        self.bit_len
+    }
+
+    pub fn store_bit(&mut self, bit: bool) -> Result<&mut Self, BocError> {
+        if self.bit_len == MAX_BITS {
+            return Err(BocError::CellOverflow);
+        }
+        if self.bit_len.is_multiple_of(8) {
+            self.data.push(0);
+        }
+        self.data[self.bit_len / 8] |= (bit as u8) << (7 - self.bit_len % 8);
+        self.bit_len += 1;
+        Ok(self)
+    }
+
+    /// Store `value` as big-endian unsigned integer of up to 64 `bits`
+    pub fn store_uint(&mut self, value: u64, bits: usize) -> Result<&mut Self, BocError> {
+        assert!(bits <= 64, "integer is too long");
+        if bits < 64 && value >> bits != 0 {
+            return Err(BocError::IntegerOverflow);
+        }
+        if self.bit_len + bits > MAX_BITS {
+            return Err(BocError::CellOverflow);
+        }
+        for i in (0..bits).rev() {
+            self.store_bit((value >> i) & 1 != 0)?;
+        }
+        Ok(self)
+    }
+
+    /// Store `value` as big-endian signed integer of up to 64 `bits`
+    pub fn store_int(&mut self, value: i64, bits: usize) -> Result<&mut Self, BocError> {
+        assert!(bits <= 64, "integer is too long");
+        let fits = match bits {
+            0 => value == 0,
+            64 => true,
+            _ => value >> (bits - 1) == value >> 63,
+        };
+        if !fits {
+            return Err(BocError::IntegerOverflow);
+        }
+        let mask = if bits == 64 {
+            u64::MAX
+        } else {
+            (1 << bits) - 1
+        };
+        self.store_uint(value as u64 & mask, bits)
+    }
+
+    /// Store the first `bits` of `data`
+    pub fn store_bits(&mut self, data: &[u8], bits: usize) -> Result<&mut Self, BocError> {
+        assert!(bits <= data.len() * 8, "not enough data");
+        if self.bit_len + bits > MAX_BITS {
+            return Err(BocError::CellOverflow);
+        }
+        for i in 0..bits {
+            self.store_bit((data[i / 8] >> (7 - i % 8)) & 1 != 0)?;
+        }
+        Ok(self)
+    }
+
+    pub fn store_bytes(&mut self, data: &[u8]) -> Result<&mut Self, BocError> {
+        self.store_bits(data, data.len() * 8)
+    }
+
+    pub fn store_reference(&mut self, cell: Cell) -> Result<&mut Self, BocError> {
+        if self.references.len() == MAX_REFS {
+            return Err(BocError::CellOverflow);
+        }
+        self.references.push(cell);
+        Ok(self)
+    }
+
+    pub fn build(&self) -> Result<Cell, BocError> {
+        Cell::new(
+            self.data.clone(),
+            self.bit_len,
+            self.references.clone(),
+            false,
+        )
+    }
+}
@@ -0,0 +1,96 @@
+//! Blockchain configuration parameters, stored in masterchain key blocks and states
+
+mod prices;
+mod validators;
+mod workchains;
+
+use alloc::vec::Vec;
+
+use crate::boc::{dict_get, BocError, Cell, CellSlice};
+
+pub use prices::{GasPrices, MsgForwardPrices};
+pub use validators::{ValidatorDescr, ValidatorSet};
+pub use workchains::WorkchainDescr;
+
+/// Get cell of parameter `index` from configuration dictionary `root` (`Hashmap 32 ^Cell`)
+pub fn config_param(root: &Cell, index: i32) -> Result<Option<&Cell>, BocError> {
+    match dict_get(CellSlice::new(root)?, &index.to_be_bytes(), 32)? {
+        Some(mut value) => Ok(Some(value.load_reference()?)),
+        None => Ok(None),
+    }
+}
+
+/// Configuration dictionary with typed access to commonly used parameters. Parameters are
+/// decoded on access, so a config taken from a proof may have only some of them available,
+/// others being pruned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockchainConfig {
+    root: Cell,
+}
+
+impl BlockchainConfig {
+    /// Use configuration dictionary with `root` cell
+    pub fn new(root: Cell) -> Self {
+        Self { root }
+    }
+
+    pub fn root(&self) -> &Cell {
+        &self.root
+    }
+
+    /// Get cell of parameter `index`, see [`config_param`]
+    pub fn param(&self, index: i32) -> Result<Option<&Cell>, BocError> {
+        config_param(&self.root, index)
+    }
+
+    /// Address of config smart-contract in masterchain, param 0
+    pub fn config_address(&self) -> Result<Option<[u8; 32]>, BocError> {
+        self.address_param(0)
+    }
+
+    /// Address of elector smart-contract in masterchain, param 1
+    pub fn elector_address(&self) -> Result<Option<[u8; 32]>, BocError> {
+        self.address_param(1)
+    }
+
+    fn address_param(&self, index: i32) -> Result<Option<[u8; 32]>, BocError> {
+        self.param(index)?
+            .map(|cell| CellSlice::new(cell)?.load_u256())
+            .transpose()
+    }
+
+    /// Descriptions of workchains, param 12
+    pub fn workchains(&self) -> Result<Option<Vec<WorkchainDescr>>, BocError> {
+        self.param(12)?.map(WorkchainDescr::parse_all).transpose()
+    }
+
+    /// Gas prices of masterchain, param 20, or basechain, param 21
+    pub fn gas_prices(&self, masterchain: bool) -> Result<Option<GasPrices>, BocError> {
+        let index = if masterchain { 20 } else { 21 };
+        self.param(index)?.map(GasPrices::parse).transpose()
+    }
+
+    /// Message forwarding prices of masterchain, param 24, or basechain, param 25
+    pub fn msg_forward_prices(
+        &self,
+        masterchain: bool,
+    ) -> Result<Option<MsgForwardPrices>, BocError> {
+        let index = if masterchain { 24 } else { 25 };
+        self.param(index)?.map(MsgForwardPrices::parse).transpose()
+    }
+
+    /// Previous validator set, param 32
+    pub fn prev_validators(&self) -> Result<Option<ValidatorSet>, BocError> {
+        self.param(32)?.map(ValidatorSet::parse).transpose()
+    }
+
+    /// Current validator set, param 34
+    pub fn validators(&self) -> Result<Option<ValidatorSet>, BocError> {
+        self.param(34)?.map(ValidatorSet::parse).transpose()
+    }
+
+    /// Next validator set, param 36, which is present only during validator set change
+    pub fn next_validators(&self) -> Result<Option<ValidatorSet>, BocError> {
+        self.param(36)?.map(ValidatorSet::parse).transpose()
+    }
+}
@@ -0,0 +1,103 @@
+use crate::boc::{BocError, Cell, CellSlice};
+
+const GAS_PRICES_TAG: u64 = 0xdd;
+const GAS_PRICES_EXT_TAG: u64 = 0xde;
+const GAS_FLAT_PFX_TAG: u64 = 0xd1;
+const MSG_FORWARD_PRICES_TAG: u64 = 0xea;
+
+/// Gas limits and prices, as in config params 20 and 21. Prices are in nanotons per 2^16 gas
+/// units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasPrices {
+    /// Gas which is paid by `flat_gas_price` as a whole
+    pub flat_gas_limit: u64,
+    pub flat_gas_price: u64,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    /// Gas limit of special accounts, same as `gas_limit` in configs without it
+    pub special_gas_limit: u64,
+    pub gas_credit: u64,
+    pub block_gas_limit: u64,
+    pub freeze_due_limit: u64,
+    pub delete_due_limit: u64,
+}
+
+impl GasPrices {
+    /// Parse `GasLimitsPrices` from config param `cell`
+    pub fn parse(cell: &Cell) -> Result<Self, BocError> {
+        let mut slice = CellSlice::new(cell)?;
+        let mut tag = slice.load_uint(8)?;
+        let (flat_gas_limit, flat_gas_price) = match tag {
+            GAS_FLAT_PFX_TAG => {
+                let flat = (slice.load_uint(64)?, slice.load_uint(64)?);
+                tag = slice.load_uint(8)?;
+                flat
+            }
+            _ => (0, 0),
+        };
+        let gas_price = slice.load_uint(64)?;
+        let gas_limit = slice.load_uint(64)?;
+        let special_gas_limit = match tag {
+            GAS_PRICES_TAG => gas_limit,
+            GAS_PRICES_EXT_TAG => slice.load_uint(64)?,
+            _ => return Err(BocError::InvalidCell("invalid gas prices tag")),
+        };
+        Ok(Self {
+            flat_gas_limit,
+            flat_gas_price,
+            gas_price,
+            gas_limit,
+            special_gas_limit,
+            gas_credit: slice.load_uint(64)?,
+            block_gas_limit: slice.load_uint(64)?,
+            freeze_due_limit: slice.load_uint(64)?,
+            delete_due_limit: slice.load_uint(64)?,
+        })
+    }
+
+    /// Fee in nanotons for `gas_used` units of gas
+    pub fn compute_gas_fee(&self, gas_used: u64) -> u128 {
+        let flat_price = self.flat_gas_price as u128;
+        match gas_used.checked_sub(self.flat_gas_limit) {
+            None | Some(0) => flat_price,
+            Some(gas) => flat_price + (self.gas_price as u128 * gas as u128).div_ceil(1 << 16),
+        }
+    }
+}
+
+/// Prices of message forwarding, as in config params 24 and 25. Prices of bits and cells are in
+/// nanotons per 2^16 units, fractions are per 2^16 of the fee.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsgForwardPrices {
+    pub lump_price: u64,
+    pub bit_price: u64,
+    pub cell_price: u64,
+    pub ihr_price_factor: u32,
+    pub first_frac: u16,
+    pub next_frac: u16,
+}
+
+impl MsgForwardPrices {
+    /// Parse `MsgForwardPrices` from config param `cell`
+    pub fn parse(cell: &Cell) -> Result<Self, BocError> {
+        let mut slice = CellSlice::new(cell)?;
+        if slice.load_uint(8)? != MSG_FORWARD_PRICES_TAG {
+            return Err(BocError::InvalidCell("invalid message forward prices tag"));
+        }
+        Ok(Self {
+            lump_price: slice.load_uint(64)?,
+            bit_price: slice.load_uint(64)?,
+            cell_price: slice.load_uint(64)?,
+            ihr_price_factor: slice.load_uint(32)? as u32,
+            first_frac: slice.load_uint(16)? as u16,
+            next_frac: slice.load_uint(16)? as u16,
+        })
+    }
+
+    /// Forwarding fee in nanotons for message of `cells` cells and `bits` bits, not counting
+    /// its root cell
+    pub fn compute_fwd_fee(&self, cells: u64, bits: u64) -> u128 {
+        let price = self.bit_price as u128 * bits as u128 + self.cell_price as u128 * cells as u128;
+        self.lump_price as u128 + price.div_ceil(1 << 16)
+    }
+}
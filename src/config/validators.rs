@@ -0,0 +1,98 @@
+use alloc::vec::Vec;
+
+use crate::boc::{dict_get, BocError, Cell, CellSlice};
+
+const VALIDATORS_TAG: u64 = 0x11;
+const VALIDATORS_EXT_TAG: u64 = 0x12;
+const VALIDATOR_TAG: u64 = 0x53;
+const VALIDATOR_ADDR_TAG: u64 = 0x73;
+const ED25519_PUBKEY_TAG: u64 = 0x8e81278a;
+
+/// Validator in [`ValidatorSet`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorDescr {
+    pub public_key: [u8; 32],
+    pub weight: u64,
+    pub adnl_addr: Option<[u8; 32]>,
+}
+
+impl ValidatorDescr {
+    fn load(slice: &mut CellSlice) -> Result<Self, BocError> {
+        let tag = slice.load_uint(8)?;
+        if !matches!(tag, VALIDATOR_TAG | VALIDATOR_ADDR_TAG)
+            || slice.load_uint(32)? != ED25519_PUBKEY_TAG
+        {
+            return Err(BocError::InvalidCell("invalid validator description"));
+        }
+        Ok(Self {
+            public_key: slice.load_u256()?,
+            weight: slice.load_uint(64)?,
+            adnl_addr: match tag {
+                VALIDATOR_ADDR_TAG => Some(slice.load_u256()?),
+                _ => None,
+            },
+        })
+    }
+}
+
+/// Set of validators, as in config params 32 to 37
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorSet {
+    pub utime_since: u32,
+    pub utime_until: u32,
+    /// Number of masterchain validators, which are the first ones in the list
+    pub main: u16,
+    pub total_weight: u64,
+    pub list: Vec<ValidatorDescr>,
+}
+
+impl ValidatorSet {
+    /// Parse `ValidatorSet` from config param `cell`
+    pub fn parse(cell: &Cell) -> Result<Self, BocError> {
+        let mut slice = CellSlice::new(cell)?;
+        let tag = slice.load_uint(8)?;
+        let utime_since = slice.load_uint(32)? as u32;
+        let utime_until = slice.load_uint(32)? as u32;
+        let total = slice.load_uint(16)? as u16;
+        let main = slice.load_uint(16)? as u16;
+        if main == 0 || main > total {
+            return Err(BocError::InvalidCell("invalid number of validators"));
+        }
+        let (list, total_weight) = match tag {
+            // list is stored inline as `Hashmap 16`
+            VALIDATORS_TAG => (Some(slice), None),
+            VALIDATORS_EXT_TAG => {
+                let total_weight = slice.load_uint(64)?;
+                let list = match slice.load_bit()? {
+                    true => Some(CellSlice::new(slice.load_reference()?)?),
+                    false => None,
+                };
+                (list, Some(total_weight))
+            }
+            _ => return Err(BocError::InvalidCell("invalid validator set tag")),
+        };
+        let list = (0..total)
+            .map(|i| {
+                let root = list
+                    .clone()
+                    .ok_or(BocError::InvalidCell("missing validator"))?;
+                let mut value = dict_get(root, &i.to_be_bytes(), 16)?
+                    .ok_or(BocError::InvalidCell("missing validator"))?;
+                ValidatorDescr::load(&mut value)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            utime_since,
+            utime_until,
+            main,
+            total_weight: total_weight.unwrap_or_else(|| list.iter().map(|v| v.weight).sum()),
+            list,
+        })
+    }
+
+    /// Validators of masterchain blocks. Masterchain validators of a session are these ones
+    /// in shuffled order.
+    pub fn main_validators(&self) -> &[ValidatorDescr] {
+        &self.list[..self.main as usize]
+    }
+}
@@ -0,0 +1,70 @@
+use alloc::vec::Vec;
+
+use crate::boc::{dict_entries, BocError, Cell, CellSlice};
+
+const WORKCHAIN_TAG: u64 = 0xa6;
+const WORKCHAIN_V2_TAG: u64 = 0xa7;
+
+/// Description of workchain, as in config param 12
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkchainDescr {
+    pub workchain: i32,
+    pub enabled_since: u32,
+    pub actual_min_split: u8,
+    pub min_split: u8,
+    pub max_split: u8,
+    pub basic: bool,
+    pub active: bool,
+    pub accept_msgs: bool,
+    pub zerostate_root_hash: [u8; 32],
+    pub zerostate_file_hash: [u8; 32],
+    pub version: u32,
+}
+
+impl WorkchainDescr {
+    /// Parse all workchains from config param `cell` (`HashmapE 32 WorkchainDescr`)
+    pub fn parse_all(cell: &Cell) -> Result<Vec<Self>, BocError> {
+        let mut param = CellSlice::new(cell)?;
+        if !param.load_bit()? {
+            return Ok(Vec::new());
+        }
+        let mut workchains = dict_entries(CellSlice::new(param.load_reference()?)?, 32)?
+            .into_iter()
+            .map(|(key, mut value)| {
+                Self::load(i32::from_be_bytes(key.try_into().unwrap()), &mut value)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // keys are ordered as unsigned
+        workchains.sort_by_key(|workchain| workchain.workchain);
+        Ok(workchains)
+    }
+
+    fn load(workchain: i32, slice: &mut CellSlice) -> Result<Self, BocError> {
+        // workchain_v2 adds split and merge timings after the fields of workchain
+        if !matches!(slice.load_uint(8)?, WORKCHAIN_TAG | WORKCHAIN_V2_TAG) {
+            return Err(BocError::InvalidCell("invalid workchain description tag"));
+        }
+        let enabled_since = slice.load_uint(32)? as u32;
+        let actual_min_split = slice.load_uint(8)? as u8;
+        let min_split = slice.load_uint(8)? as u8;
+        let max_split = slice.load_uint(8)? as u8;
+        let basic = slice.load_bit()?;
+        let active = slice.load_bit()?;
+        let accept_msgs = slice.load_bit()?;
+        // flags
+        slice.skip_bits(13)?;
+        Ok(Self {
+            workchain,
+            enabled_since,
+            actual_min_split,
+            min_split,
+            max_split,
+            basic,
+            active,
+            accept_msgs,
+            zerostate_root_hash: slice.load_u256()?,
+            zerostate_file_hash: slice.load_u256()?,
+            version: slice.load_uint(32)? as u32,
+        })
+    }
+}
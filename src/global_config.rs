@@ -0,0 +1,355 @@
+//! Loader of TON global config, `global.config.json`, which lists liteservers, DHT nodes and
+//! trusted blocks of the network.
+#![cfg_attr(
+    feature = "tokio",
+    doc = r#"
+```no_run
+# async fn run() -> Result<(), Box<dyn std::error::Error>> {
+use adnl::global_config::GlobalConfig;
+use adnl::LiteClient;
+
+let config = GlobalConfig::load("global.config.json")?;
+let mut client = LiteClient::new(config.connect_any().await?);
+let last = client.sync(config.validator.init_block.clone()).await?;
+# Ok(())
+# }
+```
+"#
+)]
+
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::path::Path;
+
+use base64::Engine as _;
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+use crate::crypto::PublicKey;
+use crate::tl::ton::{adnl, dht, r#pub, ton_node::BlockIdExt};
+use crate::tl::{self, Boxed};
+use crate::AdnlPublicKey;
+#[cfg(feature = "tokio")]
+use crate::{AdnlError, AdnlPeer, LiteBalancer};
+#[cfg(feature = "tokio")]
+use tokio::net::TcpStream;
+
+/// Error of global config loading or of connection to its liteservers
+#[derive(Debug, Error)]
+pub enum GlobalConfigError {
+    #[error("IO error")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid global config JSON")]
+    JsonError(#[from] serde_json::Error),
+    #[error("No liteservers in global config")]
+    NoLiteServers,
+    #[cfg(feature = "tokio")]
+    #[error("ADNL error")]
+    AdnlError(#[from] AdnlError),
+}
+
+/// Liteserver from `liteservers` list
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LiteServerConfig {
+    /// Stored in config as signed integer
+    #[serde(deserialize_with = "deserialize_ip")]
+    pub ip: Ipv4Addr,
+    pub port: u16,
+    pub id: AdnlPublicKey,
+}
+
+impl LiteServerConfig {
+    pub fn addr(&self) -> SocketAddrV4 {
+        SocketAddrV4::new(self.ip, self.port)
+    }
+
+    /// Connect to the liteserver, use [`crate::LiteClient::new`] to send typed queries to it
+    #[cfg(feature = "tokio")]
+    pub async fn connect(&self) -> Result<AdnlPeer<TcpStream>, AdnlError> {
+        AdnlPeer::connect(self.id, self.addr()).await
+    }
+}
+
+/// Node from `dht.static_nodes`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DhtNode {
+    pub id: AdnlPublicKey,
+    /// Address list exactly as it is signed by the node
+    pub addr_list: adnl::AddressList,
+    pub version: i32,
+    pub signature: Vec<u8>,
+}
+
+impl DhtNode {
+    /// UDP addresses of the node, addresses of other types are skipped
+    pub fn udp_addrs(&self) -> impl Iterator<Item = SocketAddrV4> + '_ {
+        self.addr_list.addrs.iter().filter_map(|addr| match addr {
+            adnl::Address::AddressUdp(addr) => Some(SocketAddrV4::new(
+                Ipv4Addr::from(addr.ip as u32),
+                addr.port as u16,
+            )),
+            _ => None,
+        })
+    }
+
+    /// Check signature of the node by its own key over `dht.node` with empty signature
+    pub fn check_signature(&self) -> bool {
+        let unsigned = Boxed(dht::Node {
+            id: tl::ton::PublicKey::PubEd25519(r#pub::Ed25519 {
+                key: *self.id.as_bytes(),
+            }),
+            addr_list: self.addr_list.clone(),
+            version: self.version,
+            signature: Vec::new(),
+        });
+        <&[u8; 64]>::try_from(self.signature.as_slice())
+            .ok()
+            .zip(PublicKey::from_bytes(*self.id.as_bytes()))
+            .is_some_and(|(signature, key)| key.verify_raw(&tl::serialize(&unsigned), signature))
+    }
+}
+
+/// `dht` section
+#[derive(Debug, Clone, PartialEq)]
+pub struct DhtConfig {
+    pub k: u32,
+    pub a: u32,
+    pub static_nodes: Vec<DhtNode>,
+}
+
+/// `validator` section with trusted masterchain blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorConfig {
+    pub zero_state: BlockIdExt,
+    /// Block to start sync from, such as with [`crate::LiteClient::sync`]. Same as
+    /// `zero_state` if the config has none.
+    pub init_block: BlockIdExt,
+    pub hardforks: Vec<BlockIdExt>,
+}
+
+/// Parsed `global.config.json`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalConfig {
+    pub liteservers: Vec<LiteServerConfig>,
+    pub dht: DhtConfig,
+    pub validator: ValidatorConfig,
+}
+
+impl GlobalConfig {
+    /// Parse global config from `json`
+    pub fn from_json(json: &str) -> Result<Self, GlobalConfigError> {
+        let raw: RawGlobalConfig = serde_json::from_str(json)?;
+        let static_nodes = raw
+            .dht
+            .static_nodes
+            .nodes
+            .into_iter()
+            .map(|node| DhtNode {
+                id: node.id,
+                addr_list: node.addr_list.into(),
+                version: node.version,
+                signature: node.signature,
+            })
+            .collect();
+        let validator = raw.validator;
+        Ok(Self {
+            liteservers: raw.liteservers,
+            dht: DhtConfig {
+                k: raw.dht.k,
+                a: raw.dht.a,
+                static_nodes,
+            },
+            validator: ValidatorConfig {
+                init_block: validator
+                    .init_block
+                    .unwrap_or_else(|| validator.zero_state.clone())
+                    .into(),
+                zero_state: validator.zero_state.into(),
+                hardforks: validator.hardforks.into_iter().map(Into::into).collect(),
+            },
+        })
+    }
+
+    /// Read and parse global config file at `path`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GlobalConfigError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Connect to liteservers in random order until a connection succeeds, returns the error
+    /// of the last one if all fail
+    #[cfg(feature = "tokio")]
+    pub async fn connect_any(&self) -> Result<AdnlPeer<TcpStream>, GlobalConfigError> {
+        use rand::seq::SliceRandom;
+
+        let mut liteservers = self.liteservers.iter().collect::<Vec<_>>();
+        liteservers.shuffle(&mut rand::thread_rng());
+        let mut error = GlobalConfigError::NoLiteServers;
+        for liteserver in liteservers {
+            match liteserver.connect().await {
+                Ok(peer) => return Ok(peer),
+                Err(e) => {
+                    log::debug!("failed to connect to liteserver {}: {e}", liteserver.addr());
+                    error = e.into();
+                }
+            }
+        }
+        Err(error)
+    }
+
+    /// Connect to all liteservers concurrently, results are in the order of `liteservers`
+    #[cfg(feature = "tokio")]
+    pub async fn connect_all(&self) -> Vec<Result<AdnlPeer<TcpStream>, AdnlError>> {
+        futures::future::join_all(self.liteservers.iter().map(LiteServerConfig::connect)).await
+    }
+
+    /// Connect to all liteservers with a [`LiteBalancer`] over them
+    #[cfg(feature = "tokio")]
+    pub async fn connect_balancer(&self) -> LiteBalancer {
+        LiteBalancer::connect(
+            self.liteservers
+                .iter()
+                .map(|liteserver| (liteserver.id, liteserver.addr().into())),
+        )
+        .await
+    }
+}
+
+fn deserialize_ip<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ipv4Addr, D::Error> {
+    Ok(Ipv4Addr::from(i32::deserialize(deserializer)? as u32))
+}
+
+fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    base64::engine::general_purpose::STANDARD
+        .decode(String::deserialize(deserializer)?)
+        .map_err(serde::de::Error::custom)
+}
+
+fn deserialize_base64_array<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error> {
+    deserialize_base64(deserializer)?
+        .try_into()
+        .map_err(|_| serde::de::Error::custom(format_args!("value must be {N} bytes")))
+}
+
+#[derive(Deserialize)]
+struct RawGlobalConfig {
+    #[serde(default)]
+    liteservers: Vec<LiteServerConfig>,
+    dht: RawDhtConfig,
+    validator: RawValidatorConfig,
+}
+
+#[derive(Deserialize)]
+struct RawDhtConfig {
+    k: u32,
+    a: u32,
+    static_nodes: RawDhtNodes,
+}
+
+#[derive(Deserialize)]
+struct RawDhtNodes {
+    nodes: Vec<RawDhtNode>,
+}
+
+#[derive(Deserialize)]
+struct RawDhtNode {
+    id: AdnlPublicKey,
+    addr_list: RawAddressList,
+    version: i32,
+    #[serde(deserialize_with = "deserialize_base64")]
+    signature: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct RawAddressList {
+    addrs: Vec<RawAddress>,
+    version: i32,
+    reinit_date: i32,
+    priority: i32,
+    expire_at: i32,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "@type")]
+enum RawAddress {
+    #[serde(rename = "adnl.address.udp")]
+    Udp { ip: i32, port: i32 },
+    #[serde(rename = "adnl.address.udp6")]
+    Udp6 {
+        #[serde(deserialize_with = "deserialize_base64_array")]
+        ip: [u8; 16],
+        port: i32,
+    },
+    #[serde(rename = "adnl.address.tunnel")]
+    Tunnel {
+        #[serde(deserialize_with = "deserialize_base64_array")]
+        to: [u8; 32],
+        pubkey: AdnlPublicKey,
+    },
+    #[serde(rename = "adnl.address.reverse")]
+    Reverse {},
+}
+
+impl From<RawAddressList> for adnl::AddressList {
+    fn from(value: RawAddressList) -> Self {
+        let addrs = value
+            .addrs
+            .into_iter()
+            .map(|addr| match addr {
+                RawAddress::Udp { ip, port } => {
+                    adnl::Address::AddressUdp(adnl::address::Udp { ip, port })
+                }
+                RawAddress::Udp6 { ip, port } => {
+                    adnl::Address::AddressUdp6(adnl::address::Udp6 { ip, port })
+                }
+                RawAddress::Tunnel { to, pubkey } => {
+                    adnl::Address::AddressTunnel(adnl::address::Tunnel {
+                        to,
+                        pubkey: tl::ton::PublicKey::PubEd25519(r#pub::Ed25519 {
+                            key: *pubkey.as_bytes(),
+                        }),
+                    })
+                }
+                RawAddress::Reverse {} => adnl::Address::AddressReverse(adnl::address::Reverse),
+            })
+            .collect();
+        Self {
+            addrs,
+            version: value.version,
+            reinit_date: value.reinit_date,
+            priority: value.priority,
+            expire_at: value.expire_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawValidatorConfig {
+    zero_state: RawBlockId,
+    init_block: Option<RawBlockId>,
+    #[serde(default)]
+    hardforks: Vec<RawBlockId>,
+}
+
+#[derive(Clone, Deserialize)]
+struct RawBlockId {
+    workchain: i32,
+    shard: i64,
+    seqno: i32,
+    #[serde(deserialize_with = "deserialize_base64_array")]
+    root_hash: [u8; 32],
+    #[serde(deserialize_with = "deserialize_base64_array")]
+    file_hash: [u8; 32],
+}
+
+impl From<RawBlockId> for BlockIdExt {
+    fn from(value: RawBlockId) -> Self {
+        Self {
+            workchain: value.workchain,
+            shard: value.shard,
+            seqno: value.seqno,
+            root_hash: value.root_hash,
+            file_hash: value.file_hash,
+        }
+    }
+}
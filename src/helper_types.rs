@@ -1,18 +1,29 @@
+use crate::boc::BocError;
+#[cfg(feature = "std")]
 use crate::crypto::PublicKey;
+use crate::proof::ProofError;
+use crate::tl::ton::lite_server;
+use crate::tl::{self, Boxed, TlError};
+use crate::tvm::StackError;
+use alloc::{format, string::String};
+use core::array::TryFromSliceError;
+use crc::{Crc, CRC_16_XMODEM};
 use sha2::{Digest, Sha256};
-use std::{array::TryFromSliceError, io::Error};
 use thiserror::Error;
 
 pub trait CryptoRandom: rand_core::RngCore + rand_core::CryptoRng {}
 
 impl<T> CryptoRandom for T where T: rand_core::RngCore + rand_core::CryptoRng {}
 
+/// CRC16 of get-method names and of user-friendly addresses
+pub(crate) static CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_XMODEM);
+
 /// Wrapper struct to hold ADNL address, which is a hash of public key
 #[derive(PartialEq, Clone)]
 pub struct AdnlAddress([u8; 32]);
 
-impl std::fmt::Debug for AdnlAddress {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for AdnlAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("AdnlAddress")
             .field(&format!("{:02x?}", &self.0))
             .finish()
@@ -33,12 +44,10 @@ impl TryFrom<&[u8]> for AdnlAddress {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&PublicKey> for AdnlAddress {
     fn from(value: &PublicKey) -> Self {
-        let mut hasher = Sha256::new();
-        hasher.update([0xc6, 0xb4, 0x13, 0x48]); // type id - always ed25519
-        hasher.update(value.as_bytes());
-        AdnlAddress(hasher.finalize().into())
+        Self::from_ed25519(value.as_bytes())
     }
 }
 
@@ -113,6 +122,14 @@ impl Default for AdnlAesParams {
 }
 
 impl AdnlAddress {
+    /// Compute address of raw ed25519 public key
+    pub fn from_ed25519(public_key: &[u8; 32]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update([0xc6, 0xb4, 0x13, 0x48]); // type id - always ed25519
+        hasher.update(public_key);
+        AdnlAddress(hasher.finalize().into())
+    }
+
     #[inline]
     pub fn to_bytes(&self) -> [u8; 32] {
         self.0
@@ -127,20 +144,96 @@ impl AdnlAddress {
 /// Common error type
 #[derive(Debug, Error)]
 pub enum AdnlError {
+    #[cfg(feature = "std")]
     #[error("IO error")]
-    IoError(#[from] Error),
+    IoError(#[from] std::io::Error),
     #[error("Integrity error")]
     IntegrityError,
     #[error("Too short packet (32 bytes min)")]
     TooShortPacket,
     #[error("Too long packet (4 MiB max)")]
     TooLongPacket,
+    #[error("Output buffer length does not match packet length")]
+    InvalidBufferLength,
     #[error("Receiver ADNL address mismatch")]
     UnknownAddr(AdnlAddress),
     #[error("End of stream")]
     EndOfStream,
     #[error("Invalid public key")]
     InvalidPublicKey,
+    #[error("Rate limit exceeded")]
+    RateLimited,
+    #[error("Proxy error: {0}")]
+    ProxyError(String),
+}
+
+/// Error of liteserver query
+#[derive(Debug, Error)]
+pub enum LiteError {
+    #[error("ADNL error")]
+    AdnlError(#[from] AdnlError),
+    #[error("TL error")]
+    TlError(#[from] TlError),
+    #[error("Query timed out")]
+    Timeout,
+    #[error("Liteserver error")]
+    ServerError(#[from] LiteServerError),
+    #[error("Message rejected by liteserver with status {0}")]
+    MessageRejected(i32),
+    #[error("Message expired before it was processed")]
+    MessageExpired,
+    #[error("Proof check failed")]
+    ProofError(#[from] ProofError),
+    #[error("Invalid BoC")]
+    BocError(#[from] BocError),
+    #[error("Invalid TVM stack")]
+    StackError(#[from] StackError),
+    #[error("Get-method failed with exit code {0}")]
+    GetMethodFailed(i32),
+    #[error("No healthy up-to-date liteserver available")]
+    NoAvailableServers,
+}
+
+impl LiteError {
+    /// Whether the same query may succeed later, see [`LiteServerError::is_retryable`]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Timeout => true,
+            Self::ServerError(e) => e.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
+/// `liteServer.error` answer to liteserver query
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Liteserver error {code}: {message}")]
+pub struct LiteServerError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl LiteServerError {
+    pub const FAILURE: i32 = 601;
+    pub const ERROR: i32 = 602;
+    pub const WARNING: i32 = 603;
+    pub const PROTOVIOLATION: i32 = 621;
+    /// Server does not have requested data yet, e.g. block is not applied
+    pub const NOT_READY: i32 = 651;
+    pub const TIMEOUT: i32 = 652;
+    pub const CANCELLED: i32 = 653;
+
+    /// Decode serialized answer to liteserver query if it is `liteServer.error`
+    pub fn from_answer(answer: &[u8]) -> Option<Self> {
+        let Boxed(lite_server::Error { code, message }) = tl::deserialize(answer).ok()?;
+        Some(Self { code, message })
+    }
+
+    /// Whether the query failed because the server was not ready or busy, so it may succeed
+    /// if repeated later, unlike e.g. invalid query
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.code, Self::NOT_READY | Self::TIMEOUT | Self::CANCELLED)
+    }
 }
 
 /// Information about connected peers.
@@ -3,55 +3,107 @@
 //! This crate provides a minimal implementation of the Abstract Datagram Network Layer (ADNL)
 //! protocol in Rust. ADNL is a network protocol used in The Open Network (TON) blockchain.
 //!
-//! ## Client example
-//!
-//! ```rust,no_run
-//! use adnl::AdnlPeer;
-//! use base64::Engine as _;
-//! use futures::{SinkExt, StreamExt};
-//! use std::error::Error;
-//!
-//! #[tokio::main]
-//! async fn main() -> Result<(), Box<dyn Error>> {
-//!     // decode liteserver public key
-//!     let remote_public = base64::engine::general_purpose::STANDARD.decode("n4VDnSCUuSpjnCyUk9e3QOOd6o0ItSWYbTnW3Wnn8wk=")?;
-//!
-//!     // act as a client: connect to ADNL server and perform handshake
-//!     let mut client = AdnlPeer::connect(remote_public, "5.9.10.47:19949").await?;
-//!
-//!     // already serialized TL with getTime query
-//!     let query = hex::decode("7af98bb435263e6c95d6fecb497dfd0aa5f031e7d412986b5ce720496db512052e8f2d100cdf068c7904345aad16000000000000")?;
-//!
-//!     // send over ADNL
-//!     client.send(query.into()).await?;
-//!
-//!     // receive result
-//!     let result = client.next().await.ok_or_else(|| "no result")??;
-//!
-//!     // get time from serialized TL answer
-//!     println!(
-//!         "received: {}",
-//!         u32::from_le_bytes(result[result.len() - 7..result.len() - 3].try_into()?)
-//!     );
-//!     Ok(())
-//! }
-//! ```
+#![cfg_attr(
+    feature = "tokio",
+    doc = r#"
+## Client example
+
+```rust,no_run
+use adnl::AdnlPeer;
+use base64::Engine as _;
+use futures::{SinkExt, StreamExt};
+use std::error::Error;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    // decode liteserver public key
+    let remote_public = base64::engine::general_purpose::STANDARD.decode("n4VDnSCUuSpjnCyUk9e3QOOd6o0ItSWYbTnW3Wnn8wk=")?;
+
+    // act as a client: connect to ADNL server and perform handshake
+    let mut client = AdnlPeer::connect(remote_public, "5.9.10.47:19949").await?;
+
+    // already serialized TL with getTime query
+    let query = hex::decode("7af98bb435263e6c95d6fecb497dfd0aa5f031e7d412986b5ce720496db512052e8f2d100cdf068c7904345aad16000000000000")?;
+
+    // send over ADNL
+    client.send(query.into()).await?;
+
+    // receive result
+    let result = client.next().await.ok_or_else(|| "no result")??;
+
+    // get time from serialized TL answer
+    println!(
+        "received: {}",
+        u32::from_le_bytes(result[result.len() - 7..result.len() - 3].try_into()?)
+    );
+    Ok(())
+}
+```
+"#
+)]
 //!
 //! See the `examples/` directory for more usage examples.
 
-pub use helper_types::{AdnlAddress, AdnlAesParams, AdnlConnectionInfo, AdnlError};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+// allow derive macros to refer to `::adnl` from inside the crate
+extern crate self as adnl;
+
+pub use helper_types::{
+    AdnlAddress, AdnlAesParams, AdnlConnectionInfo, AdnlError, LiteError, LiteServerError,
+};
 pub use primitives::codec::AdnlCodec;
+#[cfg(feature = "std")]
 pub use primitives::handshake::AdnlHandshake;
+pub use primitives::text::{AdnlParseError, AdnlPublicKey};
+#[cfg(feature = "tokio")]
+pub use wrappers::balancer::{CircuitState, LiteBalancer, LiteServerStats};
+#[cfg(feature = "blocking")]
+pub use wrappers::blocking::AdnlBlockingClient;
+#[cfg(feature = "std")]
 pub use wrappers::builder::AdnlBuilder;
+#[cfg(feature = "futures-io")]
+pub use wrappers::futures_peer::AdnlFuturesPeer;
+#[cfg(feature = "tokio")]
+pub use wrappers::limiter::{AdnlRateLimiter, AdnlRateLimits, RateLimitAction, RateLimitScope};
+#[cfg(feature = "tokio")]
+pub use wrappers::lite_client::{smc_method_id, BlockLookup, LiteClient, LiteQueryPrefix};
+#[cfg(feature = "tokio")]
 pub use wrappers::peer::AdnlPeer;
+#[cfg(feature = "tokio")]
+pub use wrappers::proxy::AdnlProxy;
+#[cfg(feature = "tokio")]
+pub use wrappers::queue::{AdnlPriority, AdnlQueueStats};
+#[cfg(feature = "websocket")]
+pub use wrappers::websocket::{AdnlWsBridge, AdnlWsStream};
 
+#[cfg(feature = "std")]
 pub mod crypto {
     pub use everscale_crypto::ed25519::*;
 }
 
+/// Runtime-independent core of the protocol, available without `std`: handshake
+/// sealing and opening, and frame encryption with [`AdnlCodec`]. Key agreement is
+/// left to the caller.
+pub mod raw {
+    pub use crate::helper_types::CryptoRandom;
+    pub use crate::primitives::handshake::{open_handshake, seal_handshake};
+    pub use crate::{AdnlAddress, AdnlAesParams, AdnlCodec, AdnlError};
+}
+
+pub mod address;
+pub mod boc;
+pub mod config;
+#[cfg(feature = "global-config")]
+pub mod global_config;
+pub mod proof;
+pub mod tl;
+pub mod tvm;
+
 mod helper_types;
 mod primitives;
 mod wrappers;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests;
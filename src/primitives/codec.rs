@@ -1,5 +1,6 @@
 use aes::cipher::{KeyIvInit, StreamCipher};
 use sha2::{Digest, Sha256};
+#[cfg(feature = "tokio")]
 use tokio_util::{
     bytes::{Buf, Bytes, BytesMut},
     codec::{Decoder, Encoder},
@@ -9,11 +10,15 @@ use crate::{AdnlAesParams, AdnlError};
 
 use super::AdnlAes;
 
+const MAX_LENGTH: usize = 1 << 24;
+
 /// Implementation of ADNL protocol. Connection must be first initialized with [`AdnlHandshake`] to exchange keys.
 pub struct AdnlCodec {
     aes_rx: AdnlAes,
     aes_tx: AdnlAes,
+    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
     last_readed_length: Option<usize>,
+    max_length: usize,
 }
 
 impl AdnlCodec {
@@ -22,6 +27,7 @@ impl AdnlCodec {
             aes_rx: AdnlAes::new(aes_params.rx_key().into(), aes_params.rx_nonce().into()),
             aes_tx: AdnlAes::new(aes_params.tx_key().into(), aes_params.tx_nonce().into()),
             last_readed_length: None,
+            max_length: MAX_LENGTH,
         }
     }
 
@@ -30,10 +36,88 @@ impl AdnlCodec {
             aes_rx: AdnlAes::new(aes_params.tx_key().into(), aes_params.tx_nonce().into()),
             aes_tx: AdnlAes::new(aes_params.rx_key().into(), aes_params.rx_nonce().into()),
             last_readed_length: None,
+            max_length: MAX_LENGTH,
+        }
+    }
+
+    /// Reject incoming packets longer than `max_length` bytes, protocol limit still applies
+    pub fn set_max_length(&mut self, max_length: usize) {
+        self.max_length = max_length.min(MAX_LENGTH);
+    }
+
+    /// Decrypt 4-byte length prefix of incoming packet. Returned number of bytes
+    /// must be then read and passed to [`AdnlCodec::decrypt_packet`].
+    pub fn decrypt_length(&mut self, mut header: [u8; 4]) -> Result<usize, AdnlError> {
+        self.aes_rx.apply_keystream(&mut header);
+        let length = u32::from_le_bytes(header) as usize;
+        if length < 64 {
+            return Err(AdnlError::TooShortPacket);
         }
+        if length > self.max_length {
+            return Err(AdnlError::TooLongPacket);
+        }
+        Ok(length)
+    }
+
+    /// Decrypt incoming packet in place and check its integrity, returns payload
+    pub fn decrypt_packet<'a>(&mut self, packet: &'a mut [u8]) -> Result<&'a [u8], AdnlError> {
+        let length = packet.len();
+        if length < 64 {
+            return Err(AdnlError::TooShortPacket);
+        }
+        self.aes_rx.apply_keystream(packet);
+        let given_hash = &packet[length - 32..];
+
+        // integrity check
+        let mut hasher = Sha256::new();
+        hasher.update(&packet[..length - 32]);
+        if given_hash != hasher.finalize().as_slice() {
+            return Err(AdnlError::IntegrityError);
+        }
+        Ok(&packet[32..length - 32])
+    }
+
+    /// Length of the packet carrying `payload_length` bytes, including length prefix
+    pub fn packet_length(payload_length: usize) -> usize {
+        payload_length + 68
+    }
+
+    /// Encrypt `buffer` into `dst`, which must be exactly [`AdnlCodec::packet_length`] bytes long
+    #[cfg(feature = "std")]
+    pub fn encrypt_packet(&mut self, buffer: &[u8], dst: &mut [u8]) -> Result<(), AdnlError> {
+        self.encrypt_packet_with_nonce(buffer, &rand::random(), dst)
+    }
+
+    /// Encrypt `buffer` into `dst` using given random `nonce`, `dst` must be exactly
+    /// [`AdnlCodec::packet_length`] bytes long, otherwise [`AdnlError::InvalidBufferLength`]
+    /// is returned
+    pub fn encrypt_packet_with_nonce(
+        &mut self,
+        buffer: &[u8],
+        nonce: &[u8; 32],
+        dst: &mut [u8],
+    ) -> Result<(), AdnlError> {
+        if buffer.len() > (MAX_LENGTH - 64) {
+            return Err(AdnlError::TooLongPacket);
+        }
+        if dst.len() != Self::packet_length(buffer.len()) {
+            return Err(AdnlError::InvalidBufferLength);
+        }
+        let length = ((buffer.len() + 64) as u32).to_le_bytes();
+        let mut hash = Sha256::new();
+        hash.update(nonce);
+        hash.update(buffer);
+        let hash = hash.finalize();
+        dst[..4].copy_from_slice(&length);
+        dst[4..36].copy_from_slice(nonce);
+        dst[36..36 + buffer.len()].copy_from_slice(buffer);
+        dst[36 + buffer.len()..].copy_from_slice(&hash);
+        self.aes_tx.apply_keystream(dst);
+        Ok(())
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Decoder for AdnlCodec {
     type Item = Bytes;
 
@@ -46,16 +130,7 @@ impl Decoder for AdnlCodec {
             if src.len() < 4 {
                 return Ok(None);
             }
-            self.aes_rx.apply_keystream(&mut src[..4]);
-            let mut length_bytes = [0u8; 4];
-            length_bytes.copy_from_slice(&src[..4]);
-            let length = u32::from_le_bytes(length_bytes) as usize;
-            if length < 64 {
-                return Err(AdnlError::TooShortPacket);
-            }
-            if length > (1 << 24) {
-                return Err(AdnlError::TooLongPacket);
-            }
+            let length = self.decrypt_length(src[..4].try_into().unwrap())?;
             src.advance(4);
             self.last_readed_length = Some(length);
             length
@@ -71,44 +146,23 @@ impl Decoder for AdnlCodec {
 
         self.last_readed_length = None;
 
-        // decode packet
-        self.aes_rx.apply_keystream(&mut src[..length]);
-        let given_hash = &src[length - 32..length];
-
-        // integrity check
-        let mut hasher = Sha256::new();
-        hasher.update(&src[..length - 32]);
-        if given_hash != hasher.finalize().as_slice() {
-            return Err(AdnlError::IntegrityError);
-        }
-
-        // copy and return buffer
-        let result = Bytes::copy_from_slice(&src[32..length - 32]);
+        // decode packet, copy and return buffer
+        let result = Bytes::copy_from_slice(self.decrypt_packet(&mut src[..length])?);
         src.advance(length);
         Ok(Some(result))
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<Bytes> for AdnlCodec {
     type Error = AdnlError;
 
     fn encode(&mut self, buffer: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        if buffer.len() > ((1 << 24) - 64) {
+        if buffer.len() > (MAX_LENGTH - 64) {
             return Err(AdnlError::TooLongPacket);
         }
-        let length = ((buffer.len() + 64) as u32).to_le_bytes();
-        let nonce = rand::random::<[u8; 32]>();
-        let mut hash = Sha256::new();
-        hash.update(nonce);
-        hash.update(&buffer);
-        let hash = hash.finalize();
-        dst.reserve(buffer.len() + 68);
-        dst.extend_from_slice(&length);
-        dst.extend_from_slice(&nonce);
-        dst.extend_from_slice(&buffer);
-        dst.extend_from_slice(&hash);
-        let start_offset = dst.len() - buffer.len() - 68;
-        self.aes_tx.apply_keystream(&mut dst[start_offset..]);
-        Ok(())
+        let start_offset = dst.len();
+        dst.resize(start_offset + Self::packet_length(buffer.len()), 0);
+        self.encrypt_packet(&buffer, &mut dst[start_offset..])
     }
 }
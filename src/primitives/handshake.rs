@@ -1,14 +1,76 @@
+#[cfg(feature = "std")]
 use crate::crypto::{KeyPair, PublicKey};
 use crate::primitives::AdnlAes;
-use crate::{AdnlAddress, AdnlAesParams, AdnlError, AdnlPeer};
+#[cfg(any(feature = "tokio", feature = "blocking", feature = "futures-io"))]
+use crate::AdnlBuilder;
+#[cfg(feature = "tokio")]
+use crate::AdnlPeer;
+use crate::{AdnlAddress, AdnlAesParams, AdnlError};
 use aes::cipher::KeyIvInit;
 use ctr::cipher::StreamCipher;
 use sha2::{Digest, Sha256};
+#[cfg(feature = "tokio")]
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+#[cfg(feature = "std")]
 use super::codec::AdnlCodec;
 
+/// Serialize and encrypt handshake packet from `sender` (raw ed25519 public key) to `receiver`,
+/// who already agreed on given `secret`
+pub fn seal_handshake(
+    receiver: &AdnlAddress,
+    sender: &[u8; 32],
+    secret: &[u8; 32],
+    aes_params: &AdnlAesParams,
+) -> [u8; 256] {
+    let mut raw_params = aes_params.to_bytes();
+    let hash = sha256(raw_params);
+    let mut aes = initialize_aes(secret, &hash);
+    aes.apply_keystream(&mut raw_params);
+
+    let mut packet = [0u8; 256];
+    packet[..32].copy_from_slice(receiver.as_bytes());
+    packet[32..64].copy_from_slice(sender);
+    packet[64..96].copy_from_slice(&hash);
+    packet[96..256].copy_from_slice(&raw_params);
+    packet
+}
+
+/// Decrypt session parameters of handshake `packet` using `secret` agreed with its sender.
+/// Receiver address and sender public key are stored unencrypted in `packet[..32]` and
+/// `packet[32..64]` respectively.
+pub fn open_handshake(packet: &[u8; 256], secret: &[u8; 32]) -> Result<AdnlAesParams, AdnlError> {
+    let hash: [u8; 32] = packet[64..96].try_into().unwrap();
+    let mut raw_params: [u8; 160] = packet[96..256].try_into().unwrap();
+    let mut aes = initialize_aes(secret, &hash);
+    aes.apply_keystream(&mut raw_params);
+
+    if hash != sha256(raw_params) {
+        return Err(AdnlError::IntegrityError);
+    }
+    Ok(AdnlAesParams::from(raw_params))
+}
+
+fn initialize_aes(secret: &[u8; 32], hash: &[u8]) -> AdnlAes {
+    let mut key = [0u8; 32];
+    key[..16].copy_from_slice(&secret[..16]);
+    key[16..32].copy_from_slice(&hash[16..32]);
+
+    let mut nonce = [0u8; 16];
+    nonce[..4].copy_from_slice(&hash[..4]);
+    nonce[4..16].copy_from_slice(&secret[20..32]);
+
+    AdnlAes::new(key.as_slice().into(), nonce.as_slice().into())
+}
+
+fn sha256(data: impl AsRef<[u8]>) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
 /// Handshake packet, must be sent from client to server prior to any datagrams
+#[cfg(feature = "std")]
 pub struct AdnlHandshake {
     receiver: AdnlAddress,
     sender: PublicKey,
@@ -16,6 +78,7 @@ pub struct AdnlHandshake {
     secret: [u8; 32],
 }
 
+#[cfg(feature = "std")]
 impl AdnlHandshake {
     /// Create handshake with given sender and receiver, who already agreed on given secret, also
     /// use given session parameters
@@ -33,6 +96,20 @@ impl AdnlHandshake {
         }
     }
 
+    /// Build client handshake to `remote_public` from random client keys and random session parameters
+    #[cfg(any(feature = "tokio", feature = "blocking", feature = "futures-io"))]
+    pub(crate) fn random_for(remote_public: impl AsRef<[u8]>) -> Result<Self, AdnlError> {
+        let local_keypair = KeyPair::generate(&mut rand::rngs::OsRng);
+        let remote_public = remote_public
+            .as_ref()
+            .try_into()
+            .ok()
+            .and_then(PublicKey::from_bytes)
+            .ok_or(AdnlError::InvalidPublicKey)?;
+        Ok(AdnlBuilder::with_random_aes_params(&mut rand::rngs::OsRng)
+            .perform_ecdh(&local_keypair, &remote_public))
+    }
+
     /// Get session AES parameters
     pub fn aes_params(&self) -> &AdnlAesParams {
         &self.aes_params
@@ -50,17 +127,12 @@ impl AdnlHandshake {
 
     /// Serialize handshake to send it over the transport
     pub fn to_bytes(&self) -> [u8; 256] {
-        let mut raw_params = self.aes_params.to_bytes();
-        let hash = Self::sha256(raw_params);
-        let mut aes = Self::initialize_aes(&self.secret, &hash);
-        aes.apply_keystream(&mut raw_params);
-
-        let mut packet = [0u8; 256];
-        packet[..32].copy_from_slice(self.receiver.as_bytes());
-        packet[32..64].copy_from_slice(self.sender.as_bytes());
-        packet[64..96].copy_from_slice(&hash);
-        packet[96..256].copy_from_slice(&raw_params);
-        packet
+        seal_handshake(
+            &self.receiver,
+            self.sender.as_bytes(),
+            &self.secret,
+            &self.aes_params,
+        )
     }
 
     pub fn make_client_codec(&self) -> AdnlCodec {
@@ -72,6 +144,7 @@ impl AdnlHandshake {
     }
 
     /// Send handshake over the given transport, build [`AdnlClient`] on top of it
+    #[cfg(feature = "tokio")]
     pub async fn perform_handshake<T: AsyncReadExt + AsyncWriteExt + Unpin>(
         &self,
         transport: T,
@@ -79,24 +152,6 @@ impl AdnlHandshake {
         AdnlPeer::perform_custom_handshake(transport, self).await
     }
 
-    fn initialize_aes(secret: &[u8; 32], hash: &[u8]) -> AdnlAes {
-        let mut key = [0u8; 32];
-        key[..16].copy_from_slice(&secret[..16]);
-        key[16..32].copy_from_slice(&hash[16..32]);
-
-        let mut nonce = [0u8; 16];
-        nonce[..4].copy_from_slice(&hash[..4]);
-        nonce[4..16].copy_from_slice(&secret[20..32]);
-
-        AdnlAes::new(key.as_slice().into(), nonce.as_slice().into())
-    }
-
-    fn sha256(data: impl AsRef<[u8]>) -> [u8; 32] {
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        hasher.finalize().into()
-    }
-
     /// Deserialize and decrypt handshake using keypair from `keypair_selector` function
     pub fn decrypt_from_raw<F: Fn(&AdnlAddress) -> Option<KeyPair>>(
         packet: &[u8; 256],
@@ -104,10 +159,7 @@ impl AdnlHandshake {
     ) -> Result<Self, AdnlError> {
         let receiver = packet[..32].try_into().unwrap();
         let sender = PublicKey::from_bytes(packet[32..64].try_into().unwrap())
-            .ok_or_else(|| AdnlError::InvalidPublicKey)?;
-        let hash: [u8; 32] = packet[64..96].try_into().unwrap();
-        let mut raw_params: [u8; 160] = packet[96..256].try_into().unwrap();
-
+            .ok_or(AdnlError::InvalidPublicKey)?;
         let keypair =
             keypair_selector(&receiver).ok_or_else(|| AdnlError::UnknownAddr(receiver.clone()))?;
 
@@ -122,17 +174,12 @@ impl AdnlHandshake {
         }
 
         let secret = keypair.compute_shared_secret(&sender);
-        let mut aes = Self::initialize_aes(&secret, &hash);
-        aes.apply_keystream(&mut raw_params);
-
-        if hash != Self::sha256(raw_params) {
-            return Err(AdnlError::IntegrityError);
-        }
+        let aes_params = open_handshake(packet, &secret)?;
 
         Ok(Self {
             receiver,
             sender,
-            aes_params: AdnlAesParams::from(raw_params),
+            aes_params,
             secret,
         })
     }
@@ -5,3 +5,4 @@ pub type AdnlAes = Ctr128BE<Aes256>;
 
 pub mod codec;
 pub mod handshake;
+pub mod text;
@@ -0,0 +1,274 @@
+//! Textual forms of ADNL addresses and public keys: hex, base64 and base32 user-friendly
+//! addresses of TON, such as `ozb6uw…` of 55 characters
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use base64::Engine as _;
+use thiserror::Error;
+
+use crate::helper_types::CRC16;
+use crate::AdnlAddress;
+
+/// Prefix byte of user-friendly ADNL address, which makes its base32 form start with `f`
+const BASE32_ADDRESS_TAG: u8 = 0x2d;
+/// Length of user-friendly address with the leading `f` dropped
+const BASE32_ADDRESS_LEN: usize = 55;
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+#[cfg(feature = "serde")]
+const ED25519_KEY_TYPE: &str = "pub.ed25519";
+
+/// Error of parsing address or key from text
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AdnlParseError {
+    #[error("Invalid format, expected hex, base64 or base32 of 32 bytes")]
+    InvalidFormat,
+    #[error("Invalid checksum of user-friendly address")]
+    InvalidChecksum,
+}
+
+pub(crate) fn base32_encode(data: &[u8]) -> String {
+    let mut text = String::with_capacity((data.len() * 8).div_ceil(5));
+    let (mut buffer, mut bits) = (0u16, 0);
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            text.push(BASE32_ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        text.push(BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    text
+}
+
+/// Decode base32 in any case, trailing bits which do not form a byte are dropped
+pub(crate) fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut data = Vec::with_capacity(text.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u16, 0);
+    for char in text.bytes() {
+        let char = char.to_ascii_lowercase();
+        let value = BASE32_ALPHABET.iter().position(|c| *c == char)? as u16;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((buffer >> bits) as u8);
+        }
+    }
+    Some(data)
+}
+
+/// Parse 32 bytes from hex or base64 in standard or URL-safe alphabet
+fn parse_bytes(text: &str) -> Result<[u8; 32], AdnlParseError> {
+    let mut bytes = [0; 32];
+    match text.len() {
+        64 => hex::decode_to_slice(text, &mut bytes).map_err(|_| AdnlParseError::InvalidFormat)?,
+        44 => {
+            let engine = match text.contains(['-', '_']) {
+                true => &base64::engine::general_purpose::URL_SAFE,
+                false => &base64::engine::general_purpose::STANDARD,
+            };
+            let mut buffer = [0; 33];
+            match engine.decode_slice(text, &mut buffer) {
+                Ok(32) => bytes.copy_from_slice(&buffer[..32]),
+                _ => return Err(AdnlParseError::InvalidFormat),
+            }
+        }
+        _ => return Err(AdnlParseError::InvalidFormat),
+    }
+    Ok(bytes)
+}
+
+impl AdnlAddress {
+    /// Parse user-friendly address of 55 base32 characters with CRC16 checksum
+    pub fn from_base32(text: &str) -> Result<Self, AdnlParseError> {
+        if text.len() != BASE32_ADDRESS_LEN {
+            return Err(AdnlParseError::InvalidFormat);
+        }
+        let mut full = String::with_capacity(BASE32_ADDRESS_LEN + 1);
+        full.push('f');
+        full.push_str(text);
+        let data = base32_decode(&full).ok_or(AdnlParseError::InvalidFormat)?;
+        if data[0] != BASE32_ADDRESS_TAG {
+            return Err(AdnlParseError::InvalidFormat);
+        }
+        if CRC16.checksum(&data[..33]).to_be_bytes() != data[33..35] {
+            return Err(AdnlParseError::InvalidChecksum);
+        }
+        Ok(Self::from(<[u8; 32]>::try_from(&data[1..33]).unwrap()))
+    }
+
+    /// Format address in user-friendly form of 55 lowercase base32 characters
+    pub fn to_base32(&self) -> String {
+        let mut data = [0; 35];
+        data[0] = BASE32_ADDRESS_TAG;
+        data[1..33].copy_from_slice(self.as_bytes());
+        let crc = CRC16.checksum(&data[..33]);
+        data[33..].copy_from_slice(&crc.to_be_bytes());
+        // the first character is always `f`
+        base32_encode(&data)[1..].into()
+    }
+
+    /// Format address in standard base64
+    pub fn to_base64(&self) -> String {
+        base64::engine::general_purpose::STANDARD.encode(self.as_bytes())
+    }
+}
+
+/// Lowercase hex
+impl fmt::Display for AdnlAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.as_bytes()))
+    }
+}
+
+/// Hex, base64 or user-friendly base32
+impl FromStr for AdnlAddress {
+    type Err = AdnlParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.len() {
+            BASE32_ADDRESS_LEN => Self::from_base32(s),
+            _ => Ok(Self::from(parse_bytes(s)?)),
+        }
+    }
+}
+
+/// Raw ed25519 public key of ADNL peer, as found in configs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AdnlPublicKey([u8; 32]);
+
+impl AdnlPublicKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self(key)
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// ADNL address of the key
+    pub fn address(&self) -> AdnlAddress {
+        AdnlAddress::from_ed25519(&self.0)
+    }
+}
+
+impl AsRef<[u8]> for AdnlPublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for AdnlPublicKey {
+    fn from(value: [u8; 32]) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<AdnlPublicKey> for crate::crypto::PublicKey {
+    type Error = crate::AdnlError;
+
+    fn try_from(value: AdnlPublicKey) -> Result<Self, Self::Error> {
+        Self::from_bytes(value.0).ok_or(crate::AdnlError::InvalidPublicKey)
+    }
+}
+
+/// Standard base64
+impl fmt::Display for AdnlPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&base64::engine::general_purpose::STANDARD.encode(self.0))
+    }
+}
+
+/// Hex or base64
+impl FromStr for AdnlPublicKey {
+    type Err = AdnlParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(parse_bytes(s)?))
+    }
+}
+
+/// Serialized as hex string, deserialized from any textual form
+#[cfg(feature = "serde")]
+impl serde::Serialize for AdnlAddress {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AdnlAddress {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        address.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serialized as `{"@type": "pub.ed25519", "key": base64}` object, deserialized from such
+/// object or from a string with hex or base64
+#[cfg(feature = "serde")]
+impl serde::Serialize for AdnlPublicKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("@type", ED25519_KEY_TYPE)?;
+        map.serialize_entry("key", &alloc::string::ToString::to_string(self))?;
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AdnlPublicKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{self, MapAccess, Visitor};
+
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = AdnlPublicKey;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("ed25519 public key string or pub.ed25519 object")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut key = None;
+                while let Some(field) = map.next_key::<String>()? {
+                    match field.as_str() {
+                        "@type" => {
+                            let key_type = map.next_value::<String>()?;
+                            if key_type != ED25519_KEY_TYPE {
+                                return Err(de::Error::invalid_value(
+                                    de::Unexpected::Str(&key_type),
+                                    &ED25519_KEY_TYPE,
+                                ));
+                            }
+                        }
+                        "key" => key = Some(map.next_value::<String>()?),
+                        _ => {
+                            map.next_value::<de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                let key = key.ok_or_else(|| de::Error::missing_field("key"))?;
+                self.visit_str(&key)
+            }
+        }
+
+        deserializer.deserialize_any(KeyVisitor)
+    }
+}
@@ -0,0 +1,168 @@
+use alloc::vec::Vec;
+
+use super::block::{check_block_root, check_shard_proof, check_state_root, load_shard_state};
+use super::{merkle_proof_roots, skip_currency_collection, ProofError};
+use crate::boc::{self, dict_get, Cell, CellSlice, CellType};
+use crate::tl::ton::lite_server::{self, AccountId};
+use crate::tl::ton::ton_node::BlockIdExt;
+
+/// Tag of `transaction$0111`
+const TRANSACTION_TAG: u64 = 0b0111;
+
+/// Account entry of shard state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardAccount {
+    /// Hash of `Account` cell
+    pub account_hash: [u8; 32],
+    pub last_trans_hash: [u8; 32],
+    pub last_trans_lt: u64,
+}
+
+fn shard_contains(shard: i64, account: &AccountId) -> bool {
+    let shard = shard as u64;
+    let prefix = u64::from_be_bytes(account.id[..8].try_into().unwrap());
+    // bits above the lowest set bit of shard form its prefix
+    let mask = !((shard & shard.wrapping_neg()) << 1).wrapping_sub(1);
+    (prefix ^ shard) & mask == 0
+}
+
+/// Check proof of shard block and its state, and find `account` in the state
+fn check_account_proof(
+    proof: &[u8],
+    shardblk: &BlockIdExt,
+    account: &AccountId,
+) -> Result<Option<ShardAccount>, ProofError> {
+    if shardblk.workchain != account.workchain || !shard_contains(shardblk.shard, account) {
+        return Err(ProofError::InvalidProof("account does not belong to shard"));
+    }
+    let [block_root, state_root] = merkle_proof_roots(proof, 2)?.try_into().unwrap();
+    check_state_root(&state_root, &check_block_root(&block_root, shardblk)?)?;
+
+    let (accounts, _) = load_shard_state(&state_root)?;
+    let mut accounts = CellSlice::new(accounts)?;
+    if !accounts.load_bit()? {
+        return Ok(None);
+    }
+    let Some(mut value) = dict_get(
+        CellSlice::new(accounts.load_reference()?)?,
+        &account.id,
+        256,
+    )?
+    else {
+        return Ok(None);
+    };
+    // extra of the dictionary: split_depth and balance
+    value.skip_bits(5)?;
+    skip_currency_collection(&mut value)?;
+    Ok(Some(ShardAccount {
+        account_hash: value.load_reference()?.hash_at(0),
+        last_trans_hash: value.load_u256()?,
+        last_trans_lt: value.load_uint(64)?,
+    }))
+}
+
+/// Check `liteServer.accountState` answer for `account` at masterchain or shard block `id`.
+/// Returns account entry of the shard state, or `None` if the account does not exist.
+pub fn check_account_state(
+    answer: &lite_server::AccountState,
+    id: &BlockIdExt,
+    account: &AccountId,
+) -> Result<Option<ShardAccount>, ProofError> {
+    if answer.id != *id {
+        return Err(ProofError::BlockMismatch);
+    }
+    check_shard_proof(id, &answer.shardblk, &answer.shard_proof)?;
+    let shard_account = check_account_proof(&answer.proof, &answer.shardblk, account)?;
+    let state_hash = match answer.state.is_empty() {
+        true => None,
+        false => {
+            // pruned state comes as Merkle proof
+            let root = boc::parse_single(&answer.state)?;
+            match root.cell_type() {
+                CellType::MerkleProof => Some(root.references()[0].hash_at(0)),
+                _ => Some(root.hash_at(0)),
+            }
+        }
+    };
+    if shard_account.as_ref().map(|a| a.account_hash) != state_hash {
+        return Err(ProofError::AccountMismatch);
+    }
+    Ok(shard_account)
+}
+
+/// Check that `liteServer.runMethodResult` answer was computed on the state of `account` at
+/// block `id`. The result of the method itself is not checked.
+pub fn check_run_method_result(
+    answer: &lite_server::RunMethodResult,
+    id: &BlockIdExt,
+    account: &AccountId,
+) -> Result<(), ProofError> {
+    if answer.id != *id {
+        return Err(ProofError::BlockMismatch);
+    }
+    let (Some(shard_proof), Some(proof), Some(state_proof)) =
+        (&answer.shard_proof, &answer.proof, &answer.state_proof)
+    else {
+        return Err(ProofError::MissingProof);
+    };
+    check_shard_proof(id, &answer.shardblk, shard_proof)?;
+    let shard_account = check_account_proof(proof, &answer.shardblk, account)?;
+    let [state] = merkle_proof_roots(state_proof, 1)?.try_into().unwrap();
+    match shard_account {
+        Some(shard_account) if shard_account.account_hash == state.hash_at(0) => Ok(()),
+        _ => Err(ProofError::AccountMismatch),
+    }
+}
+
+/// Check that `liteServer.transactionList` answer is a chain of transactions of `account`
+/// going back from transaction `lt` and `hash`, and return the transactions.
+///
+/// The answer does not prove in which blocks the transactions are, so block ids are only
+/// checked to be plausible: they must be blocks of a shard containing the account, going back
+/// in time, and not newer than `shardblk` if given. Use a shard block whose proven state has
+/// `lt` and `hash` as the last transaction of the account, such as `shardblk` of an answer
+/// checked with [`check_account_state`]. Returned ids are still reported by the server and
+/// must not be treated as verified.
+pub fn check_transactions(
+    answer: &lite_server::TransactionList,
+    account: &AccountId,
+    lt: i64,
+    hash: [u8; 32],
+    shardblk: Option<&BlockIdExt>,
+) -> Result<Vec<Cell>, ProofError> {
+    let transactions = match answer.transactions.is_empty() {
+        true => Vec::new(),
+        false => boc::parse(&answer.transactions)?,
+    };
+    if transactions.len() != answer.ids.len() {
+        return Err(ProofError::InvalidProof(
+            "number of transactions and blocks differ",
+        ));
+    }
+    let mut max_seqno = shardblk.map_or(i32::MAX, |shardblk| shardblk.seqno);
+    for id in &answer.ids {
+        if id.workchain != account.workchain || !shard_contains(id.shard, account) {
+            return Err(ProofError::BlockMismatch);
+        }
+        if id.seqno > max_seqno {
+            return Err(ProofError::InvalidProof("transaction block is too new"));
+        }
+        max_seqno = id.seqno;
+    }
+    let (mut lt, mut hash) = (lt as u64, hash);
+    for transaction in &transactions {
+        let mut slice = CellSlice::new(transaction)?;
+        if transaction.hash() != hash {
+            return Err(ProofError::TransactionMismatch);
+        }
+        if slice.load_uint(4)? != TRANSACTION_TAG {
+            return Err(ProofError::InvalidProof("invalid transaction tag"));
+        }
+        if slice.load_u256()? != account.id || slice.load_uint(64)? != lt {
+            return Err(ProofError::TransactionMismatch);
+        }
+        hash = slice.load_u256()?;
+        lt = slice.load_uint(64)?;
+    }
+    Ok(transactions)
+}
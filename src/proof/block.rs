@@ -0,0 +1,275 @@
+use sha2::{Digest, Sha256};
+
+use super::{merkle_proof_roots, ProofError};
+use crate::boc::{self, dict_get, Cell, CellSlice, CellType};
+use crate::tl::ton::lite_server;
+use crate::tl::ton::ton_node::BlockIdExt;
+
+const BLOCK_TAG: u64 = 0x11ef55aa;
+const BLOCK_INFO_TAG: u64 = 0x9bc7a987;
+const SHARD_STATE_TAG: u64 = 0x9023afe2;
+const MC_STATE_EXTRA_TAG: u64 = 0xcc26;
+
+/// Maximum depth of shard tree
+const MAX_SHARD_DEPTH: usize = 60;
+
+/// Fields of block header checked by a proof
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockInfo {
+    pub key_block: bool,
+    pub gen_utime: u32,
+    pub start_lt: u64,
+    pub end_lt: u64,
+    /// Short hash of the validator set which signed the block
+    pub gen_validator_list_hash_short: u32,
+    pub gen_catchain_seqno: u32,
+    pub prev_key_block_seqno: u32,
+    /// Hash of shard state after the block, `None` if the proof does not include it
+    pub state_hash: Option<[u8; 32]>,
+}
+
+/// Check header of block `id` by the root of its Merkle proof
+pub(crate) fn check_block_root(root: &Cell, id: &BlockIdExt) -> Result<BlockInfo, ProofError> {
+    if root.hash_at(0) != id.root_hash {
+        return Err(ProofError::RootHashMismatch);
+    }
+    let mut block = CellSlice::new(root)?;
+    if block.load_uint(32)? != BLOCK_TAG {
+        return Err(ProofError::InvalidProof("invalid block tag"));
+    }
+    let info = block.load_reference()?;
+    let _value_flow = block.load_reference()?;
+    let state_update = block.load_reference()?;
+
+    let mut info = CellSlice::new(info)?;
+    if info.load_uint(32)? != BLOCK_INFO_TAG {
+        return Err(ProofError::InvalidProof("invalid block info tag"));
+    }
+    // version
+    info.skip_bits(32)?;
+    let not_master = info.load_bit()?;
+    // after_merge, before_split, after_split, want_split, want_merge
+    info.skip_bits(5)?;
+    let key_block = info.load_bit()?;
+    // vert_seqno_incr, flags
+    info.skip_bits(1 + 8)?;
+    let seqno = info.load_uint(32)?;
+    // vert_seq_no
+    info.skip_bits(32)?;
+    let (workchain, shard) = load_shard_ident(&mut info)?;
+    let gen_utime = info.load_uint(32)? as u32;
+    let start_lt = info.load_uint(64)?;
+    let end_lt = info.load_uint(64)?;
+    let gen_validator_list_hash_short = info.load_uint(32)? as u32;
+    let gen_catchain_seqno = info.load_uint(32)? as u32;
+    // min_ref_mc_seqno
+    info.skip_bits(32)?;
+    let prev_key_block_seqno = info.load_uint(32)? as u32;
+    if workchain != id.workchain
+        || shard != id.shard
+        || seqno != id.seqno as u64
+        || not_master == id.is_masterchain()
+    {
+        return Err(ProofError::InvalidProof(
+            "block header does not match block id",
+        ));
+    }
+
+    // the new state hash of Merkle update is checked against its second reference on creation
+    let state_hash = match state_update.cell_type() {
+        CellType::MerkleUpdate => Some(state_update.data()[33..65].try_into().unwrap()),
+        CellType::PrunedBranch => None,
+        _ => return Err(ProofError::InvalidProof("invalid state update")),
+    };
+    Ok(BlockInfo {
+        key_block,
+        gen_utime,
+        start_lt,
+        end_lt,
+        gen_validator_list_hash_short,
+        gen_catchain_seqno,
+        prev_key_block_seqno,
+        state_hash,
+    })
+}
+
+/// Load `ShardIdent` as workchain and shard
+fn load_shard_ident(slice: &mut CellSlice) -> Result<(i32, i64), ProofError> {
+    if slice.load_uint(2)? != 0 {
+        return Err(ProofError::InvalidProof("invalid shard ident"));
+    }
+    // shard_pfx_bits
+    slice.skip_bits(6)?;
+    Ok((slice.load_int(32)? as i32, slice.load_uint(64)? as i64))
+}
+
+/// Check that virtual root of state proof is the state of block with `info`
+pub(crate) fn check_state_root(root: &Cell, info: &BlockInfo) -> Result<(), ProofError> {
+    match info.state_hash {
+        Some(hash) if hash == root.hash_at(0) => Ok(()),
+        Some(_) => Err(ProofError::StateHashMismatch),
+        None => Err(ProofError::InvalidProof("state update is pruned")),
+    }
+}
+
+/// Get `accounts` and `custom` references of `ShardStateUnsplit`
+pub(crate) fn load_shard_state(root: &Cell) -> Result<(&Cell, Option<&Cell>), ProofError> {
+    let mut state = CellSlice::new(root)?;
+    if state.load_uint(32)? != SHARD_STATE_TAG {
+        return Err(ProofError::InvalidProof("invalid shard state tag"));
+    }
+    // global_id
+    state.skip_bits(32)?;
+    load_shard_ident(&mut state)?;
+    // seq_no, vert_seq_no, gen_utime, gen_lt, min_ref_mc_seqno, before_split
+    state.skip_bits(32 + 32 + 32 + 64 + 32 + 1)?;
+    let has_custom = state.load_bit()?;
+    let _out_msg_queue_info = state.load_reference()?;
+    let accounts = state.load_reference()?;
+    let _other = state.load_reference()?;
+    let custom = match has_custom {
+        true => Some(state.load_reference()?),
+        false => None,
+    };
+    Ok((accounts, custom))
+}
+
+/// References of `McStateExtra`
+pub(crate) struct McStateExtra<'a> {
+    pub shard_hashes: Option<&'a Cell>,
+    /// Root of configuration dictionary
+    pub config: &'a Cell,
+    /// Cell with validator info and previous blocks, may be pruned if they are not needed
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    other: &'a Cell,
+}
+
+impl<'a> McStateExtra<'a> {
+    /// Root of `OldMcBlocksInfo` dictionary of previous masterchain blocks
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub fn prev_blocks(&self) -> Result<Option<&'a Cell>, ProofError> {
+        let mut other = CellSlice::new(self.other)?;
+        // flags, validator_info
+        other.skip_bits(16 + 32 + 32 + 1)?;
+        match other.load_bit()? {
+            true => Ok(Some(other.load_reference()?)),
+            false => Ok(None),
+        }
+    }
+}
+
+/// Load `McStateExtra` of masterchain state with virtual `root`
+pub(crate) fn load_mc_state_extra(root: &Cell) -> Result<McStateExtra<'_>, ProofError> {
+    let (_, custom) = load_shard_state(root)?;
+    let custom = custom.ok_or(ProofError::InvalidProof("no masterchain state extra"))?;
+    let mut extra = CellSlice::new(custom)?;
+    if extra.load_uint(16)? != MC_STATE_EXTRA_TAG {
+        return Err(ProofError::InvalidProof(
+            "invalid masterchain state extra tag",
+        ));
+    }
+    let shard_hashes = match extra.load_bit()? {
+        true => Some(extra.load_reference()?),
+        false => None,
+    };
+    // config_addr
+    extra.skip_bits(256)?;
+    Ok(McStateExtra {
+        shard_hashes,
+        config: extra.load_reference()?,
+        other: extra.load_reference()?,
+    })
+}
+
+/// Check `liteServer.blockHeader` answer for block `id`
+pub fn check_block_header(
+    answer: &lite_server::BlockHeader,
+    id: &BlockIdExt,
+) -> Result<BlockInfo, ProofError> {
+    if answer.id != *id {
+        return Err(ProofError::BlockMismatch);
+    }
+    let [root] = merkle_proof_roots(&answer.header_proof, 1)?
+        .try_into()
+        .unwrap();
+    check_block_root(&root, id)
+}
+
+/// Check `liteServer.blockData` answer for block `id` and return the block root
+pub fn check_block_data(
+    answer: &lite_server::BlockData,
+    id: &BlockIdExt,
+) -> Result<Cell, ProofError> {
+    if answer.id != *id {
+        return Err(ProofError::BlockMismatch);
+    }
+    if <[u8; 32]>::from(Sha256::digest(&answer.data)) != id.file_hash {
+        return Err(ProofError::FileHashMismatch);
+    }
+    let root = boc::parse_single(&answer.data)?;
+    if root.hash() != id.root_hash {
+        return Err(ProofError::RootHashMismatch);
+    }
+    Ok(root)
+}
+
+/// Check that `shard_proof` proves `shardblk` to be the top block of its shard in masterchain
+/// block `id`. No proof is needed if both are the same block.
+pub fn check_shard_proof(
+    id: &BlockIdExt,
+    shardblk: &BlockIdExt,
+    shard_proof: &[u8],
+) -> Result<(), ProofError> {
+    if id == shardblk {
+        return Ok(());
+    }
+    if !id.is_masterchain() {
+        return Err(ProofError::InvalidProof(
+            "reference block is not in masterchain",
+        ));
+    }
+    let [block_root, state_root] = merkle_proof_roots(shard_proof, 2)?.try_into().unwrap();
+    check_state_root(&state_root, &check_block_root(&block_root, id)?)?;
+
+    let extra = load_mc_state_extra(&state_root)?;
+    let shard_hashes = extra.shard_hashes.ok_or(ProofError::ShardMismatch)?;
+    let key = shardblk.workchain.to_be_bytes();
+    let mut tree =
+        dict_get(CellSlice::new(shard_hashes)?, &key, 32)?.ok_or(ProofError::ShardMismatch)?;
+
+    // walk down the binary tree of shards by bits of shard prefix
+    let shard = shardblk.shard as u64;
+    let mut node = CellSlice::new(tree.load_reference()?)?;
+    let mut prefix = 0;
+    let mut depth = 0;
+    while node.load_bit()? {
+        if depth == MAX_SHARD_DEPTH {
+            return Err(ProofError::InvalidProof("shard tree is too deep"));
+        }
+        let bit = (shard >> (63 - depth)) & 1;
+        let left = node.load_reference()?;
+        let right = node.load_reference()?;
+        node = CellSlice::new(if bit == 1 { right } else { left })?;
+        prefix |= bit << (63 - depth);
+        depth += 1;
+    }
+    let leaf_shard = prefix | (1 << (63 - depth));
+
+    // shard_descr#b or shard_descr_new#a
+    if !matches!(node.load_uint(4)?, 0xa | 0xb) {
+        return Err(ProofError::InvalidProof("invalid shard description tag"));
+    }
+    let seqno = node.load_uint(32)?;
+    // reg_mc_seqno, start_lt, end_lt
+    node.skip_bits(32 + 64 + 64)?;
+    let root_hash = node.load_u256()?;
+    let file_hash = node.load_u256()?;
+    if leaf_shard != shard
+        || seqno != shardblk.seqno as u64
+        || root_hash != shardblk.root_hash
+        || file_hash != shardblk.file_hash
+    {
+        return Err(ProofError::ShardMismatch);
+    }
+    Ok(())
+}
@@ -0,0 +1,259 @@
+use alloc::vec::Vec;
+
+use super::block::{check_block_root, check_state_root, load_mc_state_extra, BlockInfo};
+use super::{merkle_proof_roots, skip_currency_collection, ProofError};
+use crate::boc::{dict_get, Cell, CellSlice};
+use crate::config::{BlockchainConfig, ValidatorSet};
+use crate::crypto::PublicKey;
+use crate::tl::ton::lite_server::{
+    BlockLink, BlockLinkBack, BlockLinkForward, PartialBlockProof, SignatureSet,
+};
+use crate::tl::ton::{ton, ton_node::BlockIdExt};
+use crate::tl::{self, Boxed};
+use crate::AdnlAddress;
+
+const BLOCK_EXTRA_TAG: u64 = 0x4a33f6fd;
+const MC_BLOCK_EXTRA_TAG: u64 = 0xcca5;
+
+/// Check `liteServer.partialBlockProof` which starts at trusted masterchain block `known`,
+/// returns the last block it proves
+pub fn check_block_proof(
+    proof: &PartialBlockProof,
+    known: &BlockIdExt,
+) -> Result<BlockIdExt, ProofError> {
+    if proof.from != *known {
+        return Err(ProofError::BlockMismatch);
+    }
+    let mut current = known;
+    for link in &proof.steps {
+        let (from, to) = match link {
+            BlockLink::BlockLinkBack(link) => (&link.from, &link.to),
+            BlockLink::BlockLinkForward(link) => (&link.from, &link.to),
+        };
+        if from != current {
+            return Err(ProofError::InvalidProof(
+                "block proof links are not chained",
+            ));
+        }
+        check_block_link(link)?;
+        current = to;
+    }
+    if proof.to != *current {
+        return Err(ProofError::InvalidProof(
+            "block proof does not reach its last block",
+        ));
+    }
+    Ok(current.clone())
+}
+
+/// Check single link of block proof: proof of an older block by the state of a newer one, or
+/// signatures of a newer block by validators from the config of an older key block
+pub fn check_block_link(link: &BlockLink) -> Result<(), ProofError> {
+    match link {
+        BlockLink::BlockLinkBack(link) => check_link_back(link),
+        BlockLink::BlockLinkForward(link) => check_link_forward(link),
+    }
+}
+
+fn check_link_ends(from: &BlockIdExt, to: &BlockIdExt, forward: bool) -> Result<(), ProofError> {
+    if !from.is_masterchain() || !to.is_masterchain() {
+        return Err(ProofError::InvalidProof("block link is not in masterchain"));
+    }
+    if (from.seqno < to.seqno) != forward || from.seqno == to.seqno {
+        return Err(ProofError::InvalidProof(
+            "block link goes in wrong direction",
+        ));
+    }
+    Ok(())
+}
+
+/// Check proof of destination block header, which is needed to know that it is a key block.
+/// Returns `None` if the proof is empty, which is allowed only for non-key blocks
+fn check_dest_proof(
+    dest_proof: &[u8],
+    to: &BlockIdExt,
+    to_key_block: bool,
+) -> Result<Option<BlockInfo>, ProofError> {
+    if dest_proof.is_empty() {
+        return match to_key_block {
+            true => Err(ProofError::MissingProof),
+            false => Ok(None),
+        };
+    }
+    let [root] = merkle_proof_roots(dest_proof, 1)?.try_into().unwrap();
+    let info = check_block_root(&root, to)?;
+    if info.key_block != to_key_block {
+        return Err(ProofError::InvalidProof("key block flag mismatch"));
+    }
+    Ok(Some(info))
+}
+
+fn check_link_back(link: &BlockLinkBack) -> Result<(), ProofError> {
+    check_link_ends(&link.from, &link.to, false)?;
+    let [block] = merkle_proof_roots(&link.proof, 1)?.try_into().unwrap();
+    let [state] = merkle_proof_roots(&link.state_proof, 1)?
+        .try_into()
+        .unwrap();
+    check_state_root(&state, &check_block_root(&block, &link.from)?)?;
+
+    let prev_blocks = load_mc_state_extra(&state)?
+        .prev_blocks()?
+        .ok_or(ProofError::InvalidProof("no previous blocks in state"))?;
+    let mut value = dict_get(
+        CellSlice::new(prev_blocks)?,
+        &(link.to.seqno as u32).to_be_bytes(),
+        32,
+    )?
+    .ok_or(ProofError::InvalidProof(
+        "block is not in previous blocks of state",
+    ))?;
+    // extra of the dictionary: key flag and max_end_lt
+    value.skip_bits(1 + 64)?;
+    let key_block = value.load_bit()?;
+    // end_lt
+    value.skip_bits(64)?;
+    if value.load_uint(32)? != link.to.seqno as u64
+        || value.load_u256()? != link.to.root_hash
+        || value.load_u256()? != link.to.file_hash
+    {
+        return Err(ProofError::BlockMismatch);
+    }
+    if key_block != link.to_key_block {
+        return Err(ProofError::InvalidProof("key block flag mismatch"));
+    }
+    check_dest_proof(&link.dest_proof, &link.to, link.to_key_block)?;
+    Ok(())
+}
+
+fn check_link_forward(link: &BlockLinkForward) -> Result<(), ProofError> {
+    check_link_ends(&link.from, &link.to, true)?;
+    let [block] = merkle_proof_roots(&link.config_proof, 1)?
+        .try_into()
+        .unwrap();
+    if !check_block_root(&block, &link.from)?.key_block {
+        return Err(ProofError::InvalidProof(
+            "forward link does not start at key block",
+        ));
+    }
+    let validators = BlockchainConfig::new(key_block_config(&block)?.clone())
+        .validators()?
+        .ok_or(ProofError::InvalidProof("no validator set in config"))?;
+    // header of `to` ties it to the validator set of `from`, so it is required even for
+    // non-key blocks
+    let to = check_dest_proof(&link.dest_proof, &link.to, link.to_key_block)?
+        .ok_or(ProofError::MissingProof)?;
+    if to.prev_key_block_seqno != link.from.seqno as u32 {
+        return Err(ProofError::InvalidProof(
+            "forward link does not start at previous key block",
+        ));
+    }
+    // key block which switches validator set is still signed by the old one after its
+    // `utime_until`
+    if to.gen_utime < validators.utime_since
+        || (to.gen_utime >= validators.utime_until && !to.key_block)
+    {
+        return Err(ProofError::InvalidProof(
+            "block is out of validator set lifetime",
+        ));
+    }
+    let signatures = &link.signatures.0;
+    if signatures.catchain_seqno as u32 != to.gen_catchain_seqno
+        || signatures.validator_set_hash as u32 != to.gen_validator_list_hash_short
+    {
+        return Err(ProofError::InvalidProof(
+            "signatures are not for validator session of block",
+        ));
+    }
+    check_signatures(&validators, signatures, &link.to)
+}
+
+/// Get config dictionary from `McBlockExtra` of key block with virtual `root`
+fn key_block_config(root: &Cell) -> Result<&Cell, ProofError> {
+    let mut block = CellSlice::new(root)?;
+    // tag, global_id, info, value_flow, state_update
+    block.skip_bits(32 + 32)?;
+    for _ in 0..3 {
+        block.load_reference()?;
+    }
+    let mut extra = CellSlice::new(block.load_reference()?)?;
+    if extra.load_uint(32)? != BLOCK_EXTRA_TAG {
+        return Err(ProofError::InvalidProof("invalid block extra tag"));
+    }
+    // in_msg_descr, out_msg_descr, account_blocks, rand_seed, created_by
+    for _ in 0..3 {
+        extra.load_reference()?;
+    }
+    extra.skip_bits(256 + 256)?;
+    if !extra.load_bit()? {
+        return Err(ProofError::InvalidProof("no masterchain block extra"));
+    }
+
+    let mut extra = CellSlice::new(extra.load_reference()?)?;
+    if extra.load_uint(16)? != MC_BLOCK_EXTRA_TAG {
+        return Err(ProofError::InvalidProof(
+            "invalid masterchain block extra tag",
+        ));
+    }
+    if !extra.load_bit()? {
+        return Err(ProofError::InvalidProof(
+            "no config in masterchain block extra",
+        ));
+    }
+    // shard_hashes
+    if extra.load_bit()? {
+        extra.load_reference()?;
+    }
+    // shard_fees with fees and created extra
+    if extra.load_bit()? {
+        extra.load_reference()?;
+    }
+    skip_currency_collection(&mut extra)?;
+    skip_currency_collection(&mut extra)?;
+    // prev_blk_signatures, recover_create_msg, mint_msg
+    extra.load_reference()?;
+    // config_addr
+    extra.skip_bits(256)?;
+    Ok(extra.load_reference()?)
+}
+
+/// Check that masterchain validators with more than 2/3 of total weight signed block `to`
+fn check_signatures(
+    validators: &ValidatorSet,
+    signatures: &SignatureSet,
+    to: &BlockIdExt,
+) -> Result<(), ProofError> {
+    let message = tl::serialize(&Boxed(ton::BlockId {
+        root_cell_hash: to.root_hash,
+        file_hash: to.file_hash,
+    }));
+    let validators = validators.main_validators();
+    let node_ids = validators
+        .iter()
+        .map(|validator| AdnlAddress::from_ed25519(&validator.public_key))
+        .collect::<Vec<_>>();
+    let mut signed = alloc::vec![false; validators.len()];
+    let mut signed_weight = 0u128;
+    for signature in &signatures.signatures {
+        let index = node_ids
+            .iter()
+            .position(|id| *id.as_bytes() == signature.node_id_short)
+            .ok_or(ProofError::InvalidSignature)?;
+        if signed[index] {
+            return Err(ProofError::InvalidSignature);
+        }
+        let valid = <&[u8; 64]>::try_from(signature.signature.as_slice())
+            .ok()
+            .zip(PublicKey::from_bytes(validators[index].public_key))
+            .is_some_and(|(signature, key)| key.verify_raw(&message, signature));
+        if !valid {
+            return Err(ProofError::InvalidSignature);
+        }
+        signed[index] = true;
+        signed_weight += validators[index].weight as u128;
+    }
+    let total_weight = validators.iter().map(|v| v.weight as u128).sum::<u128>();
+    if signed_weight * 3 <= total_weight * 2 {
+        return Err(ProofError::NotEnoughSignatures);
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use super::block::{check_block_root, check_state_root, load_mc_state_extra};
+use super::{merkle_proof_roots, ProofError};
+use crate::config::BlockchainConfig;
+use crate::tl::ton::lite_server::ConfigInfo;
+use crate::tl::ton::ton_node::BlockIdExt;
+
+/// `getConfigParams` mode which takes config from the previous key block
+const FROM_KEY_BLOCK_MODE: u32 = 0x8000;
+
+/// Check `liteServer.configInfo` answer for masterchain block `id` and return the config of
+/// its state. Only the requested params are present in the config, others are pruned.
+pub fn check_config_info(
+    answer: &ConfigInfo,
+    id: &BlockIdExt,
+) -> Result<BlockchainConfig, ProofError> {
+    if answer.id != *id {
+        return Err(ProofError::BlockMismatch);
+    }
+    if answer.mode & FROM_KEY_BLOCK_MODE != 0 {
+        return Err(ProofError::InvalidProof(
+            "config of key block is not supported",
+        ));
+    }
+    let [block] = merkle_proof_roots(&answer.state_proof, 1)?
+        .try_into()
+        .unwrap();
+    let [state] = merkle_proof_roots(&answer.config_proof, 1)?
+        .try_into()
+        .unwrap();
+    check_state_root(&state, &check_block_root(&block, id)?)?;
+    let config = load_mc_state_extra(&state)?.config.clone();
+    Ok(BlockchainConfig::new(config))
+}
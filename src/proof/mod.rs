@@ -0,0 +1,78 @@
+//! Checks of proofs in liteserver answers. Answers are checked to be consistent with block ids
+//! passed by the caller, so the ids must come from a trusted source, such as a checked chain of
+//! block proofs, rather than from the same server.
+
+mod account;
+mod block;
+#[cfg(feature = "std")]
+mod block_link;
+mod config;
+
+use alloc::vec::Vec;
+
+use thiserror::Error;
+
+use crate::boc::{self, BocError, Cell, CellSlice, CellType};
+
+pub use account::{check_account_state, check_run_method_result, check_transactions, ShardAccount};
+pub use block::{check_block_data, check_block_header, check_shard_proof, BlockInfo};
+#[cfg(feature = "std")]
+pub use block_link::{check_block_link, check_block_proof};
+pub use config::check_config_info;
+
+/// Error of proof check, means that the answer can't be trusted
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProofError {
+    #[error("Invalid proof BoC: {0}")]
+    Boc(#[from] BocError),
+    #[error("Proof is missing from the answer")]
+    MissingProof,
+    #[error("Expected Merkle proof")]
+    NotMerkleProof,
+    #[error("Invalid proof: {0}")]
+    InvalidProof(&'static str),
+    #[error("Answer is for another block")]
+    BlockMismatch,
+    #[error("Root hash does not match block")]
+    RootHashMismatch,
+    #[error("File hash does not match block")]
+    FileHashMismatch,
+    #[error("State hash does not match block")]
+    StateHashMismatch,
+    #[error("Shard block does not match masterchain state")]
+    ShardMismatch,
+    #[error("Account state does not match proof")]
+    AccountMismatch,
+    #[error("Transaction does not match requested one")]
+    TransactionMismatch,
+    #[error("Invalid or unknown validator signature")]
+    InvalidSignature,
+    #[error("Not enough validator signatures")]
+    NotEnoughSignatures,
+}
+
+/// Parse BoC with `count` Merkle proofs and return their inner cells, which have hashes of
+/// the original cells at level 0
+fn merkle_proof_roots(proof: &[u8], count: usize) -> Result<Vec<Cell>, ProofError> {
+    let roots = boc::parse(proof)?;
+    if roots.len() != count {
+        return Err(ProofError::InvalidProof("unexpected number of roots"));
+    }
+    roots
+        .into_iter()
+        .map(|root| match root.cell_type() {
+            CellType::MerkleProof => Ok(root.references()[0].clone()),
+            _ => Err(ProofError::NotMerkleProof),
+        })
+        .collect()
+}
+
+/// Skip `CurrencyCollection`
+fn skip_currency_collection(slice: &mut CellSlice) -> Result<(), ProofError> {
+    let grams_len = slice.load_uint(4)? as usize;
+    slice.skip_bits(grams_len * 8)?;
+    if slice.load_bit()? {
+        slice.load_reference()?;
+    }
+    Ok(())
+}
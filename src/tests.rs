@@ -3,9 +3,14 @@ extern crate alloc;
 use super::*;
 use crate::crypto::{KeyPair, PublicKey};
 use alloc::vec::Vec;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
 use futures::{SinkExt, StreamExt};
 use rand_core::OsRng;
-use tokio::net::TcpListener;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(feature = "tokio")]
+use tokio::net::{TcpListener, TcpStream};
+#[cfg(feature = "tokio")]
 use tokio_util::{
     bytes::BytesMut,
     codec::{Decoder, Encoder},
@@ -65,7 +70,7 @@ fn test_handshake(
     let ecdh_raw: [u8; 32] = ecdh.try_into().unwrap();
     let handshake = AdnlHandshake::new(
         AdnlAddress::from(&remote_public),
-        local_public.clone(),
+        local_public,
         ecdh_raw,
         aes_params,
     );
@@ -75,6 +80,21 @@ fn test_handshake(
         "handshake is not the same!"
     );
 
+    // test allocation-free primitives
+    let packet: [u8; 256] = expected_handshake.as_slice().try_into().unwrap();
+    assert_eq!(
+        raw::seal_handshake(
+            &AdnlAddress::from(&remote_public),
+            local_public.as_bytes(),
+            &ecdh_raw,
+            &AdnlAesParams::from(aes_params_raw),
+        ),
+        packet,
+        "raw handshake is not the same!"
+    );
+    let opened = raw::open_handshake(&packet, &ecdh_raw).expect("invalid handshake");
+    assert_eq!(opened.to_bytes(), aes_params_raw, "aes_params mismatch");
+
     // test deserializing
     // let handshake2 = AdnlHandshake::decrypt_from_raw(expected_handshake.as_slice().try_into().unwrap(), |_| Some(key.clone())).expect("invalid handshake");
     // assert_eq!(handshake2.aes_params().to_bytes(), aes_params_raw, "aes_params mismatch");
@@ -83,6 +103,7 @@ fn test_handshake(
     // assert_eq!(&handshake2.to_bytes(), expected_handshake.as_slice(), "reencryption failed");
 }
 
+#[cfg(feature = "tokio")]
 #[test]
 fn test_send_1() {
     let aes_params = hex::decode("b3d529e34b839a521518447b68343aebaae9314ac95aaacfdb687a2163d1a98638db306b63409ef7bc906b4c9dc115488cf90dfa964f520542c69e1a4a495edf9ae9ee72023203c8b266d552f251e8d724929733428c8e276ab3bd6291367336a6ab8dc3d36243419bd0b742f76691a5dec14edbd50f7c1b58ec961ae45be58cbf6623f3ec9705bd5d227761ec79cee377e2566ff668f863552bddfd6ff3a16b").unwrap();
@@ -93,6 +114,7 @@ fn test_send_1() {
     test_send(aes_params, buffer, expected_packet);
 }
 
+#[cfg(feature = "tokio")]
 #[test]
 fn test_send_2() {
     let aes_params = hex::decode("7e3c66de7c64d4bee4368e69560101991db4b084430a336cffe676c9ac0a795d8c98367309422a8e927e62ed657ba3eaeeb6acd3bbe5564057dfd1d60609a25a48963cbb7d14acf4fc83ec59254673bc85be22d04e80e7b83c641d37cae6e1d82a400bf159490bbc0048e69234ad89e999d792eefdaa56734202546d9188706e95e1272267206a8e7ee1f7c077f76bd26e494972e34d72e257bf20364dbf39b0").unwrap();
@@ -103,6 +125,21 @@ fn test_send_2() {
     test_send(aes_params, buffer, expected_packet);
 }
 
+#[test]
+fn codec_buffer_length() {
+    let mut codec = AdnlCodec::client(&[0u8; 160].into());
+    let mut packet = vec![0u8; AdnlCodec::packet_length(4) - 1];
+    assert!(matches!(
+        codec.encrypt_packet_with_nonce(b"ping", &[0; 32], &mut packet),
+        Err(AdnlError::InvalidBufferLength)
+    ));
+    packet.push(0);
+    assert!(codec
+        .encrypt_packet_with_nonce(b"ping", &[0; 32], &mut packet)
+        .is_ok());
+}
+
+#[cfg(feature = "tokio")]
 fn test_send(aes_params: Vec<u8>, buffer: Vec<u8>, expected_packet: Vec<u8>) {
     let aes_params: [u8; 160] = aes_params.try_into().unwrap();
     let mut codec = AdnlCodec::client(&aes_params.into());
@@ -135,6 +172,7 @@ fn test_send(aes_params: Vec<u8>, buffer: Vec<u8>, expected_packet: Vec<u8>) {
     test_recv(&mut codec, packet.into(), buffer);
 }
 
+#[cfg(feature = "tokio")]
 #[test]
 fn test_recv_1() {
     let encrypted_data = hex::decode("81e95e433c87c9ad2a716637b3a12644fbfb12dbd02996abc40ed2beb352483d6ecf9e2ad181a5abde4d4146ca3a8524739d3acebb2d7599cc6b81967692a62118997e16").unwrap();
@@ -148,6 +186,7 @@ fn test_recv_1() {
     test_recv(&mut codec, encrypted_data, expected_data);
 }
 
+#[cfg(feature = "tokio")]
 #[test]
 fn test_recv_2() {
     let encrypted_data = hex::decode("b75dcf27582beb4031d6d3700c9b7925bf84a78f2bd16b186484d36427a8824ac86e27cea81eb5bcbac447a37269845c65be51babd11c80627f81b4247f84df16d05c4f1").unwrap();
@@ -161,6 +200,7 @@ fn test_recv_2() {
     test_recv(&mut codec, encrypted_data, expected_data);
 }
 
+#[cfg(feature = "tokio")]
 fn test_recv(codec: &mut AdnlCodec, encrypted_packet: Vec<u8>, expected_data: Vec<u8>) {
     let data = codec
         .decode(&mut encrypted_packet.as_slice().into())
@@ -169,16 +209,15 @@ fn test_recv(codec: &mut AdnlCodec, encrypted_packet: Vec<u8>, expected_data: Ve
     assert_eq!(data, expected_data.as_slice(), "incoming packet is wrong");
 }
 
-#[tokio::test]
-async fn integrity_test() {
+#[cfg(feature = "tokio")]
+/// Spawn ADNL echo server, returns its public key and port
+async fn spawn_echo_server() -> (PublicKey, u16) {
     let keypair = KeyPair::generate(&mut OsRng);
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let port = listener.local_addr().unwrap().port();
-    let server_public = keypair.public_key;
     tokio::spawn(async move {
         loop {
             let (socket, _) = listener.accept().await.unwrap();
-            let keypair = keypair.clone();
             tokio::spawn(async move {
                 let mut adnl_server = AdnlPeer::handle_handshake(socket, |_| Some(keypair))
                     .await
@@ -189,6 +228,13 @@ async fn integrity_test() {
             });
         }
     });
+    (keypair.public_key, port)
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn integrity_test() {
+    let (server_public, port) = spawn_echo_server().await;
 
     // act as a client: connect to ADNL server and perform handshake
     let mut client = AdnlPeer::connect(server_public.as_bytes(), ("127.0.0.1", port))
@@ -207,3 +253,3307 @@ async fn integrity_test() {
 
     assert_eq!(result, "hello".as_bytes());
 }
+
+#[cfg(feature = "tokio")]
+/// Client and server connected over TCP, incoming datagrams of the server are limited
+/// by `limiter` if given. Returns `(client, server)`.
+async fn tcp_pair(
+    limiter: Option<(&AdnlRateLimiter, RateLimitScope)>,
+) -> (AdnlPeer<TcpStream>, AdnlPeer<TcpStream>) {
+    let keypair = KeyPair::generate(&mut OsRng);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = async {
+        let (socket, _) = listener.accept().await.unwrap();
+        let server = AdnlPeer::handle_handshake(socket, |_| Some(keypair))
+            .await
+            .expect("handshake failed");
+        match limiter {
+            Some((limiter, scope)) => server.with_rate_limiter(limiter, scope),
+            None => server,
+        }
+    };
+    let client = AdnlPeer::connect(keypair.public_key.as_bytes(), ("127.0.0.1", port));
+    let (server, client) = tokio::join!(server, client);
+    (client.expect("adnl connect"), server)
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn rate_limit_disconnect() {
+    let limiter = AdnlRateLimiter::new(
+        AdnlRateLimits::default()
+            .with_frames_per_second(2)
+            .with_action(RateLimitAction::Disconnect),
+    );
+    let (mut client, mut server) = tcp_pair(Some((&limiter, RateLimitScope::Connection))).await;
+    for _ in 0..4 {
+        client.send("hello".as_bytes().into()).await.expect("send");
+    }
+    assert_eq!(server.next().await.unwrap().unwrap(), "hello".as_bytes());
+    assert_eq!(server.next().await.unwrap().unwrap(), "hello".as_bytes());
+    assert!(matches!(
+        server.next().await,
+        Some(Err(AdnlError::RateLimited))
+    ));
+    // the rest is not delivered and the connection is closed
+    assert!(server.next().await.is_none());
+    assert!(server.next().await.is_none());
+    assert!(client.next().await.is_none());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn rate_limit_throttle() {
+    let limiter = AdnlRateLimiter::new(AdnlRateLimits::default().with_frames_per_second(10));
+    let (mut client, mut server) = tcp_pair(Some((&limiter, RateLimitScope::Connection))).await;
+    let started_at = std::time::Instant::now();
+    for _ in 0..12 {
+        client.send("hello".as_bytes().into()).await.expect("send");
+    }
+    for _ in 0..12 {
+        assert_eq!(server.next().await.unwrap().unwrap(), "hello".as_bytes());
+    }
+    // two datagrams over the burst must be delayed by 100ms each
+    assert!(started_at.elapsed() >= std::time::Duration::from_millis(150));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn rate_limit_shared_ip() {
+    let limiter = AdnlRateLimiter::new(
+        AdnlRateLimits::default()
+            .with_frames_per_second(2)
+            .with_action(RateLimitAction::Disconnect),
+    );
+    let scope = RateLimitScope::Ip([127, 0, 0, 1].into());
+    let (mut client1, mut server1) = tcp_pair(Some((&limiter, scope.clone()))).await;
+    let (mut client2, mut server2) = tcp_pair(Some((&limiter, scope))).await;
+    client1.send("hello".as_bytes().into()).await.expect("send");
+    client1.send("hello".as_bytes().into()).await.expect("send");
+    client2.send("hello".as_bytes().into()).await.expect("send");
+    assert!(server1.next().await.unwrap().is_ok());
+    assert!(server1.next().await.unwrap().is_ok());
+    assert!(matches!(
+        server2.next().await,
+        Some(Err(AdnlError::RateLimited))
+    ));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn rate_limit_datagram_length() {
+    let limiter = AdnlRateLimiter::new(AdnlRateLimits::default().with_max_datagram_length(1024));
+    let (mut client, mut server) = tcp_pair(Some((&limiter, RateLimitScope::Connection))).await;
+    client.send(vec![0u8; 4096].into()).await.expect("send");
+    assert!(matches!(
+        server.next().await,
+        Some(Err(AdnlError::TooLongPacket))
+    ));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn outbound_queue_priority() {
+    let (mut client, mut server) = tcp_pair(None).await;
+    for _ in 0..3 {
+        server
+            .feed_with_priority("bulk".as_bytes().into(), AdnlPriority::Bulk)
+            .await
+            .expect("feed");
+    }
+    server
+        .feed_with_priority("ping".as_bytes().into(), AdnlPriority::Control)
+        .await
+        .expect("feed");
+    let stats = server.outbound_queue_stats();
+    assert_eq!((stats.control, stats.normal, stats.bulk), (1, 0, 3));
+    assert_eq!(stats.bytes, 16);
+
+    server.flush().await.expect("flush");
+    assert!(server.outbound_queue_stats().is_empty());
+    assert_eq!(client.next().await.unwrap().unwrap(), "ping".as_bytes());
+    for _ in 0..3 {
+        assert_eq!(client.next().await.unwrap().unwrap(), "bulk".as_bytes());
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn outbound_queue_large_frames() {
+    let (mut client, mut server) = tcp_pair(None).await;
+    for i in 0..4 {
+        server
+            .feed_with_priority(vec![i; 100_000].into(), AdnlPriority::Bulk)
+            .await
+            .expect("feed");
+    }
+    server
+        .send_with_priority("ping".as_bytes().into(), AdnlPriority::Control)
+        .await
+        .expect("send");
+    assert_eq!(client.next().await.unwrap().unwrap(), "ping".as_bytes());
+    for i in 0..4 {
+        assert_eq!(client.next().await.unwrap().unwrap(), vec![i; 100_000]);
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn outbound_queue_bytes() {
+    let (mut client, server) = tcp_pair(None).await;
+    let mut server = server.with_outbound_queue_bytes(250_000);
+    for i in 0..4 {
+        server.feed(vec![i; 100_000].into()).await.expect("feed");
+        assert!(server.outbound_queue_stats().bytes <= 300_000);
+    }
+    // the first datagram went to the transport to make room, the rest is still queued
+    assert_eq!(server.outbound_queue_stats().normal, 3);
+    server
+        .send_with_priority("ping".as_bytes().into(), AdnlPriority::Control)
+        .await
+        .expect("send");
+    // one more datagram is written to make room for ping, which then overtakes the rest
+    assert_eq!(client.next().await.unwrap().unwrap(), vec![0; 100_000]);
+    assert_eq!(client.next().await.unwrap().unwrap(), vec![1; 100_000]);
+    assert_eq!(client.next().await.unwrap().unwrap(), "ping".as_bytes());
+    for i in 2..4 {
+        assert_eq!(client.next().await.unwrap().unwrap(), vec![i; 100_000]);
+    }
+}
+
+#[cfg(feature = "tokio")]
+/// Minimal SOCKS5 proxy stand-in which requires `user:pass` credentials and supports only IPv4 targets
+async fn spawn_socks5_proxy() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut greeting = [0u8; 2];
+        socket.read_exact(&mut greeting).await.unwrap();
+        let mut methods = vec![0u8; greeting[1] as usize];
+        socket.read_exact(&mut methods).await.unwrap();
+        assert!(methods.contains(&2), "username/password must be offered");
+        socket.write_all(&[5, 2]).await.unwrap();
+
+        let mut auth = [0u8; 2];
+        socket.read_exact(&mut auth).await.unwrap();
+        let mut username = vec![0u8; auth[1] as usize];
+        socket.read_exact(&mut username).await.unwrap();
+        let mut password = vec![0u8; socket.read_u8().await.unwrap() as usize];
+        socket.read_exact(&mut password).await.unwrap();
+        let status = if (&username[..], &password[..]) == (b"user", b"pass") {
+            0
+        } else {
+            1
+        };
+        socket.write_all(&[1, status]).await.unwrap();
+        if status != 0 {
+            return;
+        }
+
+        let mut request = [0u8; 10];
+        socket.read_exact(&mut request).await.unwrap();
+        assert_eq!(&request[..4], &[5, 1, 0, 1]);
+        let ip = std::net::Ipv4Addr::new(request[4], request[5], request[6], request[7]);
+        let target_port = u16::from_be_bytes([request[8], request[9]]);
+        let mut upstream = TcpStream::connect((ip, target_port)).await.unwrap();
+        socket
+            .write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0])
+            .await
+            .unwrap();
+        let _ = tokio::io::copy_bidirectional(&mut socket, &mut upstream).await;
+    });
+    port
+}
+
+#[cfg(feature = "tokio")]
+/// Minimal HTTP CONNECT proxy stand-in without authentication
+async fn spawn_http_proxy() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(socket.read_u8().await.unwrap());
+        }
+        let head = String::from_utf8(head).unwrap();
+        let target = head
+            .strip_prefix("CONNECT ")
+            .and_then(|x| x.split_whitespace().next())
+            .unwrap()
+            .to_string();
+        let mut upstream = TcpStream::connect(target).await.unwrap();
+        socket
+            .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+            .await
+            .unwrap();
+        let _ = tokio::io::copy_bidirectional(&mut socket, &mut upstream).await;
+    });
+    port
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn connect_via_socks5() {
+    let (server_public, port) = spawn_echo_server().await;
+    let proxy_port = spawn_socks5_proxy().await;
+    let proxy =
+        AdnlProxy::socks5(format!("127.0.0.1:{}", proxy_port)).with_credentials("user", "pass");
+    let mut client = AdnlPeer::connect_via_proxy(
+        server_public.as_bytes(),
+        &format!("127.0.0.1:{}", port),
+        &proxy,
+    )
+    .await
+    .expect("adnl connect");
+    client.send("hello".as_bytes().into()).await.expect("send");
+    assert_eq!(client.next().await.unwrap().unwrap(), "hello".as_bytes());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn connect_via_socks5_bad_credentials() {
+    let (server_public, port) = spawn_echo_server().await;
+    let proxy_port = spawn_socks5_proxy().await;
+    let proxy =
+        AdnlProxy::socks5(format!("127.0.0.1:{}", proxy_port)).with_credentials("user", "wrong");
+    let result = AdnlPeer::connect_via_proxy(
+        server_public.as_bytes(),
+        &format!("127.0.0.1:{}", port),
+        &proxy,
+    )
+    .await;
+    assert!(matches!(result, Err(AdnlError::ProxyError(_))));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn connect_via_http_proxy() {
+    let (server_public, port) = spawn_echo_server().await;
+    let proxy_port = spawn_http_proxy().await;
+    let proxy = AdnlProxy::http_connect(format!("127.0.0.1:{}", proxy_port));
+    let mut client = AdnlPeer::connect_via_proxy(
+        server_public.as_bytes(),
+        &format!("127.0.0.1:{}", port),
+        &proxy,
+    )
+    .await
+    .expect("adnl connect");
+    client.send("hello".as_bytes().into()).await.expect("send");
+    assert_eq!(client.next().await.unwrap().unwrap(), "hello".as_bytes());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn pair_echo() {
+    let (mut client, mut server) = AdnlPeer::pair().await.expect("pair");
+    assert_eq!(
+        client.connection_info().remote_address(),
+        server.connection_info().local_address()
+    );
+    client.send("hello".as_bytes().into()).await.expect("send");
+    let packet = server.next().await.unwrap().unwrap();
+    server.send(packet).await.expect("send");
+    assert_eq!(client.next().await.unwrap().unwrap(), "hello".as_bytes());
+}
+
+#[cfg(feature = "tokio")]
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_socket_echo() {
+    let keypair = KeyPair::generate(&mut OsRng);
+    let path = std::env::temp_dir().join(format!(
+        "adnl-{}.sock",
+        hex::encode(rand::random::<[u8; 8]>())
+    ));
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+    tokio::spawn(async move {
+        let mut server = AdnlPeer::accept_unix(&listener, |_| Some(keypair))
+            .await
+            .expect("handshake failed");
+        while let Some(Ok(packet)) = server.next().await {
+            let _ = server.send(packet).await;
+        }
+    });
+    let mut client = AdnlPeer::connect_unix(keypair.public_key.as_bytes(), &path)
+        .await
+        .expect("adnl connect");
+    client.send("hello".as_bytes().into()).await.expect("send");
+    assert_eq!(client.next().await.unwrap().unwrap(), "hello".as_bytes());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "websocket")]
+#[tokio::test]
+async fn websocket_bridge_echo() {
+    let (server_public, port) = spawn_echo_server().await;
+    let bridge = AdnlWsBridge::bind("127.0.0.1:0", ([127, 0, 0, 1], port).into())
+        .await
+        .expect("bind");
+    let bridge_address = bridge.local_addr().unwrap();
+    tokio::spawn(bridge.run());
+    let mut client = AdnlPeer::connect_ws(
+        server_public.as_bytes(),
+        &format!("ws://{}", bridge_address),
+    )
+    .await
+    .expect("adnl connect");
+    client.send("hello".as_bytes().into()).await.expect("send");
+    assert_eq!(client.next().await.unwrap().unwrap(), "hello".as_bytes());
+}
+
+/// Server side of a blocking connection built from the raw codec, so that it does not
+/// need any async runtime
+#[cfg(feature = "blocking")]
+struct BlockingServer {
+    socket: std::net::TcpStream,
+    codec: AdnlCodec,
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingServer {
+    fn accept(listener: &std::net::TcpListener, keypair: KeyPair) -> Self {
+        use std::io::Read;
+
+        let (mut socket, _) = listener.accept().unwrap();
+        let mut packet = [0u8; 256];
+        socket.read_exact(&mut packet).unwrap();
+        let handshake =
+            AdnlHandshake::decrypt_from_raw(&packet, |_| Some(keypair)).expect("handshake failed");
+        let mut server = Self {
+            socket,
+            codec: handshake.make_server_codec(),
+        };
+        server.send(&[]);
+        server
+    }
+
+    fn send(&mut self, data: &[u8]) {
+        use std::io::Write;
+
+        let mut packet = vec![0u8; AdnlCodec::packet_length(data.len())];
+        self.codec.encrypt_packet(data, &mut packet).unwrap();
+        self.socket.write_all(&packet).unwrap();
+    }
+
+    fn receive(&mut self) -> Vec<u8> {
+        use std::io::Read;
+
+        let mut header = [0u8; 4];
+        self.socket.read_exact(&mut header).unwrap();
+        let mut packet = vec![0u8; self.codec.decrypt_length(header).unwrap()];
+        self.socket.read_exact(&mut packet).unwrap();
+        self.codec.decrypt_packet(&mut packet).unwrap().to_vec()
+    }
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn blocking_client_query() {
+    let keypair = KeyPair::generate(&mut OsRng);
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let mut server = BlockingServer::accept(&listener, keypair);
+        // plain datagram is echoed back
+        let packet = server.receive();
+        server.send(&packet);
+        // adnl.message.query is answered with the query itself
+        let mut packet = server.receive();
+        assert_eq!(&packet[..4], &[0x7a, 0xf9, 0x8b, 0xb4]);
+        packet[..4].copy_from_slice(&[0x16, 0x84, 0xac, 0x0f]);
+        server.send(b"unrelated");
+        server.send(&packet);
+    });
+
+    let mut client = AdnlBlockingClient::connect_timeout(
+        keypair.public_key.as_bytes(),
+        &([127, 0, 0, 1], port).into(),
+        std::time::Duration::from_secs(5),
+    )
+    .expect("adnl connect");
+    client.send(b"hello").expect("send");
+    assert_eq!(client.receive().expect("receive"), b"hello");
+    assert_eq!(client.query(b"ping").expect("query"), b"ping");
+    server.join().unwrap();
+}
+
+#[cfg(feature = "futures-io")]
+#[test]
+fn futures_io_echo() {
+    smol::block_on(async {
+        let keypair = KeyPair::generate(&mut OsRng);
+        let listener = smol::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        smol::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut server = AdnlFuturesPeer::handle_handshake(socket, |_| Some(keypair))
+                .await
+                .expect("handshake failed");
+            while let Some(Ok(packet)) = server.next().await {
+                let _ = server.send(packet).await;
+            }
+        })
+        .detach();
+
+        let socket = smol::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        let mut client = AdnlFuturesPeer::perform_handshake(socket, keypair.public_key.as_bytes())
+            .await
+            .expect("adnl connect");
+        client.send("hello".as_bytes().into()).await.expect("send");
+        client.send(vec![7u8; 100_000].into()).await.expect("send");
+        assert_eq!(client.next().await.unwrap().unwrap(), "hello".as_bytes());
+        assert_eq!(client.next().await.unwrap().unwrap(), vec![7u8; 100_000]);
+    });
+}
+
+#[derive(Debug, PartialEq, tl::TlWrite, tl::TlRead)]
+#[tl(
+    boxed,
+    schema = "adnl.message.query query_id:int256 query:bytes = adnl.Message"
+)]
+struct TestMessageQuery {
+    query_id: [u8; 32],
+    query: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, tl::TlWrite, tl::TlRead)]
+#[tl(schema = "liteServer.query data:bytes = Object")]
+struct TestLiteQuery {
+    data: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, tl::TlWrite, tl::TlRead)]
+#[tl(schema = "liteServer.getTime = liteServer.CurrentTime")]
+struct TestGetTime;
+
+#[test]
+fn tl_constructor_ids() {
+    use tl::TlConstructor;
+    assert_eq!(TestMessageQuery::TL_ID, 0xb48bf97a);
+    assert_eq!(TestLiteQuery::TL_ID, 0x798c06df);
+    assert_eq!(TestGetTime::TL_ID, 0x16ad5a34);
+}
+
+#[test]
+fn tl_serialize_query() {
+    // the same query as in `examples/time.rs`
+    let expected = hex::decode("7af98bb435263e6c95d6fecb497dfd0aa5f031e7d412986b5ce720496db512052e8f2d100cdf068c7904345aad16000000000000").unwrap();
+    let query = TestMessageQuery {
+        query_id: expected[4..36].try_into().unwrap(),
+        query: tl::serialize(&tl::Boxed(TestLiteQuery {
+            data: tl::serialize(&tl::Boxed(TestGetTime)),
+        })),
+    };
+    assert_eq!(tl::serialize(&query), expected);
+    assert_eq!(tl::deserialize::<TestMessageQuery>(&expected), Ok(query));
+    assert_eq!(
+        tl::deserialize::<tl::Boxed<TestGetTime>>(&[0, 0, 0, 0]),
+        Err(tl::TlError::UnknownConstructor(0))
+    );
+}
+
+#[test]
+fn tl_bytes_padding() {
+    for (length, serialized) in [(0, 4), (3, 4), (4, 8), (253, 256), (254, 260), (1000, 1004)] {
+        let data = vec![0xab; length];
+        let packet = tl::serialize(&data);
+        assert_eq!(packet.len(), serialized, "length {}", length);
+        assert_eq!(tl::deserialize::<Vec<u8>>(&packet).unwrap(), data);
+    }
+    assert_eq!(tl::serialize("abc"), b"\x03abc");
+    let data = vec![0; tl::MAX_BYTES_LEN];
+    assert_eq!(tl::check_bytes_len(&data), Ok(()));
+    assert_eq!(tl::serialize(&data).len(), tl::MAX_BYTES_LEN + 5);
+    assert_eq!(
+        tl::check_bytes_len(&vec![0; tl::MAX_BYTES_LEN + 1]),
+        Err(tl::TlError::TooLong)
+    );
+    assert_eq!(
+        tl::deserialize::<Vec<u8>>(b"\x05abc"),
+        Err(tl::TlError::UnexpectedEof)
+    );
+    assert_eq!(
+        tl::deserialize::<i32>(&[1, 0, 0, 0, 0]),
+        Err(tl::TlError::TrailingBytes)
+    );
+}
+
+#[derive(Debug, PartialEq, tl::TlWrite, tl::TlRead)]
+#[tl(boxed)]
+enum TestMessage {
+    #[tl(schema = "test.empty = test.Message")]
+    Empty,
+    #[tl(
+        schema = "test.flagged mode:# value:mode.0?int ok:mode.2?true items:vector long = test.Message"
+    )]
+    Flagged {
+        #[tl(flags)]
+        mode: u32,
+        #[tl(flags_bit = "mode.0")]
+        value: Option<i32>,
+        #[tl(flags_bit = "mode.2")]
+        ok: bool,
+        items: Vec<i64>,
+    },
+    Query(TestLiteQuery),
+}
+
+#[test]
+fn tl_enum_and_flags() {
+    let message = TestMessage::Flagged {
+        mode: 0b1010,
+        value: Some(-1),
+        ok: false,
+        items: vec![1, 2],
+    };
+    let packet = tl::serialize(&message);
+    assert_eq!(packet[4..8], 0b1011u32.to_le_bytes());
+    assert_eq!(packet.len(), 4 + 4 + 4 + 4 + 16);
+    let TestMessage::Flagged {
+        mode, value, ok, ..
+    } = tl::deserialize(&packet).unwrap()
+    else {
+        panic!("wrong variant");
+    };
+    assert_eq!((mode, value, ok), (0b1011, Some(-1), false));
+
+    for message in [
+        TestMessage::Empty,
+        TestMessage::Query(TestLiteQuery { data: vec![1; 5] }),
+    ] {
+        assert_eq!(tl::deserialize(&tl::serialize(&message)), Ok(message));
+    }
+    assert_eq!(
+        tl::serialize(&TestMessage::Query(TestLiteQuery { data: vec![] }))[..4],
+        0x798c06dfu32.to_le_bytes()
+    );
+    assert_eq!(tl::serialize(&true), 0x997275b5u32.to_le_bytes());
+}
+
+#[test]
+fn tl_generated_bindings() {
+    use tl::ton::{adnl, lite_server};
+    use tl::TlFunction;
+
+    let expected = hex::decode("7af98bb435263e6c95d6fecb497dfd0aa5f031e7d412986b5ce720496db512052e8f2d100cdf068c7904345aad16000000000000").unwrap();
+    let message = adnl::Message::MessageQuery(adnl::message::Query {
+        query_id: expected[4..36].try_into().unwrap(),
+        query: tl::serialize(&lite_server::Query {
+            data: tl::serialize(&lite_server::GetTime),
+        }),
+    });
+    assert_eq!(tl::serialize(&message), expected);
+
+    fn parse_reply<F: TlFunction>(_: &F, packet: &[u8]) -> tl::TlResult<F::Reply> {
+        tl::deserialize(packet)
+    }
+    let reply = parse_reply(
+        &lite_server::GetTime,
+        &hex::decode("0d0053e901000000").unwrap(),
+    );
+    assert_eq!(reply, Ok(tl::Boxed(lite_server::CurrentTime { now: 1 })));
+}
+
+#[cfg(feature = "tokio")]
+/// Spawn stub liteserver which answers every `liteServer.query` with `handler(data)`,
+/// returns its public key and port
+async fn spawn_lite_server<F>(handler: F) -> (PublicKey, u16)
+where
+    F: Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+{
+    spawn_slow_lite_server(std::time::Duration::ZERO, handler).await
+}
+
+#[cfg(feature = "tokio")]
+/// Liteserver which answers each query after `delay`
+async fn spawn_slow_lite_server<F>(delay: std::time::Duration, handler: F) -> (PublicKey, u16)
+where
+    F: Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+{
+    use tl::ton::{adnl, lite_server};
+
+    let keypair = KeyPair::generate(&mut OsRng);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handler = std::sync::Arc::new(handler);
+    tokio::spawn(async move {
+        loop {
+            let (socket, _) = listener.accept().await.unwrap();
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                let mut server = AdnlPeer::handle_handshake(socket, |_| Some(keypair))
+                    .await
+                    .expect("handshake failed");
+                while let Some(Ok(packet)) = server.next().await {
+                    let Ok(adnl::Message::MessageQuery(query)) = tl::deserialize(&packet) else {
+                        continue;
+                    };
+                    let lite_server::Query { data } = tl::deserialize(&query.query).unwrap();
+                    tokio::time::sleep(delay).await;
+                    let answer = adnl::Message::MessageAnswer(adnl::message::Answer {
+                        query_id: query.query_id,
+                        answer: handler(&data),
+                    });
+                    // client must skip datagrams which are not answers to its query
+                    let _ = server.send("unrelated".as_bytes().into()).await;
+                    let _ = server.send(tl::serialize(&answer).into()).await;
+                }
+            });
+        }
+    });
+    (keypair.public_key, port)
+}
+
+fn test_block_id(seqno: i32) -> tl::ton::ton_node::BlockIdExt {
+    tl::ton::ton_node::BlockIdExt {
+        workchain: -1,
+        shard: i64::MIN,
+        seqno,
+        root_hash: [1; 32],
+        file_hash: [2; 32],
+    }
+}
+
+#[cfg(feature = "tokio")]
+fn test_zero_state() -> tl::ton::ton_node::ZeroStateIdExt {
+    tl::ton::ton_node::ZeroStateIdExt {
+        workchain: -1,
+        root_hash: [3; 32],
+        file_hash: [4; 32],
+    }
+}
+
+#[cfg(feature = "tokio")]
+fn lite_server_stub(mut query: &[u8]) -> Vec<u8> {
+    use tl::ton::lite_server::*;
+    use tl::{Boxed, TlConstructor, TlRead};
+
+    // stub masterchain is at seqno 100 and never advances
+    while query.starts_with(&WaitMasterchainSeqno::TL_ID.to_le_bytes()) {
+        if WaitMasterchainSeqno::read_from(&mut query).unwrap().seqno > 100 {
+            return tl::serialize(&Boxed(Error {
+                code: 652,
+                message: "timeout".to_owned(),
+            }));
+        }
+    }
+    let id = u32::from_le_bytes(query[..4].try_into().unwrap());
+    match id {
+        GetTime::TL_ID => tl::serialize(&Boxed(CurrentTime { now: 1700000000 })),
+        GetVersion::TL_ID => tl::serialize(&Boxed(Version {
+            mode: 0,
+            version: 0x101,
+            capabilities: 7,
+            now: 1700000000,
+        })),
+        GetMasterchainInfo::TL_ID => tl::serialize(&Boxed(MasterchainInfo {
+            last: test_block_id(100),
+            state_root_hash: [5; 32],
+            init: test_zero_state(),
+        })),
+        GetMasterchainInfoExt::TL_ID => {
+            let GetMasterchainInfoExt { mode } = tl::deserialize(query).unwrap();
+            tl::serialize(&Boxed(MasterchainInfoExt {
+                mode,
+                version: 0x101,
+                capabilities: 7,
+                last: test_block_id(100),
+                last_utime: 1699999999,
+                now: 1700000000,
+                state_root_hash: [5; 32],
+                init: test_zero_state(),
+            }))
+        }
+        GetAccountState::TL_ID | GetAccountStatePrunned::TL_ID => {
+            // both queries have the same fields
+            let mut data = query.to_vec();
+            data[..4].copy_from_slice(&GetAccountState::TL_ID.to_le_bytes());
+            let GetAccountState { id, account } = tl::deserialize(&data).unwrap();
+            tl::serialize(&Boxed(AccountState {
+                id: id.clone(),
+                shardblk: id,
+                shard_proof: vec![1],
+                proof: vec![2],
+                state: account.id.to_vec(),
+            }))
+        }
+        RunSmcMethod::TL_ID => {
+            let query: RunSmcMethod = tl::deserialize(query).unwrap();
+            tl::serialize(&Boxed(RunMethodResult {
+                mode: query.mode,
+                id: query.id.clone(),
+                shardblk: query.id,
+                shard_proof: Some(vec![1]),
+                proof: Some(vec![2]),
+                state_proof: Some(vec![3]),
+                init_c7: None,
+                lib_extras: None,
+                exit_code: 0,
+                result: Some(query.method_id.to_le_bytes().to_vec()),
+            }))
+        }
+        GetBlock::TL_ID => {
+            let GetBlock { id } = tl::deserialize(query).unwrap();
+            tl::serialize(&Boxed(BlockData {
+                id,
+                data: vec![0xb5, 0xee, 0x9c, 0x72],
+            }))
+        }
+        GetBlockHeader::TL_ID => {
+            let GetBlockHeader { id, mode } = tl::deserialize(query).unwrap();
+            tl::serialize(&Boxed(BlockHeader {
+                id,
+                mode,
+                header_proof: vec![1],
+            }))
+        }
+        LookupBlock::TL_ID => {
+            let query: LookupBlock = tl::deserialize(query).unwrap();
+            // lt and utime are mapped to seqno, so that the test can check them
+            let seqno = match (query.mode, query.lt, query.utime) {
+                (0b001, None, None) => query.id.seqno,
+                (0b010, Some(lt), None) => (lt / 1000) as i32,
+                (0b100, None, Some(utime)) => utime - 1700000000,
+                _ => panic!("invalid lookupBlock {query:?}"),
+            };
+            tl::serialize(&Boxed(BlockHeader {
+                id: test_block_id(seqno),
+                mode: query.mode,
+                header_proof: Vec::new(),
+            }))
+        }
+        ListBlockTransactions::TL_ID => {
+            let query: ListBlockTransactions = tl::deserialize(query).unwrap();
+            let after = query.after.map_or(0, |after| after.lt);
+            let ids = (1..=query.count as i64)
+                .map(|i| TransactionId {
+                    mode: 0b111,
+                    account: Some([7; 32]),
+                    lt: Some(after + i),
+                    hash: Some([8; 32]),
+                    metadata: None,
+                })
+                .collect();
+            tl::serialize(&Boxed(BlockTransactions {
+                id: query.id,
+                req_count: query.count,
+                incomplete: query.reverse_order,
+                ids,
+                proof: Vec::new(),
+            }))
+        }
+        ListBlockTransactionsExt::TL_ID => {
+            let query: ListBlockTransactionsExt = tl::deserialize(query).unwrap();
+            tl::serialize(&Boxed(BlockTransactionsExt {
+                id: query.id,
+                req_count: query.count,
+                incomplete: false,
+                transactions: vec![0xb5, 0xee, 0x9c, 0x72],
+                proof: Vec::new(),
+            }))
+        }
+        GetTransactions::TL_ID => {
+            let query: GetTransactions = tl::deserialize(query).unwrap();
+            tl::serialize(&Boxed(TransactionList {
+                ids: (0..query.count as i32).map(test_block_id).collect(),
+                transactions: query.hash.to_vec(),
+            }))
+        }
+        GetOneTransaction::TL_ID => {
+            let GetOneTransaction { id, lt, .. } = tl::deserialize(query).unwrap();
+            tl::serialize(&Boxed(TransactionInfo {
+                id,
+                proof: Vec::new(),
+                transaction: lt.to_le_bytes().to_vec(),
+            }))
+        }
+        _ => panic!("unexpected query {id:#010x}"),
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_queries() {
+    let (server_public, port) = spawn_lite_server(lite_server_stub).await;
+    let mut client = LiteClient::connect(server_public.as_bytes(), ("127.0.0.1", port))
+        .await
+        .expect("connect");
+
+    assert_eq!(client.get_time().await.unwrap(), 1700000000);
+    let version = client.get_version().await.unwrap();
+    assert_eq!((version.version, version.capabilities), (0x101, 7));
+    let info = client.get_masterchain_info().await.unwrap();
+    assert_eq!(info.last, test_block_id(100));
+    assert_eq!(info.init, test_zero_state());
+    let info = client.get_masterchain_info_ext(0).await.unwrap();
+    assert_eq!((info.last.seqno, info.last_utime), (100, 1699999999));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_timeout() {
+    let (client, _server) = AdnlPeer::pair().await.expect("pair");
+    let mut client = LiteClient::new(client).with_timeout(std::time::Duration::from_millis(50));
+    assert!(matches!(client.get_time().await, Err(LiteError::Timeout)));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_account_queries() {
+    use tl::ton::lite_server::AccountId;
+
+    assert_eq!(smc_method_id("seqno"), 85143);
+    let (server_public, port) = spawn_lite_server(lite_server_stub).await;
+    let mut client = LiteClient::connect(server_public.as_bytes(), ("127.0.0.1", port))
+        .await
+        .expect("connect");
+    let account = AccountId {
+        workchain: 0,
+        id: [7; 32],
+    };
+
+    let state = client
+        .get_account_state(test_block_id(100), account.clone())
+        .await
+        .unwrap();
+    assert_eq!(state.id, test_block_id(100));
+    assert_eq!((state.proof, state.state), (vec![2], vec![7; 32]));
+    let state = client
+        .get_account_state_prunned(test_block_id(101), account.clone())
+        .await
+        .unwrap();
+    assert_eq!(state.shardblk, test_block_id(101));
+
+    let result = client
+        .run_smc_method(test_block_id(100), account, "seqno", Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(result.mode, 0b111);
+    assert_eq!(result.state_proof, Some(vec![3]));
+    assert_eq!(result.result, Some(85143i64.to_le_bytes().to_vec()));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_send_message() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tl::ton::lite_server::*;
+    use tl::{Boxed, TlConstructor};
+
+    let message = |text: &[u8]| boc::Cell::new(text.to_vec(), text.len() * 8, Vec::new(), false);
+    let (lost, good, other) = (
+        message(b"lost").unwrap(),
+        message(b"good").unwrap(),
+        message(b"other").unwrap(),
+    );
+    // "lost" message is never processed, but another one is; "good" one is followed by
+    // another message before the client sees it
+    let first = test_message_transaction(10, None, None);
+    let second = test_message_transaction(20, Some(&first), Some(&other));
+    let third = test_message_transaction(30, Some(&second), Some(&good));
+    let fourth = test_message_transaction(40, Some(&third), Some(&other));
+    let chain = alloc::vec![fourth.clone(), third.clone(), second.clone(), first.clone()];
+    let stages = [(10, &first), (20, &second), (40, &fourth)].map(|(lt, last)| {
+        let fixture = proof_fixture_with_last_transaction((lt, last.hash()));
+        (
+            fixture.mc_id.clone(),
+            test_account_state(&fixture, &fixture.account),
+        )
+    });
+    let shardblk = stages[2].1.shardblk.clone();
+    let stage = Arc::new(AtomicUsize::new(0));
+    let handler = {
+        let stage = stage.clone();
+        let (lost, good) = (boc::serialize(&lost), boc::serialize(&good));
+        move |query: &[u8]| {
+            let (mc_id, account_state) = &stages[stage.load(Ordering::SeqCst)];
+            match u32::from_le_bytes(query[..4].try_into().unwrap()) {
+                SendMessage::TL_ID => match tl::deserialize(query).unwrap() {
+                    SendMessage { body } if body == b"bad" => tl::serialize(&Boxed(Error {
+                        code: 0,
+                        message: "cannot apply external message".to_owned(),
+                    })),
+                    SendMessage { body } if body == b"rejected" => {
+                        tl::serialize(&Boxed(SendMsgStatus { status: 0 }))
+                    }
+                    SendMessage { body } => {
+                        stage.store(1 + (body == good) as usize, Ordering::SeqCst);
+                        assert!(body == good || body == lost);
+                        tl::serialize(&Boxed(SendMsgStatus { status: 1 }))
+                    }
+                },
+                GetMasterchainInfo::TL_ID => tl::serialize(&Boxed(MasterchainInfo {
+                    last: mc_id.clone(),
+                    state_root_hash: [5; 32],
+                    init: test_zero_state(),
+                })),
+                GetMasterchainInfoExt::TL_ID => tl::serialize(&Boxed(MasterchainInfoExt {
+                    mode: 0,
+                    version: 0x101,
+                    capabilities: 7,
+                    last: mc_id.clone(),
+                    last_utime: 1699999999,
+                    now: 1700000000,
+                    state_root_hash: [5; 32],
+                    init: test_zero_state(),
+                })),
+                GetAccountStatePrunned::TL_ID => tl::serialize(&Boxed(account_state.clone())),
+                GetTransactions::TL_ID => {
+                    let GetTransactions { count, hash, .. } = tl::deserialize(query).unwrap();
+                    let start = chain.iter().position(|t| t.hash() == hash).unwrap();
+                    let transactions = chain[start..]
+                        .iter()
+                        .take(count as usize)
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    tl::serialize(&Boxed(TransactionList {
+                        ids: alloc::vec![account_state.shardblk.clone(); transactions.len()],
+                        transactions: boc::BocSerializer::new().serialize(&transactions),
+                    }))
+                }
+                _ => lite_server_stub(query),
+            }
+        }
+    };
+    let (server_public, port) = spawn_lite_server(handler).await;
+    let mut client = LiteClient::connect(server_public.as_bytes(), ("127.0.0.1", port))
+        .await
+        .expect("connect");
+    let account = AccountId {
+        workchain: 0,
+        id: ACCOUNT_ONE,
+    };
+
+    match client.send_message(b"bad".to_vec()).await {
+        Err(LiteError::ServerError(e)) => {
+            assert_eq!(
+                (e.code, e.message.as_str()),
+                (0, "cannot apply external message")
+            );
+            assert!(!e.is_retryable());
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+    assert!(matches!(
+        client.send_message(b"rejected".to_vec()).await,
+        Err(LiteError::MessageRejected(0))
+    ));
+    assert!(matches!(
+        client.send_message(vec![0; tl::MAX_BYTES_LEN + 1]).await,
+        Err(LiteError::TlError(tl::TlError::TooLong))
+    ));
+    let timeout = std::time::Duration::from_secs(10);
+    assert!(matches!(
+        client
+            .send_message_and_wait(b"bad".to_vec(), account.clone(), 1700000060, timeout)
+            .await,
+        Err(LiteError::BocError(_))
+    ));
+    // stub masterchain is at 1699999999, a transaction of another message must not count
+    assert!(matches!(
+        client
+            .send_message_and_wait(boc::serialize(&lost), account.clone(), 1699999990, timeout)
+            .await,
+        Err(LiteError::MessageExpired)
+    ));
+    assert_eq!(stage.load(Ordering::SeqCst), 1);
+    // lost message which is still valid is waited for until the timeout
+    assert!(matches!(
+        client
+            .send_message_and_wait(
+                boc::serialize(&lost),
+                account.clone(),
+                1700000060,
+                std::time::Duration::from_millis(300)
+            )
+            .await,
+        Err(LiteError::Timeout)
+    ));
+    let (id, transaction) = client
+        .send_message_and_wait(boc::serialize(&good), account, 1700000060, timeout)
+        .await
+        .unwrap();
+    assert_eq!(id, shardblk);
+    assert_eq!(transaction, third);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_block_queries() {
+    use tl::ton::lite_server::{AccountId, TransactionId3};
+
+    let (server_public, port) = spawn_lite_server(lite_server_stub).await;
+    let mut client = LiteClient::connect(server_public.as_bytes(), ("127.0.0.1", port))
+        .await
+        .expect("connect");
+    let account = AccountId {
+        workchain: 0,
+        id: [7; 32],
+    };
+
+    let block = client.get_block(test_block_id(5)).await.unwrap();
+    assert_eq!(
+        (block.id, block.data),
+        (test_block_id(5), vec![0xb5, 0xee, 0x9c, 0x72])
+    );
+    let header = client.get_block_header(test_block_id(6), 1).await.unwrap();
+    assert_eq!((header.id, header.mode), (test_block_id(6), 1));
+
+    for (by, seqno) in [
+        (BlockLookup::Seqno(10), 10),
+        (BlockLookup::Lt(11000), 11),
+        (BlockLookup::Utime(1700000012), 12),
+    ] {
+        let header = client
+            .lookup_block(tl::MASTERCHAIN, tl::SHARD_FULL, by)
+            .await
+            .unwrap();
+        assert_eq!(header.id, test_block_id(seqno));
+    }
+
+    let after = TransactionId3 {
+        account: [7; 32],
+        lt: 100,
+    };
+    let transactions = client
+        .list_block_transactions(test_block_id(5), 2, Some(after), true)
+        .await
+        .unwrap();
+    assert!(transactions.incomplete);
+    let lts = transactions.ids.iter().map(|id| id.lt.unwrap());
+    assert_eq!(lts.collect::<Vec<_>>(), [101, 102]);
+    let transactions = client
+        .list_block_transactions_ext(test_block_id(5), 2, None, false)
+        .await
+        .unwrap();
+    assert_eq!(transactions.req_count, 2);
+
+    let list = client
+        .get_transactions(3, account.clone(), 100, [9; 32])
+        .await
+        .unwrap();
+    assert_eq!((list.ids.len(), list.transactions), (3, vec![9; 32]));
+    let transaction = client
+        .get_one_transaction(test_block_id(5), account, 100)
+        .await
+        .unwrap();
+    assert_eq!(transaction.transaction, 100i64.to_le_bytes());
+}
+
+#[test]
+fn block_id_display() {
+    let id = test_block_id(100);
+    assert_eq!(id.block_id().to_string(), "(-1,8000000000000000,100)");
+    assert_eq!(
+        id.to_string(),
+        format!(
+            "(-1,8000000000000000,100):{}:{}",
+            "01".repeat(32),
+            "02".repeat(32)
+        )
+    );
+    assert!(id.is_masterchain());
+    let ids = std::collections::HashSet::from([id.clone(), id, test_block_id(101)]);
+    assert_eq!(ids.len(), 2);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_prefixes() {
+    use std::time::Duration;
+
+    let (server_public, port) = spawn_lite_server(lite_server_stub).await;
+    let mut client = LiteClient::connect(server_public.as_bytes(), ("127.0.0.1", port))
+        .await
+        .expect("connect");
+    let wait = |seqno| LiteQueryPrefix::WaitMasterchainSeqno {
+        seqno,
+        timeout: Duration::from_secs(1),
+    };
+
+    let time = client
+        .query_with_prefixes(&[wait(99), wait(100)], &tl::ton::lite_server::GetTime)
+        .await
+        .unwrap();
+    assert_eq!(time.0.now, 1700000000);
+    client.set_prefixes(vec![wait(101)]);
+    assert!(matches!(
+        client.get_time().await,
+        Err(LiteError::ServerError(LiteServerError { code: 652, .. }))
+    ));
+    client.set_prefixes(Vec::new());
+    assert_eq!(client.get_time().await.unwrap(), 1700000000);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_prefix_deadline() {
+    use std::time::Duration;
+    use tl::ton::{adnl, lite_server};
+
+    let (client, mut server) = AdnlPeer::pair().await.expect("pair");
+    tokio::spawn(async move {
+        let packet = server.next().await.unwrap().unwrap();
+        let Ok(adnl::Message::MessageQuery(query)) = tl::deserialize(&packet) else {
+            panic!("unexpected datagram");
+        };
+        let lite_server::Query { data } = tl::deserialize(&query.query).unwrap();
+        let prefix = tl::serialize(&lite_server::WaitMasterchainSeqno {
+            seqno: 101,
+            timeout_ms: 500,
+        });
+        assert_eq!(&data[..prefix.len()], prefix);
+        // server waits for the block, which takes longer than client timeout
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let answer = adnl::Message::MessageAnswer(adnl::message::Answer {
+            query_id: query.query_id,
+            answer: lite_server_stub(&data),
+        });
+        server.send(tl::serialize(&answer).into()).await.unwrap();
+    });
+
+    let mut client = LiteClient::new(client).with_timeout(Duration::from_millis(100));
+    let prefix = LiteQueryPrefix::WaitMasterchainSeqno {
+        seqno: 101,
+        timeout: Duration::from_millis(500),
+    };
+    let result = client
+        .query_with_prefixes(&[prefix], &lite_server::GetTime)
+        .await;
+    let error = result.unwrap_err();
+    assert!(error.is_retryable());
+    assert!(matches!(
+        error,
+        LiteError::ServerError(LiteServerError {
+            code: LiteServerError::TIMEOUT,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn lite_server_error_decoding() {
+    use tl::ton::lite_server;
+
+    let answer = tl::serialize(&tl::Boxed(lite_server::Error {
+        code: 651,
+        message: "block is not applied".to_owned(),
+    }));
+    let error = LiteServerError::from_answer(&answer).unwrap();
+    assert_eq!(error.code, LiteServerError::NOT_READY);
+    assert!(error.is_retryable());
+    assert_eq!(
+        error.to_string(),
+        "Liteserver error 651: block is not applied"
+    );
+    let answer = tl::serialize(&tl::Boxed(lite_server::CurrentTime { now: 1 }));
+    assert_eq!(LiteServerError::from_answer(&answer), None);
+}
+
+const WALLET_V3R2_CODE: &[u8] = include_bytes!("../tests/fixtures/wallet_v3r2_code.boc");
+const WALLET_V4R2_CODE: &[u8] = include_bytes!("../tests/fixtures/wallet_v4r2_code.boc");
+
+#[test]
+fn boc_fixtures() {
+    for (boc, hash, depth) in [
+        (
+            WALLET_V3R2_CODE,
+            "84dafa449f98a6987789ba232358072bc0f76dc4524002a5d0918b9a75d2d599",
+            0,
+        ),
+        (
+            WALLET_V4R2_CODE,
+            "feb5ff6820e2ff0d9483e7e0d62c817d846789fb4ae580c878866d959dabd5c0",
+            7,
+        ),
+    ] {
+        let root = boc::parse_single(boc).unwrap();
+        assert_eq!(hex::encode(root.hash()), hash);
+        assert_eq!(root.depth(), depth);
+        assert_eq!(boc::serialize(&root), boc);
+        let with_index = boc::BocSerializer::new()
+            .with_cache_bits(true)
+            .with_crc32c(false)
+            .serialize(core::slice::from_ref(&root));
+        assert_eq!(boc::parse(&with_index).unwrap(), [root]);
+    }
+}
+
+fn pruned_branch(cell: &boc::Cell) -> boc::Cell {
+    pruned_branch_at(cell, 1)
+}
+
+/// Pruned branch of level 0 `cell` for Merkle proof with `level` nested Merkle cells
+fn pruned_branch_at(cell: &boc::Cell, level: u8) -> boc::Cell {
+    let mut data = alloc::vec![1, 1 << (level - 1)];
+    data.extend_from_slice(&cell.hash());
+    data.extend_from_slice(&cell.depth().to_be_bytes());
+    boc::Cell::new(data, 16 + 256 + 16, Vec::new(), true).unwrap()
+}
+
+fn merkle_cell(tag: u8, children: Vec<boc::Cell>) -> Result<boc::Cell, boc::BocError> {
+    let mut data = alloc::vec![tag];
+    for child in &children {
+        data.extend_from_slice(&child.hash_at(0));
+    }
+    for child in &children {
+        data.extend_from_slice(&child.depth_at(0).to_be_bytes());
+    }
+    let bit_len = 8 + children.len() * (256 + 16);
+    boc::Cell::new(data, bit_len, children, true)
+}
+
+#[test]
+fn boc_merkle_cells() {
+    let leaf = boc::Cell::new(alloc::vec![0xab, 0xc0], 10, Vec::new(), false).unwrap();
+    let hidden = boc::Cell::new(b"hidden".to_vec(), 48, alloc::vec![leaf.clone()], false).unwrap();
+    let root = boc::Cell::new(
+        b"root".to_vec(),
+        32,
+        alloc::vec![leaf.clone(), hidden.clone()],
+        false,
+    )
+    .unwrap();
+    assert_eq!(leaf.data(), [0xab, 0xc0]);
+
+    let pruned = pruned_branch(&hidden);
+    assert_eq!(pruned.cell_type(), boc::CellType::PrunedBranch);
+    assert_eq!((pruned.level(), pruned.hash_at(0)), (1, hidden.hash()));
+    assert_eq!(pruned.depth_at(0), hidden.depth());
+    let proof_root = boc::Cell::new(
+        b"root".to_vec(),
+        32,
+        alloc::vec![leaf.clone(), pruned],
+        false,
+    )
+    .unwrap();
+    assert_eq!(proof_root.level(), 1);
+    assert_eq!(proof_root.hash_at(0), root.hash());
+    assert_ne!(proof_root.hash(), root.hash());
+
+    let proof = merkle_cell(3, alloc::vec![proof_root.clone()]).unwrap();
+    assert_eq!(
+        (proof.cell_type(), proof.level()),
+        (boc::CellType::MerkleProof, 0)
+    );
+    let parsed = boc::parse_single(&boc::serialize(&proof)).unwrap();
+    assert_eq!(parsed, proof);
+    assert_eq!(parsed.references()[0].hash_at(0), root.hash());
+
+    let update = merkle_cell(4, alloc::vec![proof_root, root.clone()]).unwrap();
+    assert_eq!(
+        (update.cell_type(), update.level()),
+        (boc::CellType::MerkleUpdate, 0)
+    );
+    assert_eq!(boc::parse_single(&boc::serialize(&update)).unwrap(), update);
+
+    let mut data = alloc::vec![3];
+    data.extend_from_slice(&leaf.hash());
+    data.extend_from_slice(&root.depth().to_be_bytes());
+    assert_eq!(
+        boc::Cell::new(data, 8 + 256 + 16, alloc::vec![root], true),
+        Err(boc::BocError::InvalidCell("merkle hash mismatch"))
+    );
+}
+
+#[test]
+fn boc_invalid() {
+    let boc = WALLET_V3R2_CODE;
+    assert_eq!(boc::parse(&boc[1..]), Err(boc::BocError::InvalidMagic));
+    assert_eq!(
+        boc::parse(&boc[..boc.len() - 10]),
+        Err(boc::BocError::InvalidHeader)
+    );
+    let mut corrupted = boc.to_vec();
+    corrupted[20] ^= 1;
+    assert_eq!(boc::parse(&corrupted), Err(boc::BocError::InvalidChecksum));
+    let mut trailing = boc.to_vec();
+    trailing.push(0);
+    assert_eq!(boc::parse(&trailing), Err(boc::BocError::TrailingBytes));
+    assert_eq!(boc::parse(&boc[..6]), Err(boc::BocError::UnexpectedEof));
+}
+
+#[test]
+fn boc_cell_slice() {
+    let child = boc::CellBuilder::new().build().unwrap();
+    let mut builder = boc::CellBuilder::new();
+    builder
+        .store_bit(true)
+        .unwrap()
+        .store_uint(0x1234, 16)
+        .unwrap()
+        .store_int(-5, 7)
+        .unwrap()
+        .store_bits(&[0xf0], 3)
+        .unwrap()
+        .store_reference(child.clone())
+        .unwrap();
+    assert_eq!(
+        boc::CellBuilder::new().store_uint(4, 2).unwrap_err(),
+        boc::BocError::IntegerOverflow
+    );
+    assert_eq!(
+        boc::CellBuilder::new().store_int(-5, 3).unwrap_err(),
+        boc::BocError::IntegerOverflow
+    );
+    let cell = builder.build().unwrap();
+    assert_eq!(cell.bit_len(), 27);
+
+    let mut slice = boc::CellSlice::new(&cell).unwrap();
+    assert!(slice.load_bit().unwrap());
+    assert_eq!(slice.load_uint(16).unwrap(), 0x1234);
+    assert_eq!(slice.load_int(7).unwrap(), -5);
+    assert_eq!(slice.load_bits(3).unwrap(), [0xe0]);
+    assert_eq!(slice.remaining_bits(), 0);
+    assert_eq!(slice.load_bit(), Err(boc::BocError::CellUnderflow));
+    assert_eq!(slice.load_reference().unwrap(), &child);
+    assert_eq!(slice.load_reference(), Err(boc::BocError::CellUnderflow));
+
+    let pruned = pruned_branch(&cell);
+    assert_eq!(
+        boc::CellSlice::new(&pruned).unwrap_err(),
+        boc::BocError::ExoticCell(boc::CellType::PrunedBranch)
+    );
+}
+
+/// Store label `hml_long` with bits `offset..end` of `key` for dictionary node with `m` bits
+/// of key left
+fn store_dict_label(builder: &mut boc::CellBuilder, key: &[u8], offset: usize, end: usize) {
+    let m = key.len() * 8 - offset;
+    let length_bits = (usize::BITS - m.leading_zeros()) as usize;
+    builder.store_uint(0b10, 2).unwrap();
+    builder
+        .store_uint((end - offset) as u64, length_bits)
+        .unwrap();
+    for i in offset..end {
+        builder
+            .store_bit((key[i / 8] >> (7 - i % 8)) & 1 != 0)
+            .unwrap();
+    }
+}
+
+/// Store `DepthBalanceInfo` with zero balance
+fn store_depth_balance(builder: &mut boc::CellBuilder) {
+    builder.store_uint(0, 5 + 4 + 1).unwrap();
+}
+
+fn test_shard_account(
+    id: &[u8; 32],
+    account: &boc::Cell,
+    (last_lt, last_hash): (u64, [u8; 32]),
+) -> boc::Cell {
+    let mut leaf = boc::CellBuilder::new();
+    store_dict_label(&mut leaf, id, 3, 256);
+    store_depth_balance(&mut leaf);
+    leaf.store_reference(account.clone()).unwrap();
+    leaf.store_bytes(&last_hash).unwrap();
+    leaf.store_uint(last_lt, 64).unwrap();
+    leaf.build().unwrap()
+}
+
+fn test_block_info(builder: &mut boc::CellBuilder, workchain: i32, shard: i64) {
+    builder.store_uint(0, 2 + 6).unwrap();
+    builder.store_int(workchain as i64, 32).unwrap();
+    builder.store_uint(shard as u64, 64).unwrap();
+}
+
+fn test_shard_state(
+    workchain: i32,
+    shard: i64,
+    accounts: boc::Cell,
+    custom: Option<boc::Cell>,
+) -> boc::Cell {
+    let empty = boc::CellBuilder::new().build().unwrap();
+    let mut state = boc::CellBuilder::new();
+    state
+        .store_uint(0x9023afe2, 32)
+        .unwrap()
+        .store_uint(0, 32)
+        .unwrap();
+    test_block_info(&mut state, workchain, shard);
+    // seq_no, vert_seq_no, gen_utime, gen_lt, min_ref_mc_seqno, before_split
+    state
+        .store_bytes(&[0; 24])
+        .unwrap()
+        .store_bit(false)
+        .unwrap();
+    state.store_bit(custom.is_some()).unwrap();
+    state.store_reference(empty.clone()).unwrap();
+    state.store_reference(accounts).unwrap();
+    state.store_reference(empty).unwrap();
+    if let Some(custom) = custom {
+        state.store_reference(custom).unwrap();
+    }
+    state.build().unwrap()
+}
+
+/// Block with Merkle update to `state`, it is a key block if `mc_extra` is set
+fn test_block(
+    id: &tl::ton::ton_node::BlockIdExt,
+    state: &boc::Cell,
+    mc_extra: Option<boc::Cell>,
+) -> boc::Cell {
+    let empty = boc::CellBuilder::new().build().unwrap();
+    let mut info = boc::CellBuilder::new();
+    info.store_uint(0x9bc7a987, 32).unwrap();
+    info.store_uint(0, 32).unwrap();
+    info.store_bit(!id.is_masterchain()).unwrap();
+    info.store_uint(0, 5).unwrap();
+    info.store_bit(mc_extra.is_some()).unwrap();
+    info.store_uint(0, 1 + 8).unwrap();
+    info.store_uint(id.seqno as u64, 32).unwrap();
+    info.store_uint(0, 32).unwrap();
+    test_block_info(&mut info, id.workchain, id.shard);
+    info.store_uint(1700000000, 32).unwrap();
+    info.store_uint(1000, 64).unwrap();
+    info.store_uint(1005, 64).unwrap();
+    // gen_validator_list_hash_short, gen_catchain_seqno, min_ref_mc_seqno
+    info.store_uint(0x5e7a11da, 32).unwrap();
+    info.store_uint(3, 32).unwrap();
+    info.store_uint(0, 32).unwrap();
+    info.store_uint(7, 32).unwrap();
+
+    let extra = match mc_extra {
+        Some(mc_extra) => {
+            let mut extra = boc::CellBuilder::new();
+            extra.store_uint(0x4a33f6fd, 32).unwrap();
+            for _ in 0..3 {
+                extra.store_reference(empty.clone()).unwrap();
+            }
+            extra.store_bytes(&[0; 64]).unwrap();
+            extra.store_bit(true).unwrap();
+            extra.store_reference(mc_extra).unwrap();
+            extra.build().unwrap()
+        }
+        None => empty.clone(),
+    };
+    let state_update = merkle_cell(4, alloc::vec![empty.clone(), state.clone()]).unwrap();
+    let mut block = boc::CellBuilder::new();
+    block.store_uint(0x11ef55aa, 32).unwrap();
+    block.store_uint(0, 32).unwrap();
+    for cell in [info.build().unwrap(), empty, state_update, extra] {
+        block.store_reference(cell).unwrap();
+    }
+    block.build().unwrap()
+}
+
+/// `McStateExtra` with optional shard hashes and previous blocks dictionaries
+fn test_mc_state_extra(
+    shard_hashes: Option<boc::Cell>,
+    prev_blocks: Option<boc::Cell>,
+    config: Option<boc::Cell>,
+) -> boc::Cell {
+    let config = config.unwrap_or_else(|| boc::CellBuilder::new().build().unwrap());
+    let mut other = boc::CellBuilder::new();
+    other.store_uint(0, 16 + 32).unwrap();
+    other.store_uint(0, 32 + 1).unwrap();
+    other.store_bit(prev_blocks.is_some()).unwrap();
+    if let Some(prev_blocks) = prev_blocks {
+        other.store_reference(prev_blocks).unwrap();
+    }
+    // extra of previous blocks dictionary
+    other.store_bit(false).unwrap();
+    other.store_uint(0, 64).unwrap();
+
+    let mut extra = boc::CellBuilder::new();
+    extra.store_uint(0xcc26, 16).unwrap();
+    extra.store_bit(shard_hashes.is_some()).unwrap();
+    if let Some(shard_hashes) = shard_hashes {
+        extra.store_reference(shard_hashes).unwrap();
+    }
+    extra.store_bytes(&[0; 32]).unwrap();
+    extra.store_reference(config).unwrap();
+    extra.store_reference(other.build().unwrap()).unwrap();
+    extra.build().unwrap()
+}
+
+fn test_empty_accounts() -> boc::Cell {
+    let mut accounts = boc::CellBuilder::new();
+    accounts.store_bit(false).unwrap();
+    store_depth_balance(&mut accounts);
+    accounts.build().unwrap()
+}
+
+/// Copy of `cell` at Merkle `level` with all cells except `keep` pruned
+fn prune_except(cell: &boc::Cell, keep: &[&boc::Cell], level: u8) -> boc::Cell {
+    let level = match cell.cell_type() {
+        boc::CellType::MerkleProof | boc::CellType::MerkleUpdate => level + 1,
+        _ => level,
+    };
+    let references = cell
+        .references()
+        .iter()
+        .map(|reference| match keep.contains(&reference) {
+            true => prune_except(reference, keep, level),
+            false => pruned_branch_at(reference, level),
+        })
+        .collect();
+    boc::Cell::new(
+        cell.data().to_vec(),
+        cell.bit_len(),
+        references,
+        cell.is_exotic(),
+    )
+    .unwrap()
+}
+
+/// BoC with Merkle proofs of `roots`, each keeping `keep` cells
+fn merkle_proofs(roots: &[(&boc::Cell, &[&boc::Cell])]) -> Vec<u8> {
+    let proofs = roots
+        .iter()
+        .map(|(root, keep)| merkle_cell(3, alloc::vec![prune_except(root, keep, 1)]).unwrap())
+        .collect::<Vec<_>>();
+    boc::BocSerializer::new().serialize(&proofs)
+}
+
+const ACCOUNT_ONE: [u8; 32] = [0x11; 32];
+const ACCOUNT_TWO: [u8; 32] = [0x22; 32];
+
+struct ProofFixture {
+    mc_id: tl::ton::ton_node::BlockIdExt,
+    shardblk: tl::ton::ton_node::BlockIdExt,
+    mc_block: boc::Cell,
+    mc_state: boc::Cell,
+    shard_proof: Vec<u8>,
+    account_proof: Vec<u8>,
+    account: boc::Cell,
+    other_account: boc::Cell,
+}
+
+/// Masterchain block with workchain 0 split into two shards, and state of the left shard with
+/// two accounts
+fn proof_fixture() -> ProofFixture {
+    proof_fixture_with_last_transaction((20, [20; 32]))
+}
+
+/// The same as [`proof_fixture`] with given last transaction of the first account
+fn proof_fixture_with_last_transaction(last_transaction: (u64, [u8; 32])) -> ProofFixture {
+    let account = boc::Cell::new(b"account one".to_vec(), 88, Vec::new(), false).unwrap();
+    let other_account = boc::Cell::new(b"account two".to_vec(), 88, Vec::new(), false).unwrap();
+    let leaves = [
+        test_shard_account(&ACCOUNT_ONE, &account, last_transaction),
+        test_shard_account(&ACCOUNT_TWO, &other_account, (30, [30; 32])),
+    ];
+    let mut dict = boc::CellBuilder::new();
+    // common prefix of both accounts, fork by the third bit
+    store_dict_label(&mut dict, &ACCOUNT_ONE, 0, 2);
+    store_depth_balance(&mut dict);
+    dict.store_reference(leaves[0].clone()).unwrap();
+    dict.store_reference(leaves[1].clone()).unwrap();
+    let dict = dict.build().unwrap();
+    let mut accounts = boc::CellBuilder::new();
+    accounts.store_bit(true).unwrap();
+    accounts.store_reference(dict.clone()).unwrap();
+    store_depth_balance(&mut accounts);
+    let accounts = accounts.build().unwrap();
+
+    let shard = 0x4000_0000_0000_0000;
+    let shard_state = test_shard_state(0, shard, accounts.clone(), None);
+    let mut shardblk = tl::ton::ton_node::BlockIdExt {
+        workchain: 0,
+        shard,
+        seqno: 15,
+        root_hash: [0; 32],
+        file_hash: [9; 32],
+    };
+    let shard_block = test_block(&shardblk, &shard_state, None);
+    shardblk.root_hash = shard_block.hash();
+
+    let shard_descr = |seqno: u64, hashes: ([u8; 32], [u8; 32])| {
+        let mut descr = boc::CellBuilder::new();
+        descr.store_bit(false).unwrap().store_uint(0xb, 4).unwrap();
+        descr.store_uint(seqno, 32).unwrap();
+        descr.store_bytes(&[0; 20]).unwrap();
+        descr
+            .store_bytes(&hashes.0)
+            .unwrap()
+            .store_bytes(&hashes.1)
+            .unwrap();
+        descr.build().unwrap()
+    };
+    let mut tree = boc::CellBuilder::new();
+    tree.store_bit(true).unwrap();
+    tree.store_reference(shard_descr(15, (shardblk.root_hash, shardblk.file_hash)))
+        .unwrap();
+    tree.store_reference(shard_descr(16, ([5; 32], [6; 32])))
+        .unwrap();
+    let mut shard_hashes = boc::CellBuilder::new();
+    store_dict_label(&mut shard_hashes, &0i32.to_be_bytes(), 0, 32);
+    shard_hashes.store_reference(tree.build().unwrap()).unwrap();
+    let extra = test_mc_state_extra(Some(shard_hashes.build().unwrap()), None, None);
+    let mc_state = test_shard_state(-1, i64::MIN, test_empty_accounts(), Some(extra));
+    let mut mc_id = test_block_id(20);
+    let mc_block = test_block(&mc_id, &mc_state, None);
+    mc_id.root_hash = mc_block.hash();
+
+    let block_keep = |block: &boc::Cell| {
+        let references = block.references();
+        alloc::vec![references[0].clone(), references[2].clone()]
+    };
+    let (mc_keep, shard_keep) = (block_keep(&mc_block), block_keep(&shard_block));
+    let mc_state_keep: Vec<&boc::Cell> = {
+        let custom = &mc_state.references()[3];
+        let shard_hashes = &custom.references()[0];
+        let tree = &shard_hashes.references()[0];
+        alloc::vec![custom, shard_hashes, tree, &tree.references()[0]]
+    };
+    let shard_proof = merkle_proofs(&[
+        (&mc_block, &mc_keep.iter().collect::<Vec<_>>()),
+        (&mc_state, &mc_state_keep),
+    ]);
+    let account_proof = merkle_proofs(&[
+        (&shard_block, &shard_keep.iter().collect::<Vec<_>>()),
+        (&shard_state, &[&accounts, &dict, &leaves[0], &leaves[1]]),
+    ]);
+    ProofFixture {
+        mc_id,
+        shardblk,
+        mc_block,
+        mc_state,
+        shard_proof,
+        account_proof,
+        account,
+        other_account,
+    }
+}
+
+fn test_account_state(
+    fixture: &ProofFixture,
+    state: &boc::Cell,
+) -> tl::ton::lite_server::AccountState {
+    tl::ton::lite_server::AccountState {
+        id: fixture.mc_id.clone(),
+        shardblk: fixture.shardblk.clone(),
+        shard_proof: fixture.shard_proof.clone(),
+        proof: fixture.account_proof.clone(),
+        state: boc::serialize(state),
+    }
+}
+
+#[test]
+fn proof_account_state() {
+    use tl::ton::lite_server::AccountId;
+
+    let fixture = proof_fixture();
+    let account = AccountId {
+        workchain: 0,
+        id: ACCOUNT_ONE,
+    };
+    let mut answer = test_account_state(&fixture, &fixture.account);
+    let shard_account = proof::check_account_state(&answer, &fixture.mc_id, &account)
+        .unwrap()
+        .unwrap();
+    assert_eq!(shard_account.account_hash, fixture.account.hash());
+    assert_eq!(
+        (shard_account.last_trans_hash, shard_account.last_trans_lt),
+        ([20; 32], 20)
+    );
+
+    // account in the same shard which does not exist
+    let missing = AccountId {
+        workchain: 0,
+        id: [0x12; 32],
+    };
+    let empty = tl::ton::lite_server::AccountState {
+        state: Vec::new(),
+        ..answer.clone()
+    };
+    assert_eq!(
+        proof::check_account_state(&empty, &fixture.mc_id, &missing),
+        Ok(None)
+    );
+    assert_eq!(
+        proof::check_account_state(&empty, &fixture.mc_id, &account),
+        Err(proof::ProofError::AccountMismatch)
+    );
+    let other = test_account_state(&fixture, &fixture.other_account);
+    assert_eq!(
+        proof::check_account_state(&other, &fixture.mc_id, &account),
+        Err(proof::ProofError::AccountMismatch)
+    );
+    let wrong_shard = AccountId {
+        workchain: 0,
+        id: [0x91; 32],
+    };
+    assert_eq!(
+        proof::check_account_state(&answer, &fixture.mc_id, &wrong_shard),
+        Err(proof::ProofError::InvalidProof(
+            "account does not belong to shard"
+        ))
+    );
+
+    let mut other_block = fixture.mc_id.clone();
+    other_block.root_hash = [0; 32];
+    assert_eq!(
+        proof::check_account_state(&answer, &other_block, &account),
+        Err(proof::ProofError::BlockMismatch)
+    );
+    answer.id = other_block.clone();
+    assert_eq!(
+        proof::check_account_state(&answer, &other_block, &account),
+        Err(proof::ProofError::RootHashMismatch)
+    );
+    answer.id = fixture.mc_id.clone();
+    answer.shardblk.seqno = 14;
+    assert_eq!(
+        proof::check_account_state(&answer, &fixture.mc_id, &account),
+        Err(proof::ProofError::ShardMismatch)
+    );
+
+    // state of shard block referenced directly needs no shard proof
+    let answer = tl::ton::lite_server::AccountState {
+        id: fixture.shardblk.clone(),
+        shard_proof: Vec::new(),
+        ..test_account_state(&fixture, &fixture.account)
+    };
+    assert!(proof::check_account_state(&answer, &fixture.shardblk, &account).is_ok());
+}
+
+#[test]
+fn proof_run_method_and_blocks() {
+    use tl::ton::lite_server::{AccountId, BlockData, BlockHeader, RunMethodResult};
+
+    let fixture = proof_fixture();
+    let account = AccountId {
+        workchain: 0,
+        id: ACCOUNT_ONE,
+    };
+    let mut answer = RunMethodResult {
+        mode: 0b111,
+        id: fixture.mc_id.clone(),
+        shardblk: fixture.shardblk.clone(),
+        shard_proof: Some(fixture.shard_proof.clone()),
+        proof: Some(fixture.account_proof.clone()),
+        state_proof: Some(merkle_proofs(&[(&fixture.account, &[])])),
+        init_c7: None,
+        lib_extras: None,
+        exit_code: 0,
+        result: Some(Vec::new()),
+    };
+    assert_eq!(
+        proof::check_run_method_result(&answer, &fixture.mc_id, &account),
+        Ok(())
+    );
+    answer.state_proof = Some(merkle_proofs(&[(&fixture.other_account, &[])]));
+    assert_eq!(
+        proof::check_run_method_result(&answer, &fixture.mc_id, &account),
+        Err(proof::ProofError::AccountMismatch)
+    );
+    answer.state_proof = None;
+    assert_eq!(
+        proof::check_run_method_result(&answer, &fixture.mc_id, &account),
+        Err(proof::ProofError::MissingProof)
+    );
+
+    let references = fixture.mc_block.references();
+    let header = BlockHeader {
+        id: fixture.mc_id.clone(),
+        mode: 0,
+        header_proof: merkle_proofs(&[(&fixture.mc_block, &[&references[0], &references[2]])]),
+    };
+    let info = proof::check_block_header(&header, &fixture.mc_id).unwrap();
+    assert_eq!(
+        info,
+        proof::BlockInfo {
+            key_block: false,
+            gen_utime: 1700000000,
+            start_lt: 1000,
+            end_lt: 1005,
+            gen_validator_list_hash_short: 0x5e7a11da,
+            gen_catchain_seqno: 3,
+            prev_key_block_seqno: 7,
+            state_hash: Some(fixture.mc_state.hash()),
+        }
+    );
+    let header = BlockHeader {
+        header_proof: merkle_proofs(&[(&fixture.mc_block, &[&references[0]])]),
+        ..header
+    };
+    assert_eq!(
+        proof::check_block_header(&header, &fixture.mc_id)
+            .unwrap()
+            .state_hash,
+        None
+    );
+    let mut wrong_seqno = fixture.mc_id.clone();
+    wrong_seqno.seqno += 1;
+    let header = BlockHeader {
+        id: wrong_seqno.clone(),
+        ..header
+    };
+    assert_eq!(
+        proof::check_block_header(&header, &wrong_seqno),
+        Err(proof::ProofError::InvalidProof(
+            "block header does not match block id"
+        ))
+    );
+
+    let data = boc::serialize(&fixture.mc_block);
+    let mut id = fixture.mc_id.clone();
+    id.file_hash = <sha2::Sha256 as sha2::Digest>::digest(&data).into();
+    let block = BlockData {
+        id: id.clone(),
+        data,
+    };
+    assert_eq!(
+        proof::check_block_data(&block, &id),
+        Ok(fixture.mc_block.clone())
+    );
+    assert_eq!(
+        proof::check_block_data(&block, &fixture.mc_id),
+        Err(proof::ProofError::BlockMismatch)
+    );
+}
+
+fn test_transaction(lt: u64, prev: Option<&boc::Cell>) -> boc::Cell {
+    test_message_transaction(lt, prev, None)
+}
+
+/// Transaction of the first account with inbound message `in_msg`, the previous transaction
+/// is 10 units of logical time before
+fn test_message_transaction(
+    lt: u64,
+    prev: Option<&boc::Cell>,
+    in_msg: Option<&boc::Cell>,
+) -> boc::Cell {
+    let mut transaction = boc::CellBuilder::new();
+    transaction.store_uint(0b0111, 4).unwrap();
+    transaction.store_bytes(&ACCOUNT_ONE).unwrap();
+    transaction.store_uint(lt, 64).unwrap();
+    transaction
+        .store_bytes(&prev.map(|cell| cell.hash()).unwrap_or_default())
+        .unwrap();
+    transaction.store_uint(lt.saturating_sub(10), 64).unwrap();
+    // now, outmsg_cnt, orig_status and end_status
+    transaction.store_uint(1700000000, 32).unwrap();
+    transaction.store_uint(0, 15 + 2 + 2).unwrap();
+    let mut messages = boc::CellBuilder::new();
+    messages.store_bit(in_msg.is_some()).unwrap();
+    if let Some(in_msg) = in_msg {
+        messages.store_reference(in_msg.clone()).unwrap();
+    }
+    // empty out_msgs
+    messages.store_bit(false).unwrap();
+    transaction
+        .store_reference(messages.build().unwrap())
+        .unwrap();
+    transaction.build().unwrap()
+}
+
+#[test]
+fn proof_transactions() {
+    use tl::ton::lite_server::{AccountId, TransactionList};
+
+    let account = AccountId {
+        workchain: 0,
+        id: ACCOUNT_ONE,
+    };
+    let first = test_transaction(10, None);
+    let second = test_transaction(20, Some(&first));
+    let fixture = proof_fixture();
+    let shardblk = Some(&fixture.shardblk);
+    let mut older = fixture.shardblk.clone();
+    older.seqno -= 1;
+    let mut answer = TransactionList {
+        ids: alloc::vec![fixture.shardblk.clone(), older.clone()],
+        transactions: boc::BocSerializer::new().serialize(&[second.clone(), first.clone()]),
+    };
+    assert_eq!(
+        proof::check_transactions(&answer, &account, 20, second.hash(), shardblk),
+        Ok(alloc::vec![second.clone(), first.clone()])
+    );
+    assert_eq!(
+        proof::check_transactions(&answer, &account, 20, first.hash(), shardblk),
+        Err(proof::ProofError::TransactionMismatch)
+    );
+
+    // block ids must be of the account shard and go back in time from the proven block
+    let mut other_shard = older.clone();
+    other_shard.shard = -0x4000_0000_0000_0000;
+    answer.ids[1] = other_shard;
+    assert_eq!(
+        proof::check_transactions(&answer, &account, 20, second.hash(), shardblk),
+        Err(proof::ProofError::BlockMismatch)
+    );
+    answer.ids = alloc::vec![older.clone(), fixture.shardblk.clone()];
+    assert!(matches!(
+        proof::check_transactions(&answer, &account, 20, second.hash(), None),
+        Err(proof::ProofError::InvalidProof(_))
+    ));
+    answer.ids = alloc::vec![fixture.shardblk.clone(), older];
+    assert!(matches!(
+        proof::check_transactions(&answer, &account, 20, second.hash(), Some(&answer.ids[1])),
+        Err(proof::ProofError::InvalidProof(_))
+    ));
+
+    answer.transactions = boc::BocSerializer::new().serialize(&[first.clone(), second]);
+    assert_eq!(
+        proof::check_transactions(&answer, &account, 10, first.hash(), shardblk),
+        Err(proof::ProofError::TransactionMismatch)
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_verified() {
+    use tl::ton::lite_server::{AccountId, GetAccountState};
+
+    let fixture = proof_fixture();
+    let answer = tl::serialize(&tl::Boxed(test_account_state(&fixture, &fixture.account)));
+    let (public, port) = spawn_lite_server(move |query| {
+        let _: GetAccountState = tl::deserialize(query).unwrap();
+        answer.clone()
+    })
+    .await;
+    let mut client = LiteClient::connect(public.as_bytes(), ("127.0.0.1", port))
+        .await
+        .unwrap()
+        .with_verification(true);
+    let account = AccountId {
+        workchain: 0,
+        id: ACCOUNT_ONE,
+    };
+    let state = client
+        .get_account_state(fixture.mc_id.clone(), account.clone())
+        .await
+        .unwrap();
+    assert_eq!(state.state, boc::serialize(&fixture.account));
+    let other = AccountId {
+        workchain: 0,
+        id: ACCOUNT_TWO,
+    };
+    assert!(matches!(
+        client
+            .get_account_state(fixture.mc_id.clone(), other.clone())
+            .await,
+        Err(LiteError::ProofError(proof::ProofError::AccountMismatch))
+    ));
+
+    let mut client = client.with_verification(false);
+    assert!(client
+        .get_account_state(fixture.mc_id.clone(), other)
+        .await
+        .is_ok());
+}
+
+/// Dictionary with `entries` sorted by keys of `key_bits` bits, returns builder of the root
+/// node to store it inline or as a cell
+fn test_dict(entries: &[(u64, boc::Cell)], key_bits: usize) -> boc::CellBuilder {
+    fn node(entries: &[(u64, boc::Cell)], offset: usize, key_bits: usize) -> boc::CellBuilder {
+        let bit = |key: u64, i: usize| (key >> (key_bits - 1 - i)) & 1 != 0;
+        let (first, last) = (entries[0].0, entries[entries.len() - 1].0);
+        let mut end = offset;
+        while end < key_bits && bit(first, end) == bit(last, end) {
+            end += 1;
+        }
+        let mut builder = boc::CellBuilder::new();
+        let remaining = key_bits - offset;
+        let length_bits = (usize::BITS - remaining.leading_zeros()) as usize;
+        builder.store_uint(0b10, 2).unwrap();
+        builder
+            .store_uint((end - offset) as u64, length_bits)
+            .unwrap();
+        for i in offset..end {
+            builder.store_bit(bit(first, i)).unwrap();
+        }
+        if end == key_bits {
+            let value = &entries[0].1;
+            builder.store_bits(value.data(), value.bit_len()).unwrap();
+            for reference in value.references() {
+                builder.store_reference(reference.clone()).unwrap();
+            }
+        } else {
+            let split = entries.partition_point(|(key, _)| !bit(*key, end));
+            for part in [&entries[..split], &entries[split..]] {
+                let child = node(part, end + 1, key_bits).build().unwrap();
+                builder.store_reference(child).unwrap();
+            }
+        }
+        builder
+    }
+    node(entries, 0, key_bits)
+}
+
+/// All cells of tree with `root`
+fn subtree(root: &boc::Cell) -> Vec<boc::Cell> {
+    let mut cells = alloc::vec![root.clone()];
+    for reference in root.references() {
+        cells.extend(subtree(reference));
+    }
+    cells
+}
+
+fn test_validator_set(keys: &[KeyPair], main: u16) -> boc::Cell {
+    test_validator_set_in(keys, main, (1700000000, 1800000000))
+}
+fn test_validator_set_in(keys: &[KeyPair], main: u16, utime: (u32, u32)) -> boc::Cell {
+    let entries = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let mut descr = boc::CellBuilder::new();
+            descr.store_uint(0x53, 8).unwrap();
+            descr.store_uint(0x8e81278a, 32).unwrap();
+            descr.store_bytes(key.public_key.as_bytes()).unwrap();
+            descr.store_uint(10, 64).unwrap();
+            (i as u64, descr.build().unwrap())
+        })
+        .collect::<Vec<_>>();
+    let mut set = boc::CellBuilder::new();
+    set.store_uint(0x12, 8).unwrap();
+    set.store_uint(utime.0 as u64, 32).unwrap();
+    set.store_uint(utime.1 as u64, 32).unwrap();
+    set.store_uint(keys.len() as u64, 16).unwrap();
+    set.store_uint(main as u64, 16).unwrap();
+    set.store_uint(10 * keys.len() as u64, 64).unwrap();
+    set.store_bit(true).unwrap();
+    set.store_reference(test_dict(&entries, 16).build().unwrap())
+        .unwrap();
+    set.build().unwrap()
+}
+
+fn sign_block(
+    keys: &[&KeyPair],
+    id: &tl::ton::ton_node::BlockIdExt,
+) -> tl::ton::lite_server::SignatureSet {
+    let message = tl::serialize(&tl::Boxed(tl::ton::ton::BlockId {
+        root_cell_hash: id.root_hash,
+        file_hash: id.file_hash,
+    }));
+    let signatures = keys
+        .iter()
+        .map(|key| tl::ton::lite_server::Signature {
+            node_id_short: AdnlAddress::from_ed25519(key.public_key.as_bytes()).to_bytes(),
+            signature: key.sign_raw(&message).to_vec(),
+        })
+        .collect();
+    tl::ton::lite_server::SignatureSet {
+        validator_set_hash: 0x5e7a11da,
+        catchain_seqno: 3,
+        signatures,
+    }
+}
+
+struct BlockProofFixture {
+    keys: Vec<KeyPair>,
+    init: tl::ton::ton_node::BlockIdExt,
+    key_block: tl::ton::ton_node::BlockIdExt,
+    last: tl::ton::ton_node::BlockIdExt,
+    back: tl::ton::lite_server::BlockLinkBack,
+    forward: tl::ton::lite_server::BlockLinkForward,
+}
+
+/// Trusted block 15, which links back to key block 10, which links forward to block 20 signed
+/// by 3 main validators of 4
+fn block_proof_fixture() -> BlockProofFixture {
+    block_proof_fixture_with(7, (1700000000, 1800000000))
+}
+/// Blocks of the fixture are generated at 1700000000 and refer to previous key block 7
+fn block_proof_fixture_with(
+    key_block_seqno: i32,
+    validators_utime: (u32, u32),
+) -> BlockProofFixture {
+    use tl::ton::lite_server::{BlockLinkBack, BlockLinkForward};
+
+    let keys = (0..4)
+        .map(|_| KeyPair::generate(&mut OsRng))
+        .collect::<Vec<_>>();
+    let empty = boc::CellBuilder::new().build().unwrap();
+    let plain_state = test_shard_state(-1, i64::MIN, test_empty_accounts(), None);
+
+    let param = boc::CellBuilder::new()
+        .store_reference(test_validator_set_in(&keys, 3, validators_utime))
+        .unwrap()
+        .build()
+        .unwrap();
+    let config = test_dict(&[(34, param)], 32).build().unwrap();
+    let mut mc_extra = boc::CellBuilder::new();
+    mc_extra.store_uint(0xcca5, 16).unwrap();
+    // key_block, shard_hashes, shard_fees and its extra
+    mc_extra.store_uint(0b100, 3).unwrap();
+    mc_extra.store_uint(0, 10).unwrap();
+    mc_extra.store_reference(empty).unwrap();
+    mc_extra.store_bytes(&[0; 32]).unwrap();
+    mc_extra.store_reference(config.clone()).unwrap();
+    let mc_extra = mc_extra.build().unwrap();
+    let mut key_block = test_block_id(key_block_seqno);
+    let key_block_root = test_block(&key_block, &plain_state, Some(mc_extra.clone()));
+    key_block.root_hash = key_block_root.hash();
+
+    let mut last = test_block_id(20);
+    let last_root = test_block(&last, &plain_state, None);
+    last.root_hash = last_root.hash();
+
+    let mut prev_block = boc::CellBuilder::new();
+    prev_block
+        .store_bit(true)
+        .unwrap()
+        .store_uint(1005, 64)
+        .unwrap();
+    prev_block
+        .store_bit(true)
+        .unwrap()
+        .store_uint(1005, 64)
+        .unwrap();
+    prev_block.store_uint(key_block_seqno as u64, 32).unwrap();
+    prev_block.store_bytes(&key_block.root_hash).unwrap();
+    prev_block.store_bytes(&key_block.file_hash).unwrap();
+    let prev_blocks = test_dict(&[(key_block_seqno as u64, prev_block.build().unwrap())], 32)
+        .build()
+        .unwrap();
+    let init_extra = test_mc_state_extra(None, Some(prev_blocks.clone()), None);
+    let init_state = test_shard_state(
+        -1,
+        i64::MIN,
+        test_empty_accounts(),
+        Some(init_extra.clone()),
+    );
+    let mut init = test_block_id(15);
+    let init_root = test_block(&init, &init_state, None);
+    init.root_hash = init_root.hash();
+
+    let header_proof = |root: &boc::Cell| merkle_proofs(&[(root, &[&root.references()[0]])]);
+    let back = BlockLinkBack {
+        to_key_block: true,
+        from: init.clone(),
+        to: key_block.clone(),
+        dest_proof: header_proof(&key_block_root),
+        proof: merkle_proofs(&[(
+            &init_root,
+            &[&init_root.references()[0], &init_root.references()[2]],
+        )]),
+        state_proof: merkle_proofs(&[(
+            &init_state,
+            &[&init_extra, &init_extra.references()[1], &prev_blocks],
+        )]),
+    };
+
+    let mut config_keep = subtree(&config);
+    config_keep.extend([
+        key_block_root.references()[0].clone(),
+        key_block_root.references()[3].clone(),
+        mc_extra,
+    ]);
+    let forward = BlockLinkForward {
+        to_key_block: false,
+        from: key_block.clone(),
+        to: last.clone(),
+        dest_proof: header_proof(&last_root),
+        config_proof: merkle_proofs(&[(&key_block_root, &config_keep.iter().collect::<Vec<_>>())]),
+        signatures: tl::Boxed(sign_block(&[&keys[0], &keys[1], &keys[2]], &last)),
+    };
+    BlockProofFixture {
+        keys,
+        init,
+        key_block,
+        last,
+        back,
+        forward,
+    }
+}
+
+#[test]
+fn proof_block_links() {
+    use tl::ton::lite_server::{BlockLink, PartialBlockProof};
+
+    let fixture = block_proof_fixture();
+    let back = BlockLink::BlockLinkBack(fixture.back.clone());
+    let forward = BlockLink::BlockLinkForward(fixture.forward.clone());
+    assert_eq!(proof::check_block_link(&back), Ok(()));
+    assert_eq!(proof::check_block_link(&forward), Ok(()));
+
+    let mut proof = PartialBlockProof {
+        complete: true,
+        from: fixture.init.clone(),
+        to: fixture.last.clone(),
+        steps: alloc::vec![back.clone(), forward.clone()],
+    };
+    assert_eq!(
+        proof::check_block_proof(&proof, &fixture.init),
+        Ok(fixture.last.clone())
+    );
+    assert_eq!(
+        proof::check_block_proof(&proof, &fixture.key_block),
+        Err(proof::ProofError::BlockMismatch)
+    );
+    proof.steps.remove(0);
+    assert_eq!(
+        proof::check_block_proof(&proof, &fixture.init),
+        Err(proof::ProofError::InvalidProof(
+            "block proof links are not chained"
+        ))
+    );
+
+    let keys = &fixture.keys;
+    for (signers, error) in [
+        (
+            alloc::vec![&keys[0], &keys[1]],
+            proof::ProofError::NotEnoughSignatures,
+        ),
+        (
+            alloc::vec![&keys[0], &keys[1], &keys[3]],
+            proof::ProofError::InvalidSignature,
+        ),
+        (
+            alloc::vec![&keys[0], &keys[1], &keys[1]],
+            proof::ProofError::InvalidSignature,
+        ),
+    ] {
+        let mut link = fixture.forward.clone();
+        link.signatures = tl::Boxed(sign_block(&signers, &fixture.last));
+        assert_eq!(
+            proof::check_block_link(&BlockLink::BlockLinkForward(link)),
+            Err(error)
+        );
+    }
+    let mut link = fixture.forward.clone();
+    link.signatures.0.signatures[2].signature[0] ^= 1;
+    assert_eq!(
+        proof::check_block_link(&BlockLink::BlockLinkForward(link)),
+        Err(proof::ProofError::InvalidSignature)
+    );
+    let mut link = fixture.forward.clone();
+    link.dest_proof.clear();
+    assert_eq!(
+        proof::check_block_link(&BlockLink::BlockLinkForward(link)),
+        Err(proof::ProofError::MissingProof)
+    );
+    let mut link = fixture.forward.clone();
+    link.signatures.0.validator_set_hash ^= 1;
+    assert_eq!(
+        proof::check_block_link(&BlockLink::BlockLinkForward(link)),
+        Err(proof::ProofError::InvalidProof(
+            "signatures are not for validator session of block"
+        ))
+    );
+    let mut link = fixture.forward.clone();
+    link.signatures.0.catchain_seqno += 1;
+    assert_eq!(
+        proof::check_block_link(&BlockLink::BlockLinkForward(link)),
+        Err(proof::ProofError::InvalidProof(
+            "signatures are not for validator session of block"
+        ))
+    );
+    let skipped = block_proof_fixture_with(10, (1700000000, 1800000000));
+    assert_eq!(
+        proof::check_block_link(&BlockLink::BlockLinkForward(skipped.forward)),
+        Err(proof::ProofError::InvalidProof(
+            "forward link does not start at previous key block"
+        ))
+    );
+    for utime in [(1700000001, 1800000000), (1600000000, 1700000000)] {
+        assert_eq!(
+            proof::check_block_link(&BlockLink::BlockLinkForward(
+                block_proof_fixture_with(7, utime).forward
+            )),
+            Err(proof::ProofError::InvalidProof(
+                "block is out of validator set lifetime"
+            ))
+        );
+    }
+    let mut link = fixture.forward.clone();
+    link.to_key_block = true;
+    assert_eq!(
+        proof::check_block_link(&BlockLink::BlockLinkForward(link)),
+        Err(proof::ProofError::InvalidProof("key block flag mismatch"))
+    );
+
+    let mut link = fixture.back.clone();
+    link.to.file_hash = [0; 32];
+    assert_eq!(
+        proof::check_block_link(&BlockLink::BlockLinkBack(link)),
+        Err(proof::ProofError::BlockMismatch)
+    );
+    let mut link = fixture.back;
+    link.to_key_block = false;
+    assert_eq!(
+        proof::check_block_link(&BlockLink::BlockLinkBack(link)),
+        Err(proof::ProofError::InvalidProof("key block flag mismatch"))
+    );
+}
+
+#[test]
+fn config_validator_set() {
+    let keys = (0..3)
+        .map(|_| KeyPair::generate(&mut OsRng))
+        .collect::<Vec<_>>();
+    let set = config::ValidatorSet::parse(&test_validator_set(&keys, 2)).unwrap();
+    assert_eq!((set.main, set.total_weight, set.list.len()), (2, 30, 3));
+    assert_eq!(set.main_validators().len(), 2);
+    assert_eq!(set.list[2].public_key, *keys[2].public_key.as_bytes());
+    assert_eq!(set.list[2].adnl_addr, None);
+    assert_eq!(
+        config::ValidatorSet::parse(&test_validator_set(&keys, 4)),
+        Err(boc::BocError::InvalidCell("invalid number of validators"))
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_sync() {
+    use tl::ton::lite_server::{BlockLink, GetBlockProof, PartialBlockProof};
+
+    let fixture = block_proof_fixture();
+    let (init, key_block) = (fixture.init.clone(), fixture.key_block.clone());
+    let steps = [
+        PartialBlockProof {
+            complete: false,
+            from: fixture.init.clone(),
+            to: fixture.key_block.clone(),
+            steps: alloc::vec![BlockLink::BlockLinkBack(fixture.back)],
+        },
+        PartialBlockProof {
+            complete: true,
+            from: fixture.key_block.clone(),
+            to: fixture.last.clone(),
+            steps: alloc::vec![BlockLink::BlockLinkForward(fixture.forward)],
+        },
+    ];
+    let (public, port) = spawn_lite_server(move |query| {
+        let query: GetBlockProof = tl::deserialize(query).unwrap();
+        assert_eq!((query.mode, query.target_block), (0, None));
+        let step = steps
+            .iter()
+            .find(|step| step.from == query.known_block)
+            .unwrap();
+        tl::serialize(&tl::Boxed(step.clone()))
+    })
+    .await;
+    let mut client = LiteClient::connect(public.as_bytes(), ("127.0.0.1", port))
+        .await
+        .unwrap();
+    assert_eq!(client.sync(init).await.unwrap(), fixture.last);
+    // the server returns proof from key block for unknown block
+    assert!(matches!(
+        client.sync(key_block.clone()).await,
+        Ok(last) if last == fixture.last
+    ));
+}
+
+fn test_config(validators: boc::Cell) -> boc::Cell {
+    let cell = |bits: &[(u64, usize)], references: &[boc::Cell]| {
+        let mut builder = boc::CellBuilder::new();
+        for (value, len) in bits {
+            builder.store_uint(*value, *len).unwrap();
+        }
+        for reference in references {
+            builder.store_reference(reference.clone()).unwrap();
+        }
+        builder.build().unwrap()
+    };
+    let address = |byte: u8| {
+        let mut builder = boc::CellBuilder::new();
+        builder.store_bytes(&[byte; 32]).unwrap();
+        builder.build().unwrap()
+    };
+    let workchain = |version: u64| {
+        let mut builder = boc::CellBuilder::new();
+        builder.store_uint(0xa6, 8).unwrap();
+        builder.store_uint(1573821854, 32).unwrap();
+        builder.store_uint(0, 8).unwrap().store_uint(0, 8).unwrap();
+        builder.store_uint(8, 8).unwrap();
+        // basic, active, accept_msgs, flags
+        builder
+            .store_uint(0b111, 3)
+            .unwrap()
+            .store_uint(0, 13)
+            .unwrap();
+        builder.store_bytes(&[0x33; 32]).unwrap();
+        builder.store_bytes(&[0x44; 32]).unwrap();
+        builder.store_uint(version, 32).unwrap();
+        // wfmt_basic with vm_version and vm_mode
+        builder.store_uint(1, 4).unwrap();
+        builder
+            .store_uint(0, 32)
+            .unwrap()
+            .store_uint(0, 64)
+            .unwrap();
+        builder.build().unwrap()
+    };
+    // workchains 0 and -1, which comes after it in dictionary
+    let workchains = test_dict(&[(0, workchain(1)), (u32::MAX as u64, workchain(2))], 32)
+        .build()
+        .unwrap();
+    let gas = |masterchain: bool| match masterchain {
+        true => cell(
+            &[
+                (0xd1, 8),
+                (100, 64),
+                (10000, 64),
+                (0xde, 8),
+                (655360000, 64),
+                (1000000, 64),
+                (35000000, 64),
+                (10000, 64),
+                (2500000, 64),
+                (100000000, 64),
+                (1000000000, 64),
+            ],
+            &[],
+        ),
+        false => cell(
+            &[
+                (0xdd, 8),
+                (26214400, 64),
+                (1000000, 64),
+                (10000, 64),
+                (10000000, 64),
+                (100000000, 64),
+                (1000000000, 64),
+            ],
+            &[],
+        ),
+    };
+    let forward = |lump_price: u64| {
+        let bits = [
+            (0xea, 8),
+            (lump_price, 64),
+            (26214400, 64),
+            (2621440000, 64),
+            (98304, 32),
+            (21845, 16),
+            (21845, 16),
+        ];
+        cell(&bits, &[])
+    };
+    let params = [
+        (0, address(0x55)),
+        (1, address(0x33)),
+        (12, cell(&[(1, 1)], &[workchains])),
+        (20, gas(true)),
+        (21, gas(false)),
+        (24, forward(10000000)),
+        (25, forward(1000000)),
+        (34, validators),
+    ];
+    let entries = params
+        .into_iter()
+        .map(|(index, param)| (index, cell(&[], &[param])))
+        .collect::<Vec<_>>();
+    test_dict(&entries, 32).build().unwrap()
+}
+
+#[test]
+fn config_params() {
+    let keys = (0..2)
+        .map(|_| KeyPair::generate(&mut OsRng))
+        .collect::<Vec<_>>();
+    let config = config::BlockchainConfig::new(test_config(test_validator_set(&keys, 2)));
+    assert_eq!(config.config_address(), Ok(Some([0x55; 32])));
+    assert_eq!(config.elector_address(), Ok(Some([0x33; 32])));
+
+    let workchains = config.workchains().unwrap().unwrap();
+    assert_eq!(
+        workchains
+            .iter()
+            .map(|w| (w.workchain, w.version))
+            .collect::<Vec<_>>(),
+        [(-1, 2), (0, 1)]
+    );
+    assert_eq!(workchains[1].max_split, 8);
+    assert!(workchains[1].basic && workchains[1].active && workchains[1].accept_msgs);
+    assert_eq!(workchains[1].zerostate_file_hash, [0x44; 32]);
+
+    let gas = config.gas_prices(true).unwrap().unwrap();
+    assert_eq!(
+        (gas.flat_gas_limit, gas.gas_limit, gas.special_gas_limit),
+        (100, 1000000, 35000000)
+    );
+    assert_eq!(gas.compute_gas_fee(50), 10000);
+    assert_eq!(gas.compute_gas_fee(1100), 10000 + 10000000);
+    let gas = config.gas_prices(false).unwrap().unwrap();
+    assert_eq!((gas.flat_gas_limit, gas.special_gas_limit), (0, 1000000));
+    assert_eq!(gas.compute_gas_fee(3308), 1323200);
+
+    let forward = config.msg_forward_prices(false).unwrap().unwrap();
+    assert_eq!((forward.lump_price, forward.first_frac), (1000000, 21845));
+    assert_eq!(forward.compute_fwd_fee(1, 700), 1000000 + 280000 + 40000);
+    assert_eq!(
+        config.msg_forward_prices(true).unwrap().unwrap().lump_price,
+        10000000
+    );
+
+    assert_eq!(config.validators().unwrap().unwrap().list.len(), 2);
+    assert_eq!(config.next_validators(), Ok(None));
+    assert_eq!(config.prev_validators(), Ok(None));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_config_params() {
+    use tl::ton::lite_server::{ConfigInfo, GetConfigParams};
+
+    let keys = alloc::vec![KeyPair::generate(&mut OsRng)];
+    let config = test_config(test_validator_set(&keys, 1));
+    let extra = test_mc_state_extra(None, None, Some(config.clone()));
+    let state = test_shard_state(-1, i64::MIN, test_empty_accounts(), Some(extra.clone()));
+    let mut id = test_block_id(30);
+    let block = test_block(&id, &state, None);
+    id.root_hash = block.hash();
+
+    // keep path to gas prices of basechain only
+    let param = config_param_path(&config, 21);
+    let mut keep = alloc::vec![&extra];
+    keep.extend(&param);
+    let answer = ConfigInfo {
+        mode: 0,
+        id: id.clone(),
+        state_proof: merkle_proofs(&[(&block, &[&block.references()[0], &block.references()[2]])]),
+        config_proof: merkle_proofs(&[(&state, &keep)]),
+    };
+    let answer = tl::serialize(&tl::Boxed(answer));
+    let (public, port) = spawn_lite_server(move |query| {
+        let query: GetConfigParams = tl::deserialize(query).unwrap();
+        assert_eq!(query.param_list, [21]);
+        answer.clone()
+    })
+    .await;
+    let mut client = LiteClient::connect(public.as_bytes(), ("127.0.0.1", port))
+        .await
+        .unwrap();
+    let proven = client
+        .get_config_params(id.clone(), alloc::vec![21])
+        .await
+        .unwrap();
+    assert_eq!(proven.root().hash_at(0), config.hash());
+    assert_eq!(
+        proven.gas_prices(false).unwrap().unwrap().gas_price,
+        26214400
+    );
+    assert_eq!(
+        proven.gas_prices(true),
+        Err(boc::BocError::ExoticCell(boc::CellType::PrunedBranch))
+    );
+
+    id.file_hash = [0; 32];
+    assert!(matches!(
+        client.get_config_params(id, alloc::vec![21]).await,
+        Err(LiteError::ProofError(proof::ProofError::BlockMismatch))
+    ));
+}
+
+#[cfg(feature = "tokio")]
+/// Cells of configuration dictionary `root` on the path to param `index` and the param itself
+fn config_param_path(root: &boc::Cell, index: i32) -> Vec<boc::Cell> {
+    let target = config::config_param(root, index).unwrap().unwrap().hash();
+    fn walk(cell: &boc::Cell, target: &[u8; 32], path: &mut Vec<boc::Cell>) -> bool {
+        path.push(cell.clone());
+        if cell.hash() == *target
+            || cell
+                .references()
+                .iter()
+                .any(|reference| walk(reference, target, path))
+        {
+            return true;
+        }
+        path.pop();
+        false
+    }
+    let mut path = Vec::new();
+    assert!(walk(root, &target, &mut path));
+    let param = path.pop().unwrap();
+    path.extend(subtree(&param));
+    path
+}
+
+#[test]
+fn tvm_stack() {
+    use tl::ton::lite_server::AccountId;
+    use tvm::{Int257, StackError, StackValue};
+
+    let stack = tvm::serialize_stack(&[StackValue::Null, StackValue::from(7u64)]).unwrap();
+    let root = boc::parse_single(&stack).unwrap();
+    // depth, then tinyint on top and null in the rest
+    assert_eq!(hex::encode(root.data()), "000002010000000000000007");
+    assert_eq!(hex::encode(root.references()[0].data()), "00");
+    assert!(tvm::parse_stack(&tvm::serialize_stack(&[]).unwrap())
+        .unwrap()
+        .is_empty());
+
+    let big = Int257::from_u256([0xab; 32]);
+    let mut builder = boc::CellBuilder::new();
+    StackValue::Int(big).store(&mut builder).unwrap();
+    assert_eq!(builder.bit_len(), 15 + 257);
+    let slice = boc::CellBuilder::new()
+        .store_uint(0b1011, 4)
+        .unwrap()
+        .build()
+        .unwrap();
+    let values = alloc::vec![
+        StackValue::from(-1i64),
+        StackValue::from(u64::MAX),
+        StackValue::from(i128::MIN),
+        StackValue::Int(big),
+        StackValue::Nan,
+        StackValue::Cell(slice.clone()),
+        StackValue::Slice(slice.clone()),
+        StackValue::Builder(slice.clone()),
+        StackValue::Continuation(slice.clone()),
+        StackValue::Tuple(Vec::new()),
+        StackValue::Tuple((0..5u64).map(StackValue::from).collect()),
+        StackValue::Tuple(alloc::vec![
+            StackValue::Null,
+            StackValue::Tuple(alloc::vec![StackValue::from(true)]),
+        ]),
+    ];
+    let parsed = tvm::parse_stack(&tvm::serialize_stack(&values).unwrap()).unwrap();
+    assert_eq!(parsed, values);
+
+    // the first items of tuple are nested in its head
+    let mut builder = boc::CellBuilder::new();
+    StackValue::Tuple((0..3u64).map(StackValue::from).collect())
+        .store(&mut builder)
+        .unwrap();
+    let tuple = builder.build().unwrap();
+    assert_eq!(tuple.references()[0].references().len(), 2);
+    assert_eq!(
+        hex::encode(tuple.references()[1].data()),
+        "010000000000000002"
+    );
+
+    assert_eq!(u64::try_from(parsed[1].clone()), Ok(u64::MAX));
+    assert_eq!(
+        i64::try_from(parsed[1].clone()),
+        Err(StackError::IntegerOverflow)
+    );
+    assert_eq!(i128::try_from(parsed[2].clone()), Ok(i128::MIN));
+    assert_eq!(
+        u128::try_from(parsed[0].clone()),
+        Err(StackError::IntegerOverflow)
+    );
+    assert_eq!(
+        Int257::try_from(parsed[3].clone()).unwrap().to_u256(),
+        Some([0xab; 32])
+    );
+    assert_eq!(Int257::from(-5i64).to_u256(), None);
+    assert_eq!(
+        bool::try_from(parsed[11].clone()),
+        Err(StackError::UnexpectedType("integer"))
+    );
+    assert_eq!(
+        Int257::from_be_bytes([1; 33]),
+        Err(StackError::IntegerOverflow)
+    );
+
+    for workchain in [0, -1, 1000] {
+        let account = AccountId {
+            workchain,
+            id: [0x5a; 32],
+        };
+        let value = StackValue::from(account.clone());
+        assert_eq!(AccountId::try_from(value), Ok(account));
+    }
+    assert_eq!(
+        AccountId::try_from(StackValue::Slice(slice.clone())),
+        Err(StackError::InvalidValue("anycast address is not supported"))
+    );
+    assert_eq!(
+        AccountId::try_from(StackValue::Null),
+        Err(StackError::UnexpectedType("slice"))
+    );
+
+    // slice which takes bits 1..3 and the second reference of the cell
+    let mut builder = boc::CellBuilder::new();
+    builder.store_uint(0x04, 8).unwrap();
+    let mut cell = boc::CellBuilder::new();
+    cell.store_uint(0b0110, 4).unwrap();
+    cell.store_reference(boc::CellBuilder::new().build().unwrap())
+        .unwrap();
+    cell.store_reference(slice.clone()).unwrap();
+    builder.store_reference(cell.build().unwrap()).unwrap();
+    builder
+        .store_uint(1, 10)
+        .unwrap()
+        .store_uint(3, 10)
+        .unwrap();
+    builder.store_uint(1, 3).unwrap().store_uint(2, 3).unwrap();
+    let value = StackValue::load(&mut boc::CellSlice::new(&builder.build().unwrap()).unwrap());
+    let mut expected = boc::CellBuilder::new();
+    expected
+        .store_uint(0b11, 2)
+        .unwrap()
+        .store_reference(slice)
+        .unwrap();
+    assert_eq!(value, Ok(StackValue::Slice(expected.build().unwrap())));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_client_run_get_method() {
+    use tl::ton::lite_server::{AccountId, RunMethodResult, RunSmcMethod};
+    use tvm::StackValue;
+
+    let (public, port) = spawn_lite_server(|query| {
+        let query: RunSmcMethod = tl::deserialize(query).unwrap();
+        let mut stack = tvm::parse_stack(&query.params).unwrap();
+        stack.push(StackValue::from(query.method_id));
+        let exit_code = match query.method_id == smc_method_id("fail") {
+            true => 11,
+            false => 1,
+        };
+        tl::serialize(&tl::Boxed(RunMethodResult {
+            mode: query.mode,
+            id: query.id.clone(),
+            shardblk: query.id,
+            shard_proof: Some(Vec::new()),
+            proof: Some(Vec::new()),
+            state_proof: Some(Vec::new()),
+            init_c7: None,
+            lib_extras: None,
+            exit_code,
+            result: Some(tvm::serialize_stack(&stack).unwrap()),
+        }))
+    })
+    .await;
+    let mut client = LiteClient::connect(public.as_bytes(), ("127.0.0.1", port))
+        .await
+        .unwrap();
+    let account = AccountId {
+        workchain: 0,
+        id: [7; 32],
+    };
+    let stack = client
+        .run_get_method(
+            test_block_id(100),
+            account.clone(),
+            "get_wallet_address",
+            &[account.clone().into()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(stack.len(), 2);
+    assert_eq!(AccountId::try_from(stack[0].clone()), Ok(account.clone()));
+    assert_eq!(
+        i64::try_from(stack[1].clone()),
+        Ok(smc_method_id("get_wallet_address"))
+    );
+    assert!(matches!(
+        client
+            .run_get_method(test_block_id(100), account, "fail", &[])
+            .await,
+        Err(LiteError::GetMethodFailed(11))
+    ));
+}
+
+#[test]
+fn account_address() {
+    use address::{AccountAddress, AddressError, AddressFlags};
+    use base64::Engine as _;
+    use helper_types::CRC16;
+    use tl::ton::lite_server::AccountId;
+
+    let raw = "-1:3333333333333333333333333333333333333333333333333333333333333333";
+    let elector = AccountAddress::from_raw(raw).unwrap();
+    assert_eq!(elector, AccountAddress::new(-1, [0x33; 32]));
+    assert_eq!(elector.to_raw(), raw);
+    assert_eq!(raw.to_uppercase().parse(), Ok(elector));
+
+    for (bounceable, testnet) in [(true, false), (false, false), (true, true), (false, true)] {
+        let flags = AddressFlags {
+            bounceable,
+            testnet,
+        };
+        let friendly = elector.to_user_friendly(flags).unwrap();
+        assert_eq!(friendly.len(), 48);
+        assert_eq!(
+            AccountAddress::from_user_friendly(&friendly),
+            Ok((elector, flags))
+        );
+        assert_eq!(friendly.parse(), Ok(elector));
+    }
+    let flags = AddressFlags {
+        bounceable: true,
+        testnet: true,
+    };
+    let friendly = elector.to_user_friendly(flags).unwrap();
+    assert!(friendly.starts_with("kf8z"));
+    // standard base64 is accepted as well
+    let standard = friendly.replace('-', "+").replace('_', "/");
+    assert_eq!(
+        AccountAddress::from_user_friendly(&standard),
+        Ok((elector, flags))
+    );
+
+    let mut broken = friendly.into_bytes();
+    broken[10] = if broken[10] == b'A' { b'B' } else { b'A' };
+    assert_eq!(
+        AccountAddress::from_user_friendly(core::str::from_utf8(&broken).unwrap()),
+        Err(AddressError::InvalidChecksum)
+    );
+    let mut data = [0u8; 36];
+    data[0] = 0x12;
+    let crc = CRC16.checksum(&data[..34]);
+    data[34..].copy_from_slice(&crc.to_be_bytes());
+    let encoded = base64::engine::general_purpose::URL_SAFE.encode(data);
+    assert_eq!(
+        AccountAddress::from_user_friendly(&encoded),
+        Err(AddressError::InvalidTag(0x12))
+    );
+    for invalid in [
+        "0:1234",
+        "x:00",
+        "EQ",
+        "0:zz33333333333333333333333333333333333333333333333333333333333333",
+    ] {
+        assert_eq!(
+            invalid.parse::<AccountAddress>(),
+            Err(AddressError::InvalidFormat)
+        );
+    }
+    assert_eq!(
+        AccountAddress::new(1000, [0; 32]).to_user_friendly(AddressFlags::default()),
+        Err(AddressError::WorkchainOutOfRange(1000))
+    );
+
+    let account = AccountId::from(elector);
+    assert_eq!((account.workchain, account.id), (-1, [0x33; 32]));
+    assert_eq!(AccountAddress::from(account), elector);
+    assert_eq!(
+        AccountAddress::try_from(tvm::StackValue::from(elector)),
+        Ok(elector)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn account_address_serde() {
+    use address::AccountAddress;
+
+    let address = AccountAddress::new(0, [0xed; 32]);
+    let json = serde_json::to_string(&address).unwrap();
+    assert_eq!(json, format!("\"0:{}\"", "ed".repeat(32)));
+    assert_eq!(
+        serde_json::from_str::<AccountAddress>(&json).unwrap(),
+        address
+    );
+    let friendly = format!(
+        "\"{}\"",
+        address.to_user_friendly(Default::default()).unwrap()
+    );
+    assert_eq!(
+        serde_json::from_str::<AccountAddress>(&friendly).unwrap(),
+        address
+    );
+    assert!(serde_json::from_str::<AccountAddress>("\"0:ed\"").is_err());
+}
+
+#[test]
+fn adnl_address_text() {
+    use primitives::text::{base32_decode, base32_encode};
+
+    // RFC 4648 vectors in lowercase without padding
+    assert_eq!(base32_encode(b"foobar"), "mzxw6ytboi");
+    assert_eq!(base32_encode(b"fooba"), "mzxw6ytb");
+    assert_eq!(base32_decode("MZXW6YTBOI").unwrap(), b"foobar");
+    assert_eq!(base32_decode("mzxw1"), None);
+
+    let key = AdnlPublicKey::new([0x42; 32]);
+    let address = key.address();
+    assert_eq!(address, AdnlAddress::from_ed25519(&[0x42; 32]));
+    let hex = address.to_string();
+    assert_eq!(hex, hex::encode(address.as_bytes()));
+    let base32 = address.to_base32();
+    assert_eq!(base32.len(), 55);
+    for text in [
+        hex.clone(),
+        hex.to_uppercase(),
+        address.to_base64(),
+        address.to_base64().replace('+', "-").replace('/', "_"),
+        base32.clone(),
+        base32.to_uppercase(),
+    ] {
+        assert_eq!(text.parse::<AdnlAddress>(), Ok(address.clone()), "{text}");
+    }
+    let mut broken = base32.into_bytes();
+    broken[20] = if broken[20] == b'a' { b'b' } else { b'a' };
+    let broken = String::from_utf8(broken).unwrap();
+    assert_eq!(
+        AdnlAddress::from_base32(&broken),
+        Err(AdnlParseError::InvalidChecksum)
+    );
+    for invalid in [
+        "",
+        "00",
+        &hex[1..],
+        &"!".repeat(55),
+        "not base64 of 32 bytes, but of 44 chars!!!!",
+    ] {
+        assert_eq!(
+            invalid.parse::<AdnlAddress>(),
+            Err(AdnlParseError::InvalidFormat)
+        );
+    }
+
+    let base64 = "n4VDnSCUuSpjnCyUk9e3QOOd6o0ItSWYbTnW3Wnn8wk=";
+    let key = base64.parse::<AdnlPublicKey>().unwrap();
+    assert_eq!(key.to_string(), base64);
+    assert_eq!(hex::encode(key.as_bytes()).parse(), Ok(key));
+    assert!(PublicKey::try_from(key).is_ok());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn adnl_text_serde() {
+    let base64 = "n4VDnSCUuSpjnCyUk9e3QOOd6o0ItSWYbTnW3Wnn8wk=";
+    let key = base64.parse::<AdnlPublicKey>().unwrap();
+    let json = format!(r#"{{"@type":"pub.ed25519","key":"{base64}"}}"#);
+    assert_eq!(serde_json::to_string(&key).unwrap(), json);
+    assert_eq!(serde_json::from_str::<AdnlPublicKey>(&json).unwrap(), key);
+    assert_eq!(
+        serde_json::from_str::<AdnlPublicKey>(&format!("\"{base64}\"")).unwrap(),
+        key
+    );
+    assert!(serde_json::from_str::<AdnlPublicKey>(&json.replace("ed25519", "aes")).is_err());
+    assert!(serde_json::from_str::<AdnlPublicKey>(r#"{"@type":"pub.ed25519"}"#).is_err());
+
+    let address = key.address();
+    let json = serde_json::to_string(&address).unwrap();
+    assert_eq!(json, format!("\"{address}\""));
+    let base32 = format!("\"{}\"", address.to_base32());
+    assert_eq!(
+        serde_json::from_str::<AdnlAddress>(&base32).unwrap(),
+        address
+    );
+}
+
+#[cfg(feature = "global-config")]
+#[test]
+fn global_config_mainnet() {
+    use base64::Engine as _;
+    use global_config::GlobalConfig;
+
+    // mainnet config trimmed to one liteserver, signed static DHT nodes and the zero state
+    let config = GlobalConfig::load("tests/fixtures/global.config.json").unwrap();
+    assert_eq!(config.liteservers.len(), 1);
+    assert_eq!(
+        config.liteservers[0].addr(),
+        "5.9.10.47:19949".parse().unwrap()
+    );
+    assert_eq!(
+        config.liteservers[0].id.to_string(),
+        "n4VDnSCUuSpjnCyUk9e3QOOd6o0ItSWYbTnW3Wnn8wk="
+    );
+    let zero_state = &config.validator.zero_state;
+    assert_eq!(
+        (zero_state.workchain, zero_state.shard, zero_state.seqno),
+        (-1, i64::MIN, 0)
+    );
+    assert_eq!(
+        base64::engine::general_purpose::STANDARD.encode(zero_state.root_hash),
+        "F6OpKZKqvqeFp6CQmFomXNMfMj2EnaUSOXN+Mh+wVWk="
+    );
+    assert_eq!(config.validator.init_block, *zero_state);
+    assert!(config.validator.hardforks.is_empty());
+    assert_eq!((config.dht.k, config.dht.a), (6, 3));
+
+    let nodes = &config.dht.static_nodes;
+    assert_eq!(nodes.len(), 10);
+    assert_eq!(
+        nodes[0].id.to_string(),
+        "6PGkPQSbyFp12esf1NqmDOaLoFA8i9+Mp5+cAx5wtTU="
+    );
+    assert_eq!(
+        nodes[0].udp_addrs().collect::<Vec<_>>(),
+        ["185.86.79.9:22096".parse().unwrap()]
+    );
+    // nodes are signed by their own keys, so every parsed field must be exactly as published
+    assert!(nodes.iter().all(|node| node.check_signature()));
+    let mut node = nodes[0].clone();
+    node.addr_list.reinit_date += 1;
+    assert!(!node.check_signature());
+}
+
+#[cfg(all(feature = "global-config", feature = "tokio"))]
+fn test_global_config(liteservers: &[(i64, u16, &PublicKey)]) -> String {
+    use base64::Engine as _;
+
+    let base64 = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+    let block = |seqno: i32| {
+        format!(
+            r#"{{"workchain": -1, "shard": -9223372036854775808, "seqno": {seqno}, "root_hash": "{}", "file_hash": "{}"}}"#,
+            base64(&[seqno as u8; 32]),
+            base64(&[0xff; 32])
+        )
+    };
+    let liteservers = liteservers
+        .iter()
+        .map(|(ip, port, key)| {
+            format!(
+                r#"{{"ip": {ip}, "port": {port}, "id": {{"@type": "pub.ed25519", "key": "{}"}}}}"#,
+                base64(key.as_bytes())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"{{
+            "@type": "config.global",
+            "dht": {{
+                "@type": "dht.config.global", "k": 6, "a": 3,
+                "static_nodes": {{"@type": "dht.nodes", "nodes": [{{
+                    "@type": "dht.node",
+                    "id": {{"@type": "pub.ed25519", "key": "{}"}},
+                    "addr_list": {{
+                        "@type": "adnl.addressList",
+                        "addrs": [
+                            {{"@type": "adnl.address.udp", "ip": -1185526007, "port": 22096}},
+                            {{"@type": "adnl.address.tunnel", "to": "{}", "pubkey": {{"@type": "pub.ed25519", "key": "{}"}}}},
+                            {{"@type": "adnl.address.reverse"}}
+                        ],
+                        "version": 0, "reinit_date": 1700000000, "priority": 1, "expire_at": 0
+                    }},
+                    "version": -1,
+                    "signature": "{}"
+                }}]}}
+            }},
+            "liteservers": [{liteservers}],
+            "validator": {{
+                "@type": "validator.config.global",
+                "zero_state": {},
+                "init_block": {},
+                "hardforks": [{}]
+            }}
+        }}"#,
+        base64(&[0x11; 32]),
+        base64(&[0x33; 32]),
+        base64(&[0x44; 32]),
+        base64(&[0x22; 64]),
+        block(0),
+        block(20),
+        block(10),
+    )
+}
+
+#[cfg(all(feature = "global-config", feature = "tokio"))]
+#[tokio::test]
+async fn global_config_connect() {
+    use global_config::{GlobalConfig, GlobalConfigError};
+
+    let (public, port) = spawn_lite_server(lite_server_stub).await;
+    // port of a closed listener refuses connections
+    let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed_port = closed.local_addr().unwrap().port();
+    drop(closed);
+    let localhost = 0x7f000001;
+    let json = test_global_config(&[
+        (localhost, closed_port, &public),
+        (localhost, port, &public),
+    ]);
+    let config = GlobalConfig::from_json(&json).unwrap();
+
+    let node = &config.dht.static_nodes[0];
+    assert_eq!(
+        node.udp_addrs().collect::<Vec<_>>(),
+        ["185.86.79.9:22096".parse().unwrap()]
+    );
+    assert_eq!(node.addr_list.addrs.len(), 3);
+    assert_eq!(
+        (node.addr_list.reinit_date, node.addr_list.priority),
+        (1700000000, 1)
+    );
+    assert_eq!((node.version, node.signature.len()), (-1, 64));
+    assert!(!node.check_signature());
+    assert_eq!(node.id, AdnlPublicKey::new([0x11; 32]));
+    assert_eq!(config.validator.init_block.seqno, 20);
+    assert_eq!(config.validator.hardforks[0].seqno, 10);
+
+    let results = config.connect_all().await;
+    assert!(results[0].is_err());
+    let mut client = LiteClient::new(results.into_iter().nth(1).unwrap().unwrap());
+    assert_eq!(client.get_time().await.unwrap(), 1700000000);
+    for _ in 0..4 {
+        let mut client = LiteClient::new(config.connect_any().await.unwrap());
+        assert_eq!(client.get_time().await.unwrap(), 1700000000);
+    }
+
+    let json = test_global_config(&[(localhost, closed_port, &public)]);
+    let config = GlobalConfig::from_json(&json).unwrap();
+    assert!(matches!(
+        config.connect_any().await,
+        Err(GlobalConfigError::AdnlError(_))
+    ));
+    let json = test_global_config(&[]);
+    let config = GlobalConfig::from_json(&json).unwrap();
+    assert!(matches!(
+        config.connect_any().await,
+        Err(GlobalConfigError::NoLiteServers)
+    ));
+    assert!(matches!(
+        GlobalConfig::from_json(&json.replace("\"k\"", "\"x\"")),
+        Err(GlobalConfigError::JsonError(_))
+    ));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_balancer() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tl::ton::lite_server::*;
+    use tl::{Boxed, TlConstructor};
+
+    fn query_id(query: &[u8]) -> u32 {
+        u32::from_le_bytes(query[..4].try_into().unwrap())
+    }
+    fn error(code: i32) -> Vec<u8> {
+        tl::serialize(&Boxed(Error {
+            code,
+            message: "error".to_owned(),
+        }))
+    }
+
+    let (lagging, lagging_port) = spawn_lite_server(|query| match query_id(query) {
+        GetMasterchainInfo::TL_ID => tl::serialize(&Boxed(MasterchainInfo {
+            last: test_block_id(80),
+            state_root_hash: [5; 32],
+            init: test_zero_state(),
+        })),
+        _ => lite_server_stub(query),
+    })
+    .await;
+    let recovered = Arc::new(AtomicBool::new(false));
+    let flaky_recovered = recovered.clone();
+    let (flaky, flaky_port) = spawn_lite_server(move |query| match query_id(query) {
+        GetTime::TL_ID if !flaky_recovered.load(Ordering::SeqCst) => {
+            error(LiteServerError::NOT_READY)
+        }
+        GetVersion::TL_ID => error(400),
+        _ => lite_server_stub(query),
+    })
+    .await;
+    // the only healthy server is the slowest one
+    let (healthy, healthy_port) =
+        spawn_slow_lite_server(Duration::from_millis(200), lite_server_stub).await;
+    let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed_port = closed.local_addr().unwrap().port();
+    drop(closed);
+
+    let address = |port| ([127, 0, 0, 1], port).into();
+    let mut balancer = LiteBalancer::connect([
+        (
+            AdnlPublicKey::new(lagging.to_bytes()),
+            address(lagging_port),
+        ),
+        (AdnlPublicKey::new(flaky.to_bytes()), address(flaky_port)),
+        (
+            AdnlPublicKey::new(healthy.to_bytes()),
+            address(healthy_port),
+        ),
+        (AdnlPublicKey::new(healthy.to_bytes()), address(closed_port)),
+    ])
+    .await
+    .with_circuit_breaker(2, Duration::from_secs(60))
+    .with_refresh_interval(Duration::from_secs(3600));
+    let stats = balancer.stats();
+    assert_eq!(balancer.last_seqno(), Some(100));
+    assert_eq!(stats[0].last_seqno, Some(80));
+    let lagging_latency = stats[0].latency;
+    assert!(stats[2].latency.unwrap() >= Duration::from_millis(200));
+    assert!(stats[1].latency.unwrap() < stats[2].latency.unwrap());
+    assert!(!stats[3].connected);
+    assert_eq!(stats[3].consecutive_failures, 1);
+
+    // invalid query is not retried on other servers
+    let result = balancer.query(&GetVersion).await;
+    assert!(matches!(
+        result,
+        Err(LiteError::ServerError(LiteServerError { code: 400, .. }))
+    ));
+    assert_eq!(balancer.stats()[1].consecutive_failures, 0);
+
+    // lagging server is skipped, flaky one is tried first and the query is retried
+    for failures in 1..=2 {
+        assert_eq!(balancer.query(&GetTime).await.unwrap().0.now, 1700000000);
+        let stats = balancer.stats();
+        assert_eq!(stats[0].latency, lagging_latency);
+        assert_eq!(stats[1].consecutive_failures, failures);
+        assert_eq!(stats[2].consecutive_failures, 0);
+    }
+    assert_eq!(balancer.stats()[1].circuit, CircuitState::Open);
+    assert_eq!(balancer.query(&GetTime).await.unwrap().0.now, 1700000000);
+    assert_eq!(balancer.stats()[1].consecutive_failures, 2);
+
+    // skip the cooldown without waiting for it
+    tokio::time::pause();
+    tokio::time::advance(Duration::from_secs(61)).await;
+    tokio::time::resume();
+    assert_eq!(balancer.stats()[1].circuit, CircuitState::HalfOpen);
+    recovered.store(true, Ordering::SeqCst);
+    balancer.refresh().await;
+    let stats = balancer.stats();
+    assert_eq!(stats[1].circuit, CircuitState::Closed);
+    assert_eq!(stats[1].consecutive_failures, 0);
+    assert_eq!(stats[3].circuit, CircuitState::Open);
+    assert_eq!(stats[3].consecutive_failures, 2);
+    assert_eq!(balancer.query(&GetTime).await.unwrap().0.now, 1700000000);
+
+    let mut balancer = LiteBalancer::<TcpStream>::new([]);
+    assert!(matches!(
+        balancer.query(&GetTime).await,
+        Err(LiteError::NoAvailableServers)
+    ));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn lite_balancer_stale_seqno() {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tl::ton::lite_server::*;
+    use tl::{Boxed, TlConstructor};
+
+    let down = Arc::new(AtomicBool::new(false));
+    let queries = Arc::new(AtomicUsize::new(0));
+    let (server_down, server_queries) = (down.clone(), queries.clone());
+    let (fast, fast_port) =
+        spawn_lite_server(
+            move |query| match u32::from_le_bytes(query[..4].try_into().unwrap()) {
+                GetMasterchainInfo::TL_ID if server_down.load(Ordering::SeqCst) => {
+                    tl::serialize(&Boxed(Error {
+                        code: LiteServerError::NOT_READY,
+                        message: "not ready".to_owned(),
+                    }))
+                }
+                GetTime::TL_ID => {
+                    server_queries.fetch_add(1, Ordering::SeqCst);
+                    lite_server_stub(query)
+                }
+                _ => lite_server_stub(query),
+            },
+        )
+        .await;
+    let (slow, slow_port) =
+        spawn_slow_lite_server(Duration::from_millis(100), lite_server_stub).await;
+
+    let address = |port| ([127, 0, 0, 1], port).into();
+    // the largest lag must not wrap around and exclude every server
+    let mut balancer = LiteBalancer::connect([
+        (AdnlPublicKey::new(fast.to_bytes()), address(fast_port)),
+        (AdnlPublicKey::new(slow.to_bytes()), address(slow_port)),
+    ])
+    .await
+    .with_max_lag(u32::MAX)
+    .with_refresh_interval(Duration::from_secs(3600));
+    assert_eq!(balancer.query(&GetTime).await.unwrap().0.now, 1700000000);
+    assert_eq!(queries.load(Ordering::SeqCst), 1);
+
+    // the fast server keeps answering queries, but its seqno is unknown after a failed poll
+    down.store(true, Ordering::SeqCst);
+    balancer.refresh().await;
+    let stats = balancer.stats();
+    assert_eq!(
+        (stats[0].last_seqno, stats[0].circuit),
+        (None, CircuitState::Closed)
+    );
+    assert_eq!(stats[1].last_seqno, Some(100));
+    assert_eq!(balancer.query(&GetTime).await.unwrap().0.now, 1700000000);
+    assert_eq!(queries.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,74 @@
+//! Helpers for block ids of generated `tonNode` types
+
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use super::ton::ton_node::{BlockId, BlockIdExt};
+
+/// Workchain id of masterchain
+pub const MASTERCHAIN: i32 = -1;
+/// Shard prefix of shard which covers the whole workchain
+pub const SHARD_FULL: i64 = i64::MIN;
+
+impl BlockId {
+    pub fn new(workchain: i32, shard: i64, seqno: i32) -> Self {
+        Self {
+            workchain,
+            shard,
+            seqno,
+        }
+    }
+
+    pub fn is_masterchain(&self) -> bool {
+        self.workchain == MASTERCHAIN
+    }
+}
+
+impl BlockIdExt {
+    /// Get short id of this block without hashes
+    pub fn block_id(&self) -> BlockId {
+        BlockId::new(self.workchain, self.shard, self.seqno)
+    }
+
+    pub fn is_masterchain(&self) -> bool {
+        self.workchain == MASTERCHAIN
+    }
+}
+
+impl Eq for BlockId {}
+
+impl Hash for BlockId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.workchain, self.shard, self.seqno).hash(state);
+    }
+}
+
+impl Eq for BlockIdExt {}
+
+impl Hash for BlockIdExt {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.block_id().hash(state);
+        self.root_hash.hash(state);
+    }
+}
+
+/// Formatted as `(workchain,shard,seqno)` with shard in hex, as in TON tools
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{:016x},{})", self.workchain, self.shard, self.seqno)
+    }
+}
+
+/// Formatted as `(workchain,shard,seqno):root_hash:file_hash` with hashes in uppercase hex,
+/// as in TON tools
+impl fmt::Display for BlockIdExt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.block_id(),
+            hex::encode_upper(self.root_hash),
+            hex::encode_upper(self.file_hash)
+        )
+    }
+}
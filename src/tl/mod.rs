@@ -0,0 +1,124 @@
+//! TL (Type Language) serialization used by all payloads carried over ADNL.
+//!
+//! Types are bare by default: they are serialized without constructor id, as in TL fields
+//! with lowercase type names. Boxed types are prefixed with 4-byte constructor id, use
+//! `#[tl(boxed)]` or [`Boxed`] for them.
+//!
+//! ```
+//! use adnl::tl::{self, TlRead, TlWrite};
+//!
+//! #[derive(Debug, PartialEq, TlWrite, TlRead)]
+//! #[tl(boxed, schema = "liteServer.currentTime now:int = liteServer.CurrentTime")]
+//! struct CurrentTime {
+//!     now: i32,
+//! }
+//!
+//! let packet = tl::serialize(&CurrentTime { now: 1 });
+//! assert_eq!(packet, [0x0d, 0x00, 0x53, 0xe9, 0x01, 0x00, 0x00, 0x00]);
+//! assert_eq!(tl::deserialize::<CurrentTime>(&packet).unwrap(), CurrentTime { now: 1 });
+//! ```
+
+mod block_id;
+mod primitives;
+#[allow(clippy::module_inception)]
+pub mod ton;
+
+use alloc::vec::Vec;
+use thiserror::Error;
+
+pub use adnl_tl_derive::{TlRead, TlWrite};
+pub use block_id::{MASTERCHAIN, SHARD_FULL};
+pub use primitives::{check_bytes_len, read_bytes, write_bytes, MAX_BYTES_LEN};
+
+/// Items used by derived and generated code
+#[doc(hidden)]
+pub mod __private {
+    pub use super::{Boxed, TlFunction, TlRead, TlWrite};
+    pub use alloc::{string::String, vec::Vec};
+}
+
+/// TL deserialization error
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TlError {
+    #[error("Unexpected end of TL packet")]
+    UnexpectedEof,
+    #[error("Unknown TL constructor {0:#010x}")]
+    UnknownConstructor(u32),
+    #[error("Trailing bytes after TL object")]
+    TrailingBytes,
+    #[error("Invalid TL data")]
+    InvalidData,
+    #[error("TL bytes are longer than {MAX_BYTES_LEN} bytes")]
+    TooLong,
+}
+
+pub type TlResult<T> = Result<T, TlError>;
+
+/// Type which can be serialized to TL
+pub trait TlWrite {
+    /// Append serialized `self` to `packet`
+    ///
+    /// # Panics
+    ///
+    /// If `self` has `bytes` or `string` longer than [`MAX_BYTES_LEN`], which can not be
+    /// represented in TL. Check data from outside with [`check_bytes_len`] before.
+    fn write_to(&self, packet: &mut Vec<u8>);
+}
+
+/// Type which can be deserialized from TL
+pub trait TlRead: Sized {
+    /// Deserialize value from the beginning of `packet`, advancing it past the read bytes
+    fn read_from(packet: &mut &[u8]) -> TlResult<Self>;
+}
+
+/// Type with known constructor id, implemented by `#[derive(TlWrite)]` if `schema` or `id`
+/// is given
+pub trait TlConstructor {
+    const TL_ID: u32;
+}
+
+/// TL function, whose answer is deserialized as `Reply`
+pub trait TlFunction: TlWrite {
+    type Reply: TlRead;
+}
+
+/// Wrapper which serializes bare type `T` as boxed, prefixed with its constructor id
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Boxed<T>(pub T);
+
+impl<T: TlConstructor + TlWrite> TlWrite for Boxed<T> {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        T::TL_ID.write_to(packet);
+        self.0.write_to(packet);
+    }
+}
+
+impl<T: TlConstructor + TlRead> TlRead for Boxed<T> {
+    fn read_from(packet: &mut &[u8]) -> TlResult<Self> {
+        let id = u32::read_from(packet)?;
+        if id != T::TL_ID {
+            return Err(TlError::UnknownConstructor(id));
+        }
+        T::read_from(packet).map(Boxed)
+    }
+}
+
+/// Serialize `value` into new buffer
+///
+/// # Panics
+///
+/// If `value` has `bytes` or `string` longer than [`MAX_BYTES_LEN`], see [`TlWrite::write_to`]
+pub fn serialize<T: TlWrite + ?Sized>(value: &T) -> Vec<u8> {
+    let mut packet = Vec::new();
+    value.write_to(&mut packet);
+    packet
+}
+
+/// Deserialize `packet`, which must contain exactly one value
+pub fn deserialize<T: TlRead>(mut packet: &[u8]) -> TlResult<T> {
+    let value = T::read_from(&mut packet)?;
+    if !packet.is_empty() {
+        return Err(TlError::TrailingBytes);
+    }
+    Ok(value)
+}
@@ -0,0 +1,210 @@
+//! TL built-in types: `int`, `long`, `int128`, `int256`, `double`, `Bool`, `bytes`,
+//! `string` and `vector`
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{TlError, TlRead, TlResult, TlWrite};
+
+/// `boolTrue = Bool`
+const BOOL_TRUE: u32 = 0x997275b5;
+/// `boolFalse = Bool`
+const BOOL_FALSE: u32 = 0xbc799737;
+
+fn take<'a>(packet: &mut &'a [u8], length: usize) -> TlResult<&'a [u8]> {
+    if packet.len() < length {
+        return Err(TlError::UnexpectedEof);
+    }
+    let (head, tail) = packet.split_at(length);
+    *packet = tail;
+    Ok(head)
+}
+
+fn take_array<const N: usize>(packet: &mut &[u8]) -> TlResult<[u8; N]> {
+    Ok(take(packet, N)?.try_into().unwrap())
+}
+
+/// Maximum length of TL `bytes` and `string`, whose length prefix is 3 bytes long
+pub const MAX_BYTES_LEN: usize = (1 << 24) - 1;
+
+/// Check that `data` fits into TL `bytes`
+pub fn check_bytes_len(data: &[u8]) -> TlResult<()> {
+    if data.len() > MAX_BYTES_LEN {
+        return Err(TlError::TooLong);
+    }
+    Ok(())
+}
+
+/// Append `data` serialized as TL `bytes`: length prefix of 1 or 4 bytes, then data
+/// padded with zeroes to multiple of 4 bytes
+///
+/// # Panics
+///
+/// If `data` is longer than [`MAX_BYTES_LEN`]
+pub fn write_bytes(data: &[u8], packet: &mut Vec<u8>) {
+    let prefix = if data.len() < 254 {
+        packet.push(data.len() as u8);
+        1
+    } else {
+        assert!(data.len() <= MAX_BYTES_LEN, "TL bytes are too long");
+        packet.push(254);
+        packet.extend_from_slice(&(data.len() as u32).to_le_bytes()[..3]);
+        4
+    };
+    packet.extend_from_slice(data);
+    let padding = (4 - (prefix + data.len()) % 4) % 4;
+    packet.extend_from_slice(&[0u8; 3][..padding]);
+}
+
+/// Read TL `bytes` from `packet`, skipping padding
+pub fn read_bytes<'a>(packet: &mut &'a [u8]) -> TlResult<&'a [u8]> {
+    let (prefix, length) = match take(packet, 1)?[0] {
+        254 => {
+            let length = take_array::<3>(packet)?;
+            (
+                4,
+                u32::from_le_bytes([length[0], length[1], length[2], 0]) as usize,
+            )
+        }
+        255 => return Err(TlError::InvalidData),
+        length => (1, length as usize),
+    };
+    let data = take(packet, length)?;
+    take(packet, (4 - (prefix + length) % 4) % 4)?;
+    Ok(data)
+}
+
+macro_rules! impl_le_number {
+    ($($ty:ty),*) => {
+        $(
+            impl TlWrite for $ty {
+                fn write_to(&self, packet: &mut Vec<u8>) {
+                    packet.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+
+            impl TlRead for $ty {
+                fn read_from(packet: &mut &[u8]) -> TlResult<Self> {
+                    take_array(packet).map(<$ty>::from_le_bytes)
+                }
+            }
+        )*
+    };
+}
+
+// `int`, `#`, `long` and `double`
+impl_le_number!(i32, u32, i64, u64, f64);
+
+/// `int128`, `int256` and `int512` are written as is
+impl<const N: usize> TlWrite for [u8; N] {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        packet.extend_from_slice(self);
+    }
+}
+
+impl<const N: usize> TlRead for [u8; N] {
+    fn read_from(packet: &mut &[u8]) -> TlResult<Self> {
+        take_array(packet)
+    }
+}
+
+/// Boxed `Bool`
+impl TlWrite for bool {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        let id = if *self { BOOL_TRUE } else { BOOL_FALSE };
+        id.write_to(packet);
+    }
+}
+
+impl TlRead for bool {
+    fn read_from(packet: &mut &[u8]) -> TlResult<Self> {
+        match u32::read_from(packet)? {
+            BOOL_TRUE => Ok(true),
+            BOOL_FALSE => Ok(false),
+            id => Err(TlError::UnknownConstructor(id)),
+        }
+    }
+}
+
+impl TlWrite for [u8] {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        write_bytes(self, packet);
+    }
+}
+
+impl TlWrite for Vec<u8> {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        write_bytes(self, packet);
+    }
+}
+
+impl TlRead for Vec<u8> {
+    fn read_from(packet: &mut &[u8]) -> TlResult<Self> {
+        read_bytes(packet).map(<[u8]>::to_vec)
+    }
+}
+
+impl TlWrite for str {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        write_bytes(self.as_bytes(), packet);
+    }
+}
+
+impl TlWrite for String {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        write_bytes(self.as_bytes(), packet);
+    }
+}
+
+impl TlRead for String {
+    fn read_from(packet: &mut &[u8]) -> TlResult<Self> {
+        let data = read_bytes(packet)?;
+        String::from_utf8(data.to_vec()).map_err(|_| TlError::InvalidData)
+    }
+}
+
+/// Bare `vector t`: number of elements, then elements
+impl<T: TlWrite> TlWrite for [T] {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        (self.len() as u32).write_to(packet);
+        for item in self {
+            item.write_to(packet);
+        }
+    }
+}
+
+impl<T: TlWrite> TlWrite for Vec<T> {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        self.as_slice().write_to(packet);
+    }
+}
+
+impl<T: TlRead> TlRead for Vec<T> {
+    fn read_from(packet: &mut &[u8]) -> TlResult<Self> {
+        let length = u32::read_from(packet)? as usize;
+        // every element takes at least 4 bytes, do not preallocate for malformed length
+        if length > packet.len() / 4 {
+            return Err(TlError::UnexpectedEof);
+        }
+        (0..length).map(|_| T::read_from(packet)).collect()
+    }
+}
+
+impl<T: TlWrite + ?Sized> TlWrite for &T {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        (**self).write_to(packet);
+    }
+}
+
+impl<T: TlWrite + ?Sized> TlWrite for Box<T> {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        (**self).write_to(packet);
+    }
+}
+
+impl<T: TlRead> TlRead for Box<T> {
+    fn read_from(packet: &mut &[u8]) -> TlResult<Self> {
+        T::read_from(packet).map(Box::new)
+    }
+}
@@ -0,0 +1,1667 @@
+// Generated by adnl-tl-codegen from TL schemas, do not edit.
+
+use ::adnl::tl::__private::*;
+
+/// `PrivateKey`
+#[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+pub enum PrivateKey {
+    PkUnenc(pk::Unenc),
+    PkEd25519(pk::Ed25519),
+    PkAes(pk::Aes),
+    PkOverlay(pk::Overlay),
+}
+
+/// `PublicKey`
+#[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+pub enum PublicKey {
+    PubUnenc(r#pub::Unenc),
+    PubEd25519(r#pub::Ed25519),
+    PubAes(r#pub::Aes),
+    PubOverlay(r#pub::Overlay),
+}
+
+pub mod adnl {
+    use ::adnl::tl::__private::*;
+
+    /// `adnl.addressList addrs:vector adnl.Address version:int reinit_date:int priority:int expire_at:int = adnl.AddressList`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x2227e658)]
+    pub struct AddressList {
+        pub addrs: Vec<Address>,
+        pub version: i32,
+        pub reinit_date: i32,
+        pub priority: i32,
+        pub expire_at: i32,
+    }
+
+    /// `adnl.node id:PublicKey addr_list:adnl.addressList = adnl.Node`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x6b561285)]
+    pub struct Node {
+        pub id: super::PublicKey,
+        pub addr_list: AddressList,
+    }
+
+    /// `adnl.nodes nodes:vector adnl.node = adnl.Nodes`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xa209db56)]
+    pub struct Nodes {
+        pub nodes: Vec<Node>,
+    }
+
+    /// `adnl.packetContents rand1:bytes flags:# from:flags.0?PublicKey from_short:flags.1?adnl.id.short message:flags.2?adnl.Message messages:flags.3?vector adnl.Message address:flags.4?adnl.addressList priority_address:flags.5?adnl.addressList seqno:flags.6?long confirm_seqno:flags.7?long recv_addr_list_version:flags.8?int recv_priority_addr_list_version:flags.9?int reinit_date:flags.10?int dst_reinit_date:flags.10?int signature:flags.11?bytes rand2:bytes = adnl.PacketContents`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xd142cd89)]
+    pub struct PacketContents {
+        pub rand1: Vec<u8>,
+        #[tl(flags)]
+        pub flags: u32,
+        #[tl(flags_bit = "flags.0")]
+        pub from: Option<super::PublicKey>,
+        #[tl(flags_bit = "flags.1")]
+        pub from_short: Option<id::Short>,
+        #[tl(flags_bit = "flags.2")]
+        pub message: Option<Message>,
+        #[tl(flags_bit = "flags.3")]
+        pub messages: Option<Vec<Message>>,
+        #[tl(flags_bit = "flags.4")]
+        pub address: Option<AddressList>,
+        #[tl(flags_bit = "flags.5")]
+        pub priority_address: Option<AddressList>,
+        #[tl(flags_bit = "flags.6")]
+        pub seqno: Option<i64>,
+        #[tl(flags_bit = "flags.7")]
+        pub confirm_seqno: Option<i64>,
+        #[tl(flags_bit = "flags.8")]
+        pub recv_addr_list_version: Option<i32>,
+        #[tl(flags_bit = "flags.9")]
+        pub recv_priority_addr_list_version: Option<i32>,
+        #[tl(flags_bit = "flags.10")]
+        pub reinit_date: Option<i32>,
+        #[tl(flags_bit = "flags.10")]
+        pub dst_reinit_date: Option<i32>,
+        #[tl(flags_bit = "flags.11")]
+        pub signature: Option<Vec<u8>>,
+        pub rand2: Vec<u8>,
+    }
+
+    /// `adnl.pong value:long = adnl.Pong`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x20747c0e)]
+    pub struct Pong {
+        pub value: i64,
+    }
+
+    /// `adnl.ping value:long = adnl.Pong`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x1faaa1bf)]
+    pub struct Ping {
+        pub value: i64,
+    }
+
+    impl TlFunction for Ping {
+        type Reply = Boxed<Pong>;
+    }
+
+    /// `adnl.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum Message {
+        MessageQuery(message::Query),
+        MessageAnswer(message::Answer),
+        MessageCreateChannel(message::CreateChannel),
+        MessageConfirmChannel(message::ConfirmChannel),
+        MessageCustom(message::Custom),
+        MessageNop(message::Nop),
+        MessageReinit(message::Reinit),
+        MessagePart(message::Part),
+    }
+
+    /// `adnl.Address`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum Address {
+        AddressUdp(address::Udp),
+        AddressUdp6(address::Udp6),
+        AddressTunnel(address::Tunnel),
+        AddressReverse(address::Reverse),
+    }
+
+    pub mod address {
+        use ::adnl::tl::__private::*;
+
+        /// `adnl.address.udp ip:int port:int = adnl.Address`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x670da6e7)]
+        pub struct Udp {
+            pub ip: i32,
+            pub port: i32,
+        }
+
+        /// `adnl.address.udp6 ip:int128 port:int = adnl.Address`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0xe31d63fa)]
+        pub struct Udp6 {
+            pub ip: [u8; 16],
+            pub port: i32,
+        }
+
+        /// `adnl.address.tunnel to:int256 pubkey:PublicKey = adnl.Address`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x092b02eb)]
+        pub struct Tunnel {
+            pub to: [u8; 32],
+            pub pubkey: super::super::PublicKey,
+        }
+
+        /// `adnl.address.reverse = adnl.Address`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x27795286)]
+        pub struct Reverse;
+    }
+
+    pub mod id {
+        use ::adnl::tl::__private::*;
+
+        /// `adnl.id.short id:int256 = adnl.id.Short`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x3e3f654f)]
+        pub struct Short {
+            pub id: [u8; 32],
+        }
+    }
+
+    pub mod message {
+        use ::adnl::tl::__private::*;
+
+        /// `adnl.message.query query_id:int256 query:bytes = adnl.Message`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0xb48bf97a)]
+        pub struct Query {
+            pub query_id: [u8; 32],
+            pub query: Vec<u8>,
+        }
+
+        /// `adnl.message.answer query_id:int256 answer:bytes = adnl.Message`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x0fac8416)]
+        pub struct Answer {
+            pub query_id: [u8; 32],
+            pub answer: Vec<u8>,
+        }
+
+        /// `adnl.message.createChannel key:int256 date:int = adnl.Message`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0xe673c3bb)]
+        pub struct CreateChannel {
+            pub key: [u8; 32],
+            pub date: i32,
+        }
+
+        /// `adnl.message.confirmChannel key:int256 peer_key:int256 date:int = adnl.Message`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x60dd1d69)]
+        pub struct ConfirmChannel {
+            pub key: [u8; 32],
+            pub peer_key: [u8; 32],
+            pub date: i32,
+        }
+
+        /// `adnl.message.custom data:bytes = adnl.Message`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x204818f5)]
+        pub struct Custom {
+            pub data: Vec<u8>,
+        }
+
+        /// `adnl.message.nop = adnl.Message`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x17f8dfda)]
+        pub struct Nop;
+
+        /// `adnl.message.reinit date:int = adnl.Message`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x10c20520)]
+        pub struct Reinit {
+            pub date: i32,
+        }
+
+        /// `adnl.message.part hash:int256 total_size:int offset:int data:bytes = adnl.Message`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0xfd452d39)]
+        pub struct Part {
+            pub hash: [u8; 32],
+            pub total_size: i32,
+            pub offset: i32,
+            pub data: Vec<u8>,
+        }
+    }
+}
+
+pub mod dht {
+    use ::adnl::tl::__private::*;
+
+    /// `dht.node id:PublicKey addr_list:adnl.addressList version:int signature:bytes = dht.Node`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x84533248)]
+    pub struct Node {
+        pub id: super::PublicKey,
+        pub addr_list: super::adnl::AddressList,
+        pub version: i32,
+        pub signature: Vec<u8>,
+    }
+
+    /// `dht.nodes nodes:vector dht.node = dht.Nodes`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x7974a0be)]
+    pub struct Nodes {
+        pub nodes: Vec<Node>,
+    }
+
+    /// `dht.key id:int256 name:bytes idx:int = dht.Key`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xf667de8f)]
+    pub struct Key {
+        pub id: [u8; 32],
+        pub name: Vec<u8>,
+        pub idx: i32,
+    }
+
+    /// `dht.keyDescription key:dht.key id:PublicKey update_rule:dht.UpdateRule signature:bytes = dht.KeyDescription`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x281d4e05)]
+    pub struct KeyDescription {
+        pub key: Key,
+        pub id: super::PublicKey,
+        pub update_rule: UpdateRule,
+        pub signature: Vec<u8>,
+    }
+
+    /// `dht.value key:dht.keyDescription value:bytes ttl:int signature:bytes = dht.Value`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x90ad27cb)]
+    pub struct Value {
+        pub key: KeyDescription,
+        pub value: Vec<u8>,
+        pub ttl: i32,
+        pub signature: Vec<u8>,
+    }
+
+    /// `dht.pong random_id:long = dht.Pong`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x5a8aef81)]
+    pub struct Pong {
+        pub random_id: i64,
+    }
+
+    /// `dht.valueNotFound nodes:dht.nodes = dht.ValueResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xa2620568)]
+    pub struct ValueNotFound {
+        pub nodes: Nodes,
+    }
+
+    /// `dht.valueFound value:dht.Value = dht.ValueResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xe40cf774)]
+    pub struct ValueFound {
+        pub value: Boxed<Value>,
+    }
+
+    /// `dht.stored = dht.Stored`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x7026fb08)]
+    pub struct Stored;
+
+    /// `dht.message node:dht.node = dht.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xbc0cdb8e)]
+    pub struct Message {
+        pub node: Node,
+    }
+
+    /// `dht.ping random_id:long = dht.Pong`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xcbeb3f18)]
+    pub struct Ping {
+        pub random_id: i64,
+    }
+
+    impl TlFunction for Ping {
+        type Reply = Boxed<Pong>;
+    }
+
+    /// `dht.store value:dht.value = dht.Stored`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x34934212)]
+    pub struct Store {
+        pub value: Value,
+    }
+
+    impl TlFunction for Store {
+        type Reply = Boxed<Stored>;
+    }
+
+    /// `dht.findNode key:int256 k:int = dht.Nodes`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x6ce2ce6b)]
+    pub struct FindNode {
+        pub key: [u8; 32],
+        pub k: i32,
+    }
+
+    impl TlFunction for FindNode {
+        type Reply = Boxed<Nodes>;
+    }
+
+    /// `dht.findValue key:int256 k:int = dht.ValueResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xae4b6011)]
+    pub struct FindValue {
+        pub key: [u8; 32],
+        pub k: i32,
+    }
+
+    impl TlFunction for FindValue {
+        type Reply = ValueResult;
+    }
+
+    /// `dht.getSignedAddressList = dht.Node`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xa97948ed)]
+    pub struct GetSignedAddressList;
+
+    impl TlFunction for GetSignedAddressList {
+        type Reply = Boxed<Node>;
+    }
+
+    /// `dht.query node:dht.node = True`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x7d530769)]
+    pub struct Query {
+        pub node: Node,
+    }
+
+    /// `dht.UpdateRule`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum UpdateRule {
+        UpdateRuleSignature(update_rule::Signature),
+        UpdateRuleAnybody(update_rule::Anybody),
+        UpdateRuleOverlayNodes(update_rule::OverlayNodes),
+    }
+
+    /// `dht.ValueResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum ValueResult {
+        ValueNotFound(ValueNotFound),
+        ValueFound(ValueFound),
+    }
+
+    pub mod update_rule {
+        use ::adnl::tl::__private::*;
+
+        /// `dht.updateRule.signature = dht.UpdateRule`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0xcc9f31f7)]
+        pub struct Signature;
+
+        /// `dht.updateRule.anybody = dht.UpdateRule`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x61578e14)]
+        pub struct Anybody;
+
+        /// `dht.updateRule.overlayNodes = dht.UpdateRule`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x26779383)]
+        pub struct OverlayNodes;
+    }
+}
+
+pub mod fec {
+    use ::adnl::tl::__private::*;
+
+    /// `fec.raptorQ data_size:int symbol_size:int symbols_count:int = fec.Type`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x8b93a7e0)]
+    pub struct RaptorQ {
+        pub data_size: i32,
+        pub symbol_size: i32,
+        pub symbols_count: i32,
+    }
+
+    /// `fec.roundRobin data_size:int symbol_size:int symbols_count:int = fec.Type`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x32f528e4)]
+    pub struct RoundRobin {
+        pub data_size: i32,
+        pub symbol_size: i32,
+        pub symbols_count: i32,
+    }
+
+    /// `fec.online data_size:int symbol_size:int symbols_count:int = fec.Type`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x0127660c)]
+    pub struct Online {
+        pub data_size: i32,
+        pub symbol_size: i32,
+        pub symbols_count: i32,
+    }
+
+    /// `fec.Type`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum Type {
+        RaptorQ(RaptorQ),
+        RoundRobin(RoundRobin),
+        Online(Online),
+    }
+}
+
+pub mod lite_server {
+    use ::adnl::tl::__private::*;
+
+    /// `liteServer.error code:int message:string = liteServer.Error`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xbba9e148)]
+    pub struct Error {
+        pub code: i32,
+        pub message: String,
+    }
+
+    /// `liteServer.accountId workchain:int id:int256 = liteServer.AccountId`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x75a0e2c5)]
+    pub struct AccountId {
+        pub workchain: i32,
+        pub id: [u8; 32],
+    }
+
+    /// `liteServer.libraryEntry hash:int256 data:bytes = liteServer.LibraryEntry`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x8aff2446)]
+    pub struct LibraryEntry {
+        pub hash: [u8; 32],
+        pub data: Vec<u8>,
+    }
+
+    /// `liteServer.masterchainInfo last:tonNode.blockIdExt state_root_hash:int256 init:tonNode.zeroStateIdExt = liteServer.MasterchainInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x85832881)]
+    pub struct MasterchainInfo {
+        pub last: super::ton_node::BlockIdExt,
+        pub state_root_hash: [u8; 32],
+        pub init: super::ton_node::ZeroStateIdExt,
+    }
+
+    /// `liteServer.masterchainInfoExt mode:# version:int capabilities:long last:tonNode.blockIdExt last_utime:int now:int state_root_hash:int256 init:tonNode.zeroStateIdExt = liteServer.MasterchainInfoExt`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xa8cce0f5)]
+    pub struct MasterchainInfoExt {
+        pub mode: u32,
+        pub version: i32,
+        pub capabilities: i64,
+        pub last: super::ton_node::BlockIdExt,
+        pub last_utime: i32,
+        pub now: i32,
+        pub state_root_hash: [u8; 32],
+        pub init: super::ton_node::ZeroStateIdExt,
+    }
+
+    /// `liteServer.currentTime now:int = liteServer.CurrentTime`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xe953000d)]
+    pub struct CurrentTime {
+        pub now: i32,
+    }
+
+    /// `liteServer.version mode:# version:int capabilities:long now:int = liteServer.Version`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x5a0491e5)]
+    pub struct Version {
+        pub mode: u32,
+        pub version: i32,
+        pub capabilities: i64,
+        pub now: i32,
+    }
+
+    /// `liteServer.blockData id:tonNode.blockIdExt data:bytes = liteServer.BlockData`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xa574ed6c)]
+    pub struct BlockData {
+        pub id: super::ton_node::BlockIdExt,
+        pub data: Vec<u8>,
+    }
+
+    /// `liteServer.blockState id:tonNode.blockIdExt root_hash:int256 file_hash:int256 data:bytes = liteServer.BlockState`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xabaddc0c)]
+    pub struct BlockState {
+        pub id: super::ton_node::BlockIdExt,
+        pub root_hash: [u8; 32],
+        pub file_hash: [u8; 32],
+        pub data: Vec<u8>,
+    }
+
+    /// `liteServer.blockHeader id:tonNode.blockIdExt mode:# header_proof:bytes = liteServer.BlockHeader`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x752d8219)]
+    pub struct BlockHeader {
+        pub id: super::ton_node::BlockIdExt,
+        pub mode: u32,
+        pub header_proof: Vec<u8>,
+    }
+
+    /// `liteServer.sendMsgStatus status:int = liteServer.SendMsgStatus`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x3950e597)]
+    pub struct SendMsgStatus {
+        pub status: i32,
+    }
+
+    /// `liteServer.accountState id:tonNode.blockIdExt shardblk:tonNode.blockIdExt shard_proof:bytes proof:bytes state:bytes = liteServer.AccountState`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x7079c751)]
+    pub struct AccountState {
+        pub id: super::ton_node::BlockIdExt,
+        pub shardblk: super::ton_node::BlockIdExt,
+        pub shard_proof: Vec<u8>,
+        pub proof: Vec<u8>,
+        pub state: Vec<u8>,
+    }
+
+    /// `liteServer.runMethodResult mode:# id:tonNode.blockIdExt shardblk:tonNode.blockIdExt shard_proof:mode.0?bytes proof:mode.0?bytes state_proof:mode.1?bytes init_c7:mode.3?bytes lib_extras:mode.4?bytes exit_code:int result:mode.2?bytes = liteServer.RunMethodResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xa39a616b)]
+    pub struct RunMethodResult {
+        #[tl(flags)]
+        pub mode: u32,
+        pub id: super::ton_node::BlockIdExt,
+        pub shardblk: super::ton_node::BlockIdExt,
+        #[tl(flags_bit = "mode.0")]
+        pub shard_proof: Option<Vec<u8>>,
+        #[tl(flags_bit = "mode.0")]
+        pub proof: Option<Vec<u8>>,
+        #[tl(flags_bit = "mode.1")]
+        pub state_proof: Option<Vec<u8>>,
+        #[tl(flags_bit = "mode.3")]
+        pub init_c7: Option<Vec<u8>>,
+        #[tl(flags_bit = "mode.4")]
+        pub lib_extras: Option<Vec<u8>>,
+        pub exit_code: i32,
+        #[tl(flags_bit = "mode.2")]
+        pub result: Option<Vec<u8>>,
+    }
+
+    /// `liteServer.shardInfo id:tonNode.blockIdExt shardblk:tonNode.blockIdExt shard_proof:bytes shard_descr:bytes = liteServer.ShardInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x9fe6cd84)]
+    pub struct ShardInfo {
+        pub id: super::ton_node::BlockIdExt,
+        pub shardblk: super::ton_node::BlockIdExt,
+        pub shard_proof: Vec<u8>,
+        pub shard_descr: Vec<u8>,
+    }
+
+    /// `liteServer.allShardsInfo id:tonNode.blockIdExt proof:bytes data:bytes = liteServer.AllShardsInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x098fe72d)]
+    pub struct AllShardsInfo {
+        pub id: super::ton_node::BlockIdExt,
+        pub proof: Vec<u8>,
+        pub data: Vec<u8>,
+    }
+
+    /// `liteServer.transactionInfo id:tonNode.blockIdExt proof:bytes transaction:bytes = liteServer.TransactionInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x0edeed47)]
+    pub struct TransactionInfo {
+        pub id: super::ton_node::BlockIdExt,
+        pub proof: Vec<u8>,
+        pub transaction: Vec<u8>,
+    }
+
+    /// `liteServer.transactionList ids:vector tonNode.blockIdExt transactions:bytes = liteServer.TransactionList`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x6f26c60b)]
+    pub struct TransactionList {
+        pub ids: Vec<super::ton_node::BlockIdExt>,
+        pub transactions: Vec<u8>,
+    }
+
+    /// `liteServer.transactionMetadata mode:# depth:int initiator:liteServer.accountId initiator_lt:long = liteServer.TransactionMetadata`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xff706385)]
+    pub struct TransactionMetadata {
+        pub mode: u32,
+        pub depth: i32,
+        pub initiator: AccountId,
+        pub initiator_lt: i64,
+    }
+
+    /// `liteServer.transactionId mode:# account:mode.0?int256 lt:mode.1?long hash:mode.2?int256 metadata:mode.8?liteServer.transactionMetadata = liteServer.TransactionId`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xb12f65af)]
+    pub struct TransactionId {
+        #[tl(flags)]
+        pub mode: u32,
+        #[tl(flags_bit = "mode.0")]
+        pub account: Option<[u8; 32]>,
+        #[tl(flags_bit = "mode.1")]
+        pub lt: Option<i64>,
+        #[tl(flags_bit = "mode.2")]
+        pub hash: Option<[u8; 32]>,
+        #[tl(flags_bit = "mode.8")]
+        pub metadata: Option<TransactionMetadata>,
+    }
+
+    /// `liteServer.transactionId3 account:int256 lt:long = liteServer.TransactionId3`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x2c81da77)]
+    pub struct TransactionId3 {
+        pub account: [u8; 32],
+        pub lt: i64,
+    }
+
+    /// `liteServer.blockTransactions id:tonNode.blockIdExt req_count:# incomplete:Bool ids:vector liteServer.transactionId proof:bytes = liteServer.BlockTransactions`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xbd8cad2b)]
+    pub struct BlockTransactions {
+        pub id: super::ton_node::BlockIdExt,
+        pub req_count: u32,
+        pub incomplete: bool,
+        pub ids: Vec<TransactionId>,
+        pub proof: Vec<u8>,
+    }
+
+    /// `liteServer.blockTransactionsExt id:tonNode.blockIdExt req_count:# incomplete:Bool transactions:bytes proof:bytes = liteServer.BlockTransactionsExt`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xfb8ffce4)]
+    pub struct BlockTransactionsExt {
+        pub id: super::ton_node::BlockIdExt,
+        pub req_count: u32,
+        pub incomplete: bool,
+        pub transactions: Vec<u8>,
+        pub proof: Vec<u8>,
+    }
+
+    /// `liteServer.signature node_id_short:int256 signature:bytes = liteServer.Signature`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xa3def855)]
+    pub struct Signature {
+        pub node_id_short: [u8; 32],
+        pub signature: Vec<u8>,
+    }
+
+    /// `liteServer.signatureSet validator_set_hash:int catchain_seqno:int signatures:vector liteServer.signature = liteServer.SignatureSet`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xf644a6e6)]
+    pub struct SignatureSet {
+        pub validator_set_hash: i32,
+        pub catchain_seqno: i32,
+        pub signatures: Vec<Signature>,
+    }
+
+    /// `liteServer.blockLinkBack to_key_block:Bool from:tonNode.blockIdExt to:tonNode.blockIdExt dest_proof:bytes proof:bytes state_proof:bytes = liteServer.BlockLink`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xef7e1bef)]
+    pub struct BlockLinkBack {
+        pub to_key_block: bool,
+        pub from: super::ton_node::BlockIdExt,
+        pub to: super::ton_node::BlockIdExt,
+        pub dest_proof: Vec<u8>,
+        pub proof: Vec<u8>,
+        pub state_proof: Vec<u8>,
+    }
+
+    /// `liteServer.blockLinkForward to_key_block:Bool from:tonNode.blockIdExt to:tonNode.blockIdExt dest_proof:bytes config_proof:bytes signatures:liteServer.SignatureSet = liteServer.BlockLink`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x520fce1c)]
+    pub struct BlockLinkForward {
+        pub to_key_block: bool,
+        pub from: super::ton_node::BlockIdExt,
+        pub to: super::ton_node::BlockIdExt,
+        pub dest_proof: Vec<u8>,
+        pub config_proof: Vec<u8>,
+        pub signatures: Boxed<SignatureSet>,
+    }
+
+    /// `liteServer.partialBlockProof complete:Bool from:tonNode.blockIdExt to:tonNode.blockIdExt steps:vector liteServer.BlockLink = liteServer.PartialBlockProof`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x8ed0d2c1)]
+    pub struct PartialBlockProof {
+        pub complete: bool,
+        pub from: super::ton_node::BlockIdExt,
+        pub to: super::ton_node::BlockIdExt,
+        pub steps: Vec<BlockLink>,
+    }
+
+    /// `liteServer.configInfo mode:# id:tonNode.blockIdExt state_proof:bytes config_proof:bytes = liteServer.ConfigInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xae7b272f)]
+    pub struct ConfigInfo {
+        pub mode: u32,
+        pub id: super::ton_node::BlockIdExt,
+        pub state_proof: Vec<u8>,
+        pub config_proof: Vec<u8>,
+    }
+
+    /// `liteServer.validatorStats mode:# id:tonNode.blockIdExt count:int complete:Bool state_proof:bytes data_proof:bytes = liteServer.ValidatorStats`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xb9f796d8)]
+    pub struct ValidatorStats {
+        pub mode: u32,
+        pub id: super::ton_node::BlockIdExt,
+        pub count: i32,
+        pub complete: bool,
+        pub state_proof: Vec<u8>,
+        pub data_proof: Vec<u8>,
+    }
+
+    /// `liteServer.libraryResult result:vector liteServer.libraryEntry = liteServer.LibraryResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x117ab96b)]
+    pub struct LibraryResult {
+        pub result: Vec<LibraryEntry>,
+    }
+
+    /// `liteServer.libraryResultWithProof id:tonNode.blockIdExt mode:# result:vector liteServer.libraryEntry state_proof:bytes data_proof:bytes = liteServer.LibraryResultWithProof`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x10a927bf)]
+    pub struct LibraryResultWithProof {
+        pub id: super::ton_node::BlockIdExt,
+        pub mode: u32,
+        pub result: Vec<LibraryEntry>,
+        pub state_proof: Vec<u8>,
+        pub data_proof: Vec<u8>,
+    }
+
+    /// `liteServer.shardBlockLink id:tonNode.blockIdExt proof:bytes = liteServer.ShardBlockLink`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xd30dcf72)]
+    pub struct ShardBlockLink {
+        pub id: super::ton_node::BlockIdExt,
+        pub proof: Vec<u8>,
+    }
+
+    /// `liteServer.shardBlockProof masterchain_id:tonNode.blockIdExt links:vector liteServer.shardBlockLink = liteServer.ShardBlockProof`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x1d62a07a)]
+    pub struct ShardBlockProof {
+        pub masterchain_id: super::ton_node::BlockIdExt,
+        pub links: Vec<ShardBlockLink>,
+    }
+
+    /// `liteServer.lookupBlockResult id:tonNode.blockIdExt mode:# mc_block_id:tonNode.blockIdExt client_mc_state_proof:bytes mc_block_proof:bytes shard_links:vector liteServer.shardBlockLink header:bytes prev_header:bytes = liteServer.LookupBlockResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x99786be7)]
+    pub struct LookupBlockResult {
+        pub id: super::ton_node::BlockIdExt,
+        pub mode: u32,
+        pub mc_block_id: super::ton_node::BlockIdExt,
+        pub client_mc_state_proof: Vec<u8>,
+        pub mc_block_proof: Vec<u8>,
+        pub shard_links: Vec<ShardBlockLink>,
+        pub header: Vec<u8>,
+        pub prev_header: Vec<u8>,
+    }
+
+    /// `liteServer.getMasterchainInfo = liteServer.MasterchainInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x89b5e62e)]
+    pub struct GetMasterchainInfo;
+
+    impl TlFunction for GetMasterchainInfo {
+        type Reply = Boxed<MasterchainInfo>;
+    }
+
+    /// `liteServer.getMasterchainInfoExt mode:# = liteServer.MasterchainInfoExt`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x70a671df)]
+    pub struct GetMasterchainInfoExt {
+        pub mode: u32,
+    }
+
+    impl TlFunction for GetMasterchainInfoExt {
+        type Reply = Boxed<MasterchainInfoExt>;
+    }
+
+    /// `liteServer.getTime = liteServer.CurrentTime`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x16ad5a34)]
+    pub struct GetTime;
+
+    impl TlFunction for GetTime {
+        type Reply = Boxed<CurrentTime>;
+    }
+
+    /// `liteServer.getVersion = liteServer.Version`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x232b940b)]
+    pub struct GetVersion;
+
+    impl TlFunction for GetVersion {
+        type Reply = Boxed<Version>;
+    }
+
+    /// `liteServer.getBlock id:tonNode.blockIdExt = liteServer.BlockData`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x6377cf0d)]
+    pub struct GetBlock {
+        pub id: super::ton_node::BlockIdExt,
+    }
+
+    impl TlFunction for GetBlock {
+        type Reply = Boxed<BlockData>;
+    }
+
+    /// `liteServer.getState id:tonNode.blockIdExt = liteServer.BlockState`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xba6e2eb6)]
+    pub struct GetState {
+        pub id: super::ton_node::BlockIdExt,
+    }
+
+    impl TlFunction for GetState {
+        type Reply = Boxed<BlockState>;
+    }
+
+    /// `liteServer.getBlockHeader id:tonNode.blockIdExt mode:# = liteServer.BlockHeader`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x21ec069e)]
+    pub struct GetBlockHeader {
+        pub id: super::ton_node::BlockIdExt,
+        pub mode: u32,
+    }
+
+    impl TlFunction for GetBlockHeader {
+        type Reply = Boxed<BlockHeader>;
+    }
+
+    /// `liteServer.sendMessage body:bytes = liteServer.SendMsgStatus`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x690ad482)]
+    pub struct SendMessage {
+        pub body: Vec<u8>,
+    }
+
+    impl TlFunction for SendMessage {
+        type Reply = Boxed<SendMsgStatus>;
+    }
+
+    /// `liteServer.getAccountState id:tonNode.blockIdExt account:liteServer.accountId = liteServer.AccountState`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x6b890e25)]
+    pub struct GetAccountState {
+        pub id: super::ton_node::BlockIdExt,
+        pub account: AccountId,
+    }
+
+    impl TlFunction for GetAccountState {
+        type Reply = Boxed<AccountState>;
+    }
+
+    /// `liteServer.getAccountStatePrunned id:tonNode.blockIdExt account:liteServer.accountId = liteServer.AccountState`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x5a698507)]
+    pub struct GetAccountStatePrunned {
+        pub id: super::ton_node::BlockIdExt,
+        pub account: AccountId,
+    }
+
+    impl TlFunction for GetAccountStatePrunned {
+        type Reply = Boxed<AccountState>;
+    }
+
+    /// `liteServer.runSmcMethod mode:# id:tonNode.blockIdExt account:liteServer.accountId method_id:long params:bytes = liteServer.RunMethodResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x5cc65dd2)]
+    pub struct RunSmcMethod {
+        pub mode: u32,
+        pub id: super::ton_node::BlockIdExt,
+        pub account: AccountId,
+        pub method_id: i64,
+        pub params: Vec<u8>,
+    }
+
+    impl TlFunction for RunSmcMethod {
+        type Reply = Boxed<RunMethodResult>;
+    }
+
+    /// `liteServer.getShardInfo id:tonNode.blockIdExt workchain:int shard:long exact:Bool = liteServer.ShardInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x46a2f425)]
+    pub struct GetShardInfo {
+        pub id: super::ton_node::BlockIdExt,
+        pub workchain: i32,
+        pub shard: i64,
+        pub exact: bool,
+    }
+
+    impl TlFunction for GetShardInfo {
+        type Reply = Boxed<ShardInfo>;
+    }
+
+    /// `liteServer.getAllShardsInfo id:tonNode.blockIdExt = liteServer.AllShardsInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x74d3fd6b)]
+    pub struct GetAllShardsInfo {
+        pub id: super::ton_node::BlockIdExt,
+    }
+
+    impl TlFunction for GetAllShardsInfo {
+        type Reply = Boxed<AllShardsInfo>;
+    }
+
+    /// `liteServer.getOneTransaction id:tonNode.blockIdExt account:liteServer.accountId lt:long = liteServer.TransactionInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xd40f24ea)]
+    pub struct GetOneTransaction {
+        pub id: super::ton_node::BlockIdExt,
+        pub account: AccountId,
+        pub lt: i64,
+    }
+
+    impl TlFunction for GetOneTransaction {
+        type Reply = Boxed<TransactionInfo>;
+    }
+
+    /// `liteServer.getTransactions count:# account:liteServer.accountId lt:long hash:int256 = liteServer.TransactionList`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x1c40e7a1)]
+    pub struct GetTransactions {
+        pub count: u32,
+        pub account: AccountId,
+        pub lt: i64,
+        pub hash: [u8; 32],
+    }
+
+    impl TlFunction for GetTransactions {
+        type Reply = Boxed<TransactionList>;
+    }
+
+    /// `liteServer.lookupBlock mode:# id:tonNode.blockId lt:mode.1?long utime:mode.2?int = liteServer.BlockHeader`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xfac8f71e)]
+    pub struct LookupBlock {
+        #[tl(flags)]
+        pub mode: u32,
+        pub id: super::ton_node::BlockId,
+        #[tl(flags_bit = "mode.1")]
+        pub lt: Option<i64>,
+        #[tl(flags_bit = "mode.2")]
+        pub utime: Option<i32>,
+    }
+
+    impl TlFunction for LookupBlock {
+        type Reply = Boxed<BlockHeader>;
+    }
+
+    /// `liteServer.lookupBlockWithProof mode:# id:tonNode.blockId mc_block_id:tonNode.blockIdExt lt:mode.1?long utime:mode.2?int = liteServer.LookupBlockResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x9c045ff8)]
+    pub struct LookupBlockWithProof {
+        #[tl(flags)]
+        pub mode: u32,
+        pub id: super::ton_node::BlockId,
+        pub mc_block_id: super::ton_node::BlockIdExt,
+        #[tl(flags_bit = "mode.1")]
+        pub lt: Option<i64>,
+        #[tl(flags_bit = "mode.2")]
+        pub utime: Option<i32>,
+    }
+
+    impl TlFunction for LookupBlockWithProof {
+        type Reply = Boxed<LookupBlockResult>;
+    }
+
+    /// `liteServer.listBlockTransactions id:tonNode.blockIdExt mode:# count:# after:mode.7?liteServer.transactionId3 reverse_order:mode.6?true want_proof:mode.5?true = liteServer.BlockTransactions`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xadfcc7da)]
+    pub struct ListBlockTransactions {
+        pub id: super::ton_node::BlockIdExt,
+        #[tl(flags)]
+        pub mode: u32,
+        pub count: u32,
+        #[tl(flags_bit = "mode.7")]
+        pub after: Option<TransactionId3>,
+        #[tl(flags_bit = "mode.6")]
+        pub reverse_order: bool,
+        #[tl(flags_bit = "mode.5")]
+        pub want_proof: bool,
+    }
+
+    impl TlFunction for ListBlockTransactions {
+        type Reply = Boxed<BlockTransactions>;
+    }
+
+    /// `liteServer.listBlockTransactionsExt id:tonNode.blockIdExt mode:# count:# after:mode.7?liteServer.transactionId3 reverse_order:mode.6?true want_proof:mode.5?true = liteServer.BlockTransactionsExt`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x0079dd5c)]
+    pub struct ListBlockTransactionsExt {
+        pub id: super::ton_node::BlockIdExt,
+        #[tl(flags)]
+        pub mode: u32,
+        pub count: u32,
+        #[tl(flags_bit = "mode.7")]
+        pub after: Option<TransactionId3>,
+        #[tl(flags_bit = "mode.6")]
+        pub reverse_order: bool,
+        #[tl(flags_bit = "mode.5")]
+        pub want_proof: bool,
+    }
+
+    impl TlFunction for ListBlockTransactionsExt {
+        type Reply = Boxed<BlockTransactionsExt>;
+    }
+
+    /// `liteServer.getBlockProof mode:# known_block:tonNode.blockIdExt target_block:mode.0?tonNode.blockIdExt = liteServer.PartialBlockProof`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x8aea9c44)]
+    pub struct GetBlockProof {
+        #[tl(flags)]
+        pub mode: u32,
+        pub known_block: super::ton_node::BlockIdExt,
+        #[tl(flags_bit = "mode.0")]
+        pub target_block: Option<super::ton_node::BlockIdExt>,
+    }
+
+    impl TlFunction for GetBlockProof {
+        type Reply = Boxed<PartialBlockProof>;
+    }
+
+    /// `liteServer.getConfigAll mode:# id:tonNode.blockIdExt = liteServer.ConfigInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x911b26b7)]
+    pub struct GetConfigAll {
+        pub mode: u32,
+        pub id: super::ton_node::BlockIdExt,
+    }
+
+    impl TlFunction for GetConfigAll {
+        type Reply = Boxed<ConfigInfo>;
+    }
+
+    /// `liteServer.getConfigParams mode:# id:tonNode.blockIdExt param_list:vector int = liteServer.ConfigInfo`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x2a111c19)]
+    pub struct GetConfigParams {
+        pub mode: u32,
+        pub id: super::ton_node::BlockIdExt,
+        pub param_list: Vec<i32>,
+    }
+
+    impl TlFunction for GetConfigParams {
+        type Reply = Boxed<ConfigInfo>;
+    }
+
+    /// `liteServer.getValidatorStats mode:# id:tonNode.blockIdExt limit:int start_after:mode.0?int256 modified_after:mode.2?int = liteServer.ValidatorStats`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x091a58bc)]
+    pub struct GetValidatorStats {
+        #[tl(flags)]
+        pub mode: u32,
+        pub id: super::ton_node::BlockIdExt,
+        pub limit: i32,
+        #[tl(flags_bit = "mode.0")]
+        pub start_after: Option<[u8; 32]>,
+        #[tl(flags_bit = "mode.2")]
+        pub modified_after: Option<i32>,
+    }
+
+    impl TlFunction for GetValidatorStats {
+        type Reply = Boxed<ValidatorStats>;
+    }
+
+    /// `liteServer.getLibraries library_list:vector int256 = liteServer.LibraryResult`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xd122b662)]
+    pub struct GetLibraries {
+        pub library_list: Vec<[u8; 32]>,
+    }
+
+    impl TlFunction for GetLibraries {
+        type Reply = Boxed<LibraryResult>;
+    }
+
+    /// `liteServer.getLibrariesWithProof id:tonNode.blockIdExt mode:# library_list:vector int256 = liteServer.LibraryResultWithProof`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xd97693bd)]
+    pub struct GetLibrariesWithProof {
+        pub id: super::ton_node::BlockIdExt,
+        pub mode: u32,
+        pub library_list: Vec<[u8; 32]>,
+    }
+
+    impl TlFunction for GetLibrariesWithProof {
+        type Reply = Boxed<LibraryResultWithProof>;
+    }
+
+    /// `liteServer.getShardBlockProof id:tonNode.blockIdExt = liteServer.ShardBlockProof`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x4ca60350)]
+    pub struct GetShardBlockProof {
+        pub id: super::ton_node::BlockIdExt,
+    }
+
+    impl TlFunction for GetShardBlockProof {
+        type Reply = Boxed<ShardBlockProof>;
+    }
+
+    /// `liteServer.queryPrefix = Object`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x72d3e686)]
+    pub struct QueryPrefix;
+
+    /// `liteServer.query data:bytes = Object`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x798c06df)]
+    pub struct Query {
+        pub data: Vec<u8>,
+    }
+
+    /// `liteServer.waitMasterchainSeqno seqno:int timeout_ms:int = Object`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xbaeab892)]
+    pub struct WaitMasterchainSeqno {
+        pub seqno: i32,
+        pub timeout_ms: i32,
+    }
+
+    /// `liteServer.BlockLink`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum BlockLink {
+        BlockLinkBack(BlockLinkBack),
+        BlockLinkForward(BlockLinkForward),
+    }
+
+    pub mod debug {
+        use ::adnl::tl::__private::*;
+
+        /// `liteServer.debug.verbosity value:int = liteServer.debug.Verbosity`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x5d404733)]
+        pub struct Verbosity {
+            pub value: i32,
+        }
+    }
+}
+
+pub mod overlay {
+    use ::adnl::tl::__private::*;
+
+    /// `overlay.node id:PublicKey overlay:int256 version:int signature:bytes = overlay.Node`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xb86b8a83)]
+    pub struct Node {
+        pub id: super::PublicKey,
+        pub overlay: [u8; 32],
+        pub version: i32,
+        pub signature: Vec<u8>,
+    }
+
+    /// `overlay.nodes nodes:vector overlay.node = overlay.Nodes`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xe487290e)]
+    pub struct Nodes {
+        pub nodes: Vec<Node>,
+    }
+
+    /// `overlay.message overlay:int256 = overlay.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x75252420)]
+    pub struct Message {
+        pub overlay: [u8; 32],
+    }
+
+    /// `overlay.broadcastList hashes:vector int256 = overlay.BroadcastList`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x18d1dedf)]
+    pub struct BroadcastList {
+        pub hashes: Vec<[u8; 32]>,
+    }
+
+    /// `overlay.certificate issued_by:PublicKey expire_at:int max_size:int signature:bytes = overlay.Certificate`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xe09ed731)]
+    pub struct Certificate {
+        pub issued_by: super::PublicKey,
+        pub expire_at: i32,
+        pub max_size: i32,
+        pub signature: Vec<u8>,
+    }
+
+    /// `overlay.emptyCertificate = overlay.Certificate`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x32dabccf)]
+    pub struct EmptyCertificate;
+
+    /// `overlay.certificateId overlay_id:int256 node:int256 expire_at:int max_size:int = overlay.CertificateId`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x8fae60b9)]
+    pub struct CertificateId {
+        pub overlay_id: [u8; 32],
+        pub node: [u8; 32],
+        pub expire_at: i32,
+        pub max_size: i32,
+    }
+
+    /// `overlay.unicast data:bytes = overlay.Broadcast`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x33534e24)]
+    pub struct Unicast {
+        pub data: Vec<u8>,
+    }
+
+    /// `overlay.broadcast src:PublicKey certificate:overlay.Certificate flags:int data:bytes date:int signature:bytes = overlay.Broadcast`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xb15a2b6b)]
+    pub struct Broadcast {
+        pub src: super::PublicKey,
+        pub certificate: CertificateBoxed,
+        pub flags: i32,
+        pub data: Vec<u8>,
+        pub date: i32,
+        pub signature: Vec<u8>,
+    }
+
+    /// `overlay.broadcastFec src:PublicKey certificate:overlay.Certificate data_hash:int256 data_size:int flags:int data:bytes seqno:int fec:fec.Type date:int signature:bytes = overlay.Broadcast`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xbad7c36a)]
+    pub struct BroadcastFec {
+        pub src: super::PublicKey,
+        pub certificate: CertificateBoxed,
+        pub data_hash: [u8; 32],
+        pub data_size: i32,
+        pub flags: i32,
+        pub data: Vec<u8>,
+        pub seqno: i32,
+        pub fec: super::fec::Type,
+        pub date: i32,
+        pub signature: Vec<u8>,
+    }
+
+    /// `overlay.broadcastFecShort src:PublicKey certificate:overlay.Certificate broadcast_hash:int256 part_data_hash:int256 seqno:int signature:bytes = overlay.Broadcast`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xf1881342)]
+    pub struct BroadcastFecShort {
+        pub src: super::PublicKey,
+        pub certificate: CertificateBoxed,
+        pub broadcast_hash: [u8; 32],
+        pub part_data_hash: [u8; 32],
+        pub seqno: i32,
+        pub signature: Vec<u8>,
+    }
+
+    /// `overlay.broadcastNotFound = overlay.Broadcast`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x95863624)]
+    pub struct BroadcastNotFound;
+
+    /// `overlay.getRandomPeers peers:overlay.nodes = overlay.Nodes`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x48ee64ab)]
+    pub struct GetRandomPeers {
+        pub peers: Nodes,
+    }
+
+    impl TlFunction for GetRandomPeers {
+        type Reply = Boxed<Nodes>;
+    }
+
+    /// `overlay.query overlay:int256 = True`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0xccfd8443)]
+    pub struct Query {
+        pub overlay: [u8; 32],
+    }
+
+    /// `overlay.getBroadcast hash:int256 = overlay.Broadcast`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x2d35f2a0)]
+    pub struct GetBroadcast {
+        pub hash: [u8; 32],
+    }
+
+    impl TlFunction for GetBroadcast {
+        type Reply = BroadcastBoxed;
+    }
+
+    /// `overlay.getBroadcastList list:overlay.broadcastList = overlay.BroadcastList`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x421c283a)]
+    pub struct GetBroadcastList {
+        pub list: BroadcastList,
+    }
+
+    impl TlFunction for GetBroadcastList {
+        type Reply = Boxed<BroadcastList>;
+    }
+
+    /// `overlay.Broadcast`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum BroadcastBoxed {
+        FecReceived(fec::Received),
+        FecCompleted(fec::Completed),
+        Unicast(Unicast),
+        Broadcast(Broadcast),
+        BroadcastFec(BroadcastFec),
+        BroadcastFecShort(BroadcastFecShort),
+        BroadcastNotFound(BroadcastNotFound),
+    }
+
+    /// `overlay.Certificate`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum CertificateBoxed {
+        Certificate(Certificate),
+        EmptyCertificate(EmptyCertificate),
+    }
+
+    pub mod broadcast {
+        use ::adnl::tl::__private::*;
+
+        /// `overlay.broadcast.id src:int256 data_hash:int256 flags:int = overlay.broadcast.Id`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x51fd789a)]
+        pub struct Id {
+            pub src: [u8; 32],
+            pub data_hash: [u8; 32],
+            pub flags: i32,
+        }
+
+        /// `overlay.broadcast.toSign hash:int256 date:int = overlay.broadcast.ToSign`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0xfa374e7c)]
+        pub struct ToSign {
+            pub hash: [u8; 32],
+            pub date: i32,
+        }
+    }
+
+    pub mod broadcast_fec {
+        use ::adnl::tl::__private::*;
+
+        /// `overlay.broadcastFec.id src:int256 type:int256 data_hash:int256 size:int flags:int = overlay.broadcastFec.Id`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0xfb3155a6)]
+        pub struct Id {
+            pub src: [u8; 32],
+            pub r#type: [u8; 32],
+            pub data_hash: [u8; 32],
+            pub size: i32,
+            pub flags: i32,
+        }
+
+        /// `overlay.broadcastFec.partId broadcast_hash:int256 data_hash:int256 seqno:int = overlay.broadcastFec.PartId`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0xa46962d0)]
+        pub struct PartId {
+            pub broadcast_hash: [u8; 32],
+            pub data_hash: [u8; 32],
+            pub seqno: i32,
+        }
+    }
+
+    pub mod fec {
+        use ::adnl::tl::__private::*;
+
+        /// `overlay.fec.received hash:int256 = overlay.Broadcast`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0xd55c14ec)]
+        pub struct Received {
+            pub hash: [u8; 32],
+        }
+
+        /// `overlay.fec.completed hash:int256 = overlay.Broadcast`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x09d76914)]
+        pub struct Completed {
+            pub hash: [u8; 32],
+        }
+    }
+
+    pub mod node {
+        use ::adnl::tl::__private::*;
+
+        /// `overlay.node.toSign id:adnl.id.short overlay:int256 version:int = overlay.node.ToSign`
+        #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+        #[tl(id = 0x03d8a8e1)]
+        pub struct ToSign {
+            pub id: super::super::adnl::id::Short,
+            pub overlay: [u8; 32],
+            pub version: i32,
+        }
+    }
+}
+
+pub mod pk {
+    use ::adnl::tl::__private::*;
+
+    /// `pk.unenc data:bytes = PrivateKey`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xb1db9b30)]
+    pub struct Unenc {
+        pub data: Vec<u8>,
+    }
+
+    /// `pk.ed25519 key:int256 = PrivateKey`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x49682317)]
+    pub struct Ed25519 {
+        pub key: [u8; 32],
+    }
+
+    /// `pk.aes key:int256 = PrivateKey`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xa5e85137)]
+    pub struct Aes {
+        pub key: [u8; 32],
+    }
+
+    /// `pk.overlay name:bytes = PrivateKey`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x37a5f65b)]
+    pub struct Overlay {
+        pub name: Vec<u8>,
+    }
+}
+
+pub mod r#pub {
+    use ::adnl::tl::__private::*;
+
+    /// `pub.unenc data:bytes = PublicKey`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xb61f450a)]
+    pub struct Unenc {
+        pub data: Vec<u8>,
+    }
+
+    /// `pub.ed25519 key:int256 = PublicKey`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x4813b4c6)]
+    pub struct Ed25519 {
+        pub key: [u8; 32],
+    }
+
+    /// `pub.aes key:int256 = PublicKey`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x2dbcadd4)]
+    pub struct Aes {
+        pub key: [u8; 32],
+    }
+
+    /// `pub.overlay name:bytes = PublicKey`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x34ba45cb)]
+    pub struct Overlay {
+        pub name: Vec<u8>,
+    }
+}
+
+pub mod rldp {
+    use ::adnl::tl::__private::*;
+
+    /// `rldp.messagePart transfer_id:int256 fec_type:fec.Type part:int total_size:long seqno:int data:bytes = rldp.MessagePart`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x185c22cc)]
+    pub struct MessagePart {
+        pub transfer_id: [u8; 32],
+        pub fec_type: super::fec::Type,
+        pub part: i32,
+        pub total_size: i64,
+        pub seqno: i32,
+        pub data: Vec<u8>,
+    }
+
+    /// `rldp.confirm transfer_id:int256 part:int seqno:int = rldp.MessagePart`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xf582dc58)]
+    pub struct Confirm {
+        pub transfer_id: [u8; 32],
+        pub part: i32,
+        pub seqno: i32,
+    }
+
+    /// `rldp.complete transfer_id:int256 part:int = rldp.MessagePart`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xbc0cb2bf)]
+    pub struct Complete {
+        pub transfer_id: [u8; 32],
+        pub part: i32,
+    }
+
+    /// `rldp.message id:int256 data:bytes = rldp.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x7d1bcd1e)]
+    pub struct Message {
+        pub id: [u8; 32],
+        pub data: Vec<u8>,
+    }
+
+    /// `rldp.query query_id:int256 max_answer_size:long timeout:int data:bytes = rldp.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x8a794d69)]
+    pub struct Query {
+        pub query_id: [u8; 32],
+        pub max_answer_size: i64,
+        pub timeout: i32,
+        pub data: Vec<u8>,
+    }
+
+    /// `rldp.answer query_id:int256 data:bytes = rldp.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xa3fc5c03)]
+    pub struct Answer {
+        pub query_id: [u8; 32],
+        pub data: Vec<u8>,
+    }
+
+    /// `rldp.MessagePart`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum MessagePartBoxed {
+        MessagePart(MessagePart),
+        Confirm(Confirm),
+        Complete(Complete),
+    }
+
+    /// `rldp.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum MessageBoxed {
+        Message(Message),
+        Query(Query),
+        Answer(Answer),
+    }
+}
+
+pub mod tcp {
+    use ::adnl::tl::__private::*;
+
+    /// `tcp.pong random_id:long = tcp.Pong`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xdc69fb03)]
+    pub struct Pong {
+        pub random_id: i64,
+    }
+
+    /// `tcp.authentificate nonce:bytes = tcp.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x445bab12)]
+    pub struct Authentificate {
+        pub nonce: Vec<u8>,
+    }
+
+    /// `tcp.authentificationNonce nonce:bytes = tcp.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xe35d4ab6)]
+    pub struct AuthentificationNonce {
+        pub nonce: Vec<u8>,
+    }
+
+    /// `tcp.authentificationComplete key:PublicKey signature:bytes = tcp.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xf7ad9ea6)]
+    pub struct AuthentificationComplete {
+        pub key: super::PublicKey,
+        pub signature: Vec<u8>,
+    }
+
+    /// `tcp.ping random_id:long = tcp.Pong`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(boxed, id = 0x4d082b9a)]
+    pub struct Ping {
+        pub random_id: i64,
+    }
+
+    impl TlFunction for Ping {
+        type Reply = Boxed<Pong>;
+    }
+
+    /// `tcp.Message`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    pub enum Message {
+        Authentificate(Authentificate),
+        AuthentificationNonce(AuthentificationNonce),
+        AuthentificationComplete(AuthentificationComplete),
+    }
+}
+
+pub mod ton {
+    use ::adnl::tl::__private::*;
+
+    /// `ton.blockId root_cell_hash:int256 file_hash:int256 = ton.BlockId`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xc50b6e70)]
+    pub struct BlockId {
+        pub root_cell_hash: [u8; 32],
+        pub file_hash: [u8; 32],
+    }
+}
+
+pub mod ton_node {
+    use ::adnl::tl::__private::*;
+
+    /// `tonNode.blockId workchain:int shard:long seqno:int = tonNode.BlockId`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0xb7cdb167)]
+    pub struct BlockId {
+        pub workchain: i32,
+        pub shard: i64,
+        pub seqno: i32,
+    }
+
+    /// `tonNode.blockIdExt workchain:int shard:long seqno:int root_hash:int256 file_hash:int256 = tonNode.BlockIdExt`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x6752eb78)]
+    pub struct BlockIdExt {
+        pub workchain: i32,
+        pub shard: i64,
+        pub seqno: i32,
+        pub root_hash: [u8; 32],
+        pub file_hash: [u8; 32],
+    }
+
+    /// `tonNode.zeroStateIdExt workchain:int root_hash:int256 file_hash:int256 = tonNode.ZeroStateIdExt`
+    #[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]
+    #[tl(id = 0x1d7235ae)]
+    pub struct ZeroStateIdExt {
+        pub workchain: i32,
+        pub root_hash: [u8; 32],
+        pub file_hash: [u8; 32],
+    }
+}
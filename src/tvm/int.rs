@@ -0,0 +1,133 @@
+use super::StackError;
+
+/// Signed 257-bit integer of TVM, stored as big-endian two's complement sign-extended to
+/// 33 bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Int257([u8; 33]);
+
+impl Int257 {
+    pub const ZERO: Self = Self([0; 33]);
+
+    /// Non-negative integer from 256-bit big-endian `value`, such as a hash or a public key
+    pub fn from_u256(value: [u8; 32]) -> Self {
+        let mut bytes = [0; 33];
+        bytes[1..].copy_from_slice(&value);
+        Self(bytes)
+    }
+
+    /// Value as 256-bit big-endian unsigned integer, `None` if it is negative
+    pub fn to_u256(&self) -> Option<[u8; 32]> {
+        match self.is_negative() {
+            true => None,
+            false => Some(self.0[1..].try_into().unwrap()),
+        }
+    }
+
+    /// Integer from big-endian two's complement `bytes`, sign-extended to 33 bytes. Fails if
+    /// the value does not fit into 257 bits.
+    pub fn from_be_bytes(bytes: [u8; 33]) -> Result<Self, StackError> {
+        if !matches!(bytes[0], 0x00 | 0xff) {
+            return Err(StackError::IntegerOverflow);
+        }
+        Ok(Self(bytes))
+    }
+
+    /// Big-endian two's complement bytes, sign-extended to 33 bytes
+    pub fn to_be_bytes(&self) -> [u8; 33] {
+        self.0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0[0] == 0xff
+    }
+
+    /// Value as signed integer of `N` bytes if it fits
+    fn to_signed<const N: usize>(self) -> Option<[u8; N]> {
+        let sign = self.0[0];
+        let (high, low) = self.0.split_at(33 - N);
+        (high.iter().all(|byte| *byte == sign) && (low[0] & 0x80 != 0) == (sign != 0))
+            .then(|| low.try_into().unwrap())
+    }
+
+    /// Value as unsigned integer of `N` bytes if it fits
+    fn to_unsigned<const N: usize>(self) -> Option<[u8; N]> {
+        let (high, low) = self.0.split_at(33 - N);
+        high.iter()
+            .all(|byte| *byte == 0)
+            .then(|| low.try_into().unwrap())
+    }
+
+    pub(crate) fn to_i64(self) -> Option<i64> {
+        self.to_signed().map(i64::from_be_bytes)
+    }
+}
+
+impl From<i128> for Int257 {
+    fn from(value: i128) -> Self {
+        let sign = if value < 0 { 0xff } else { 0 };
+        let mut bytes = [sign; 33];
+        bytes[17..].copy_from_slice(&value.to_be_bytes());
+        Self(bytes)
+    }
+}
+
+impl From<u128> for Int257 {
+    fn from(value: u128) -> Self {
+        let mut bytes = [0; 33];
+        bytes[17..].copy_from_slice(&value.to_be_bytes());
+        Self(bytes)
+    }
+}
+
+impl From<i64> for Int257 {
+    fn from(value: i64) -> Self {
+        Self::from(value as i128)
+    }
+}
+
+impl From<u64> for Int257 {
+    fn from(value: u64) -> Self {
+        Self::from(value as u128)
+    }
+}
+
+impl TryFrom<Int257> for i64 {
+    type Error = StackError;
+
+    fn try_from(value: Int257) -> Result<Self, Self::Error> {
+        value.to_i64().ok_or(StackError::IntegerOverflow)
+    }
+}
+
+impl TryFrom<Int257> for u64 {
+    type Error = StackError;
+
+    fn try_from(value: Int257) -> Result<Self, Self::Error> {
+        value
+            .to_unsigned()
+            .map(u64::from_be_bytes)
+            .ok_or(StackError::IntegerOverflow)
+    }
+}
+
+impl TryFrom<Int257> for i128 {
+    type Error = StackError;
+
+    fn try_from(value: Int257) -> Result<Self, Self::Error> {
+        value
+            .to_signed()
+            .map(i128::from_be_bytes)
+            .ok_or(StackError::IntegerOverflow)
+    }
+}
+
+impl TryFrom<Int257> for u128 {
+    type Error = StackError;
+
+    fn try_from(value: Int257) -> Result<Self, Self::Error> {
+        value
+            .to_unsigned()
+            .map(u128::from_be_bytes)
+            .ok_or(StackError::IntegerOverflow)
+    }
+}
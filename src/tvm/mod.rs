@@ -0,0 +1,34 @@
+//! TVM stack values in `VmStack` TL-B serialization, used for parameters and results of
+//! get-methods in `liteServer.runSmcMethod`.
+//!
+//! ```
+//! use adnl::tvm::{self, StackValue};
+//!
+//! let params = tvm::serialize_stack(&[StackValue::from(7u64), StackValue::Null]).unwrap();
+//! let stack = tvm::parse_stack(&params).unwrap();
+//! assert_eq!(u64::try_from(stack[0].clone()).unwrap(), 7);
+//! assert_eq!(stack[1], StackValue::Null);
+//! ```
+
+mod int;
+mod stack;
+
+use thiserror::Error;
+
+use crate::boc::BocError;
+
+pub use int::Int257;
+pub use stack::{parse_stack, serialize_stack, StackValue};
+
+/// Error of TVM stack parsing or conversion of a stack value
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum StackError {
+    #[error("Invalid stack BoC: {0}")]
+    Boc(#[from] BocError),
+    #[error("Invalid stack value: {0}")]
+    InvalidValue(&'static str),
+    #[error("Expected {0} on stack")]
+    UnexpectedType(&'static str),
+    #[error("Integer does not fit into requested type")]
+    IntegerOverflow,
+}
@@ -0,0 +1,375 @@
+use alloc::vec::Vec;
+
+use super::{Int257, StackError};
+use crate::boc::{self, BocError, Cell, CellBuilder, CellSlice};
+use crate::tl::ton::lite_server::AccountId;
+
+const NULL_TAG: u64 = 0x00;
+const TINY_INT_TAG: u64 = 0x01;
+/// First byte of `vm_stk_int#0201_` and `vm_stk_nan#02ff`
+const INT_TAG: u64 = 0x02;
+const CELL_TAG: u64 = 0x03;
+const SLICE_TAG: u64 = 0x04;
+const BUILDER_TAG: u64 = 0x05;
+const CONT_TAG: u64 = 0x06;
+const TUPLE_TAG: u64 = 0x07;
+
+const ADDR_STD_TAG: u64 = 0b10;
+const ADDR_VAR_TAG: u64 = 0b11;
+
+/// Maximum number of values on stack
+const MAX_DEPTH: usize = (1 << 24) - 1;
+
+/// Value on TVM stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackValue {
+    Null,
+    Int(Int257),
+    Nan,
+    Cell(Cell),
+    /// Slice with all data and references of the cell
+    Slice(Cell),
+    /// Builder with the data and references of the cell
+    Builder(Cell),
+    /// Serialized `VmCont`, which is kept as is since it is not usable outside of TVM
+    Continuation(Cell),
+    Tuple(Vec<StackValue>),
+}
+
+impl StackValue {
+    /// Store `VmStackValue`
+    pub fn store(&self, builder: &mut CellBuilder) -> Result<(), BocError> {
+        match self {
+            Self::Null => {
+                builder.store_uint(NULL_TAG, 8)?;
+            }
+            Self::Int(value) => match value.to_i64() {
+                Some(value) => {
+                    builder.store_uint(TINY_INT_TAG, 8)?.store_int(value, 64)?;
+                }
+                None => {
+                    let bytes = value.to_be_bytes();
+                    builder.store_uint(INT_TAG << 7, 15)?;
+                    builder
+                        .store_bit(bytes[0] & 1 != 0)?
+                        .store_bytes(&bytes[1..])?;
+                }
+            },
+            Self::Nan => {
+                builder.store_uint((INT_TAG << 8) | 0xff, 16)?;
+            }
+            Self::Cell(cell) => {
+                builder
+                    .store_uint(CELL_TAG, 8)?
+                    .store_reference(cell.clone())?;
+            }
+            Self::Slice(cell) => {
+                builder
+                    .store_uint(SLICE_TAG, 8)?
+                    .store_reference(cell.clone())?;
+                // st_bits, end_bits, st_ref, end_ref
+                builder
+                    .store_uint(0, 10)?
+                    .store_uint(cell.bit_len() as u64, 10)?;
+                builder
+                    .store_uint(0, 3)?
+                    .store_uint(cell.references().len() as u64, 3)?;
+            }
+            Self::Builder(cell) => {
+                builder
+                    .store_uint(BUILDER_TAG, 8)?
+                    .store_reference(cell.clone())?;
+            }
+            Self::Continuation(cont) => {
+                builder.store_uint(CONT_TAG, 8)?;
+                builder.store_bits(cont.data(), cont.bit_len())?;
+                for reference in cont.references() {
+                    builder.store_reference(reference.clone())?;
+                }
+            }
+            Self::Tuple(items) => {
+                let len = u16::try_from(items.len()).map_err(|_| BocError::IntegerOverflow)?;
+                builder
+                    .store_uint(TUPLE_TAG, 8)?
+                    .store_uint(len as u64, 16)?;
+                // the first items form nested `VmTuple` in head, the last one is in tail
+                let (mut head, mut tail) = (None, None);
+                for (i, item) in items.iter().enumerate() {
+                    core::mem::swap(&mut head, &mut tail);
+                    if i > 1 {
+                        let mut node = CellBuilder::new();
+                        node.store_reference(tail.take().unwrap())?;
+                        node.store_reference(head.take().unwrap())?;
+                        head = Some(node.build()?);
+                    }
+                    tail = Some(item.to_cell()?);
+                }
+                for reference in [head, tail].into_iter().flatten() {
+                    builder.store_reference(reference)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Load `VmStackValue`
+    pub fn load(slice: &mut CellSlice) -> Result<Self, StackError> {
+        Ok(match slice.load_uint(8)? {
+            NULL_TAG => Self::Null,
+            TINY_INT_TAG => Self::Int(slice.load_int(64)?.into()),
+            INT_TAG => match slice.load_uint(7)? {
+                0 => {
+                    let mut bytes = [0; 33];
+                    if slice.load_bit()? {
+                        bytes[0] = 0xff;
+                    }
+                    bytes[1..].copy_from_slice(&slice.load_u256()?);
+                    Self::Int(Int257::from_be_bytes(bytes)?)
+                }
+                0x7f if slice.load_bit()? => Self::Nan,
+                _ => return Err(StackError::InvalidValue("invalid integer tag")),
+            },
+            CELL_TAG => Self::Cell(slice.load_reference()?.clone()),
+            SLICE_TAG => {
+                let cell = slice.load_reference()?;
+                let (st_bits, end_bits) = (slice.load_uint(10)?, slice.load_uint(10)?);
+                let (st_ref, end_ref) = (slice.load_uint(3)?, slice.load_uint(3)?);
+                if st_bits > end_bits || st_ref > end_ref || end_ref > 4 {
+                    return Err(StackError::InvalidValue("invalid slice bounds"));
+                }
+                if (st_bits, st_ref) == (0, 0)
+                    && end_bits == cell.bit_len() as u64
+                    && end_ref == cell.references().len() as u64
+                {
+                    Self::Slice(cell.clone())
+                } else {
+                    let mut source = CellSlice::new(cell)?;
+                    source.skip_bits(st_bits as usize)?;
+                    for _ in 0..st_ref {
+                        source.load_reference()?;
+                    }
+                    let mut part = CellBuilder::new();
+                    let bits = (end_bits - st_bits) as usize;
+                    part.store_bits(&source.load_bits(bits)?, bits)?;
+                    for _ in st_ref..end_ref {
+                        part.store_reference(source.load_reference()?.clone())?;
+                    }
+                    Self::Slice(part.build()?)
+                }
+            }
+            BUILDER_TAG => Self::Builder(slice.load_reference()?.clone()),
+            CONT_TAG => {
+                let mut cont = CellBuilder::new();
+                let bits = slice.remaining_bits();
+                cont.store_bits(&slice.load_bits(bits)?, bits)?;
+                while slice.remaining_references() > 0 {
+                    cont.store_reference(slice.load_reference()?.clone())?;
+                }
+                Self::Continuation(cont.build()?)
+            }
+            TUPLE_TAG => {
+                let len = slice.load_uint(16)?;
+                // `VmTuple` of the first items is nested in head, the last item is in tail
+                let mut items = Vec::with_capacity(len as usize);
+                let mut node = slice.clone();
+                let mut remaining = len;
+                while remaining > 0 {
+                    if remaining == 1 {
+                        items.push(Self::from_cell(node.load_reference()?)?);
+                        break;
+                    }
+                    let head = node.load_reference()?;
+                    items.push(Self::from_cell(node.load_reference()?)?);
+                    if remaining == 2 {
+                        items.push(Self::from_cell(head)?);
+                        break;
+                    }
+                    node = CellSlice::new(head)?;
+                    remaining -= 1;
+                }
+                for _ in 0..len.min(2) {
+                    slice.load_reference()?;
+                }
+                items.reverse();
+                Self::Tuple(items)
+            }
+            _ => return Err(StackError::InvalidValue("unknown value tag")),
+        })
+    }
+
+    fn to_cell(&self) -> Result<Cell, BocError> {
+        let mut builder = CellBuilder::new();
+        self.store(&mut builder)?;
+        builder.build()
+    }
+
+    fn from_cell(cell: &Cell) -> Result<Self, StackError> {
+        Self::load(&mut CellSlice::new(cell)?)
+    }
+}
+
+/// Serialize stack `values` into BoC with `VmStack`, the last value is the top of the stack
+pub fn serialize_stack(values: &[StackValue]) -> Result<Vec<u8>, StackError> {
+    if values.len() > MAX_DEPTH {
+        return Err(StackError::InvalidValue("stack is too deep"));
+    }
+    // `vm_stk_cons` keeps the rest of the stack in a reference and the top value inline
+    let mut rest = CellBuilder::new().build()?;
+    let mut root = CellBuilder::new();
+    root.store_uint(values.len() as u64, 24)?;
+    if let Some((top, values)) = values.split_last() {
+        for value in values {
+            let mut node = CellBuilder::new();
+            node.store_reference(rest)?;
+            value.store(&mut node)?;
+            rest = node.build()?;
+        }
+        root.store_reference(rest)?;
+        top.store(&mut root)?;
+    }
+    Ok(boc::serialize(&root.build()?))
+}
+
+/// Parse BoC with `VmStack`, the last value is the top of the stack
+pub fn parse_stack(data: &[u8]) -> Result<Vec<StackValue>, StackError> {
+    let root = boc::parse_single(data)?;
+    let mut node = CellSlice::new(&root)?;
+    let depth = node.load_uint(24)? as usize;
+    let mut values = Vec::new();
+    for _ in 0..depth {
+        let rest = node.load_reference()?;
+        values.push(StackValue::load(&mut node)?);
+        node = CellSlice::new(rest)?;
+    }
+    values.reverse();
+    Ok(values)
+}
+
+macro_rules! impl_int_conversions {
+    ($($ty:ty),*) => {$(
+        impl From<$ty> for StackValue {
+            fn from(value: $ty) -> Self {
+                Self::Int(value.into())
+            }
+        }
+
+        impl TryFrom<StackValue> for $ty {
+            type Error = StackError;
+
+            fn try_from(value: StackValue) -> Result<Self, Self::Error> {
+                Int257::try_from(value)?.try_into()
+            }
+        }
+    )*};
+}
+
+impl_int_conversions!(i64, u64, i128, u128);
+
+impl From<Int257> for StackValue {
+    fn from(value: Int257) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl TryFrom<StackValue> for Int257 {
+    type Error = StackError;
+
+    fn try_from(value: StackValue) -> Result<Self, Self::Error> {
+        match value {
+            StackValue::Int(value) => Ok(value),
+            _ => Err(StackError::UnexpectedType("integer")),
+        }
+    }
+}
+
+/// Booleans of TVM are integers -1 and 0
+impl From<bool> for StackValue {
+    fn from(value: bool) -> Self {
+        Self::from(-(value as i64))
+    }
+}
+
+impl TryFrom<StackValue> for bool {
+    type Error = StackError;
+
+    fn try_from(value: StackValue) -> Result<Self, Self::Error> {
+        Ok(i64::try_from(value)? != 0)
+    }
+}
+
+impl From<Cell> for StackValue {
+    fn from(value: Cell) -> Self {
+        Self::Cell(value)
+    }
+}
+
+impl TryFrom<StackValue> for Cell {
+    type Error = StackError;
+
+    fn try_from(value: StackValue) -> Result<Self, Self::Error> {
+        match value {
+            StackValue::Cell(cell) => Ok(cell),
+            _ => Err(StackError::UnexpectedType("cell")),
+        }
+    }
+}
+
+impl TryFrom<StackValue> for Vec<StackValue> {
+    type Error = StackError;
+
+    fn try_from(value: StackValue) -> Result<Self, Self::Error> {
+        match value {
+            StackValue::Tuple(items) => Ok(items),
+            _ => Err(StackError::UnexpectedType("tuple")),
+        }
+    }
+}
+
+/// Address as a slice with `addr_std`, or `addr_var` for workchains out of `int8`, without
+/// anycast
+impl From<AccountId> for StackValue {
+    fn from(value: AccountId) -> Self {
+        let mut builder = CellBuilder::new();
+        match i8::try_from(value.workchain) {
+            Ok(workchain) => {
+                builder.store_uint(ADDR_STD_TAG << 1, 3).unwrap();
+                builder.store_int(workchain as i64, 8).unwrap();
+            }
+            Err(_) => {
+                builder.store_uint(ADDR_VAR_TAG << 1, 3).unwrap();
+                builder.store_uint(256, 9).unwrap();
+                builder.store_int(value.workchain as i64, 32).unwrap();
+            }
+        }
+        builder.store_bytes(&value.id).unwrap();
+        Self::Slice(builder.build().unwrap())
+    }
+}
+
+/// Address from a slice with `addr_std` or 256-bit `addr_var` without anycast
+impl TryFrom<StackValue> for AccountId {
+    type Error = StackError;
+
+    fn try_from(value: StackValue) -> Result<Self, Self::Error> {
+        let StackValue::Slice(cell) = value else {
+            return Err(StackError::UnexpectedType("slice"));
+        };
+        let mut slice = CellSlice::new(&cell)?;
+        let tag = slice.load_uint(2)?;
+        if !matches!(tag, ADDR_STD_TAG | ADDR_VAR_TAG) {
+            return Err(StackError::InvalidValue("expected internal address"));
+        }
+        if slice.load_bit()? {
+            return Err(StackError::InvalidValue("anycast address is not supported"));
+        }
+        let workchain = match tag {
+            ADDR_STD_TAG => slice.load_int(8)? as i32,
+            _ if slice.load_uint(9)? == 256 => slice.load_int(32)? as i32,
+            _ => return Err(StackError::InvalidValue("unsupported address length")),
+        };
+        Ok(Self {
+            workchain,
+            id: slice.load_u256()?,
+        })
+    }
+}
@@ -0,0 +1,384 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio::time::Instant;
+
+use crate::tl::ton::lite_server;
+use crate::tl::{self, TlFunction};
+use crate::wrappers::lite_client::check_send_status;
+use crate::{AdnlPublicKey, LiteClient, LiteError};
+
+/// Default number of masterchain blocks a server may be behind the most recent one
+const DEFAULT_MAX_LAG: u32 = 8;
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_ATTEMPTS: usize = 3;
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Weight of the last sample in moving average of latency
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+type ConnectFuture<T> = Pin<Box<dyn Future<Output = Result<LiteClient<T>, LiteError>> + Send>>;
+type Connector<T> = Arc<dyn Fn() -> ConnectFuture<T> + Send + Sync>;
+
+/// State of circuit breaker of a server in [`LiteBalancer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Server is used for queries
+    Closed,
+    /// Server failed too many times in a row and is not used until its cooldown ends
+    Open,
+    /// Cooldown has ended, the next probe or query decides whether the server is healthy again
+    HalfOpen,
+}
+
+/// Snapshot of a server state in [`LiteBalancer`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiteServerStats {
+    /// Moving average of query latency
+    pub latency: Option<Duration>,
+    /// The last masterchain seqno reported by the server, `None` if the last poll failed
+    pub last_seqno: Option<i32>,
+    pub consecutive_failures: u32,
+    pub circuit: CircuitState,
+    pub connected: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Policy {
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+struct Server<T>
+where
+    T: AsyncRead + AsyncWrite,
+{
+    client: Option<LiteClient<T>>,
+    /// Creates a new connection after the previous one has failed
+    connector: Option<Connector<T>>,
+    latency: Option<Duration>,
+    last_seqno: Option<i32>,
+    failures: u32,
+    /// Circuit is open until this time
+    open_until: Option<Instant>,
+}
+
+impl<T> Server<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    fn circuit(&self, now: Instant) -> CircuitState {
+        match self.open_until {
+            None => CircuitState::Closed,
+            Some(until) if now < until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    fn is_usable(&self) -> bool {
+        self.client.is_some() || self.connector.is_some()
+    }
+
+    /// Run `query`, connecting first if needed, and update the state by its result
+    async fn query<Q: TlFunction>(
+        &mut self,
+        query: &Q,
+        policy: Policy,
+    ) -> Result<Q::Reply, LiteError> {
+        if self.client.is_none() {
+            if let Some(connector) = &self.connector {
+                match connector().await {
+                    Ok(client) => self.client = Some(client),
+                    Err(e) => {
+                        self.record_failure(&e, policy);
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        let client = self.client.as_mut().ok_or(LiteError::NoAvailableServers)?;
+        let started = Instant::now();
+        let result = client.query(query).await;
+        match &result {
+            // the server works, the query itself is invalid
+            Err(LiteError::ServerError(e)) if !e.is_retryable() => self.record_success(started),
+            Ok(_) => self.record_success(started),
+            Err(e) => self.record_failure(e, policy),
+        }
+        result
+    }
+
+    fn record_success(&mut self, started: Instant) {
+        let sample = started.elapsed();
+        self.latency = Some(match self.latency {
+            Some(latency) => {
+                latency.mul_f64(1.0 - LATENCY_SMOOTHING) + sample.mul_f64(LATENCY_SMOOTHING)
+            }
+            None => sample,
+        });
+        self.failures = 0;
+        self.open_until = None;
+    }
+
+    fn record_failure(&mut self, error: &LiteError, policy: Policy) {
+        log::debug!("liteserver query failed: {error}");
+        // connection is broken, a new one is made on the next attempt if possible
+        if matches!(error, LiteError::AdnlError(_)) && self.connector.is_some() {
+            self.client = None;
+        }
+        self.failures += 1;
+        let now = Instant::now();
+        if self.failures >= policy.failure_threshold || self.circuit(now) == CircuitState::HalfOpen
+        {
+            self.open_until = Some(now + policy.cooldown);
+        }
+    }
+}
+
+/// Balancer of queries over several liteservers.
+///
+/// Masterchain seqno of every server is polled with `getMasterchainInfo` at most once per
+/// refresh interval, before queries. Queries are sent to the server with the lowest latency
+/// among ones which are at most `max_lag` blocks behind the most recent seqno. Servers which
+/// fail several times in a row are not used until their cooldown ends, after which they are
+/// probed again by the next refresh.
+///
+/// Failed queries are retried on other servers, see [`Self::query`], except for
+/// non-retryable errors of the server, such as invalid query.
+///
+/// Queries take `&mut self`, so a balancer runs one query at a time, like a single
+/// [`LiteClient`]. To run queries concurrently, split servers between several balancers.
+pub struct LiteBalancer<T = TcpStream>
+where
+    T: AsyncRead + AsyncWrite,
+{
+    servers: Vec<Server<T>>,
+    policy: Policy,
+    max_lag: u32,
+    max_attempts: usize,
+    refresh_interval: Duration,
+    last_refresh: Option<Instant>,
+}
+
+impl LiteBalancer<TcpStream> {
+    /// Connect to liteservers with public keys at addresses. Servers which are not available
+    /// are reconnected when their cooldown ends.
+    pub async fn connect(servers: impl IntoIterator<Item = (AdnlPublicKey, SocketAddr)>) -> Self {
+        let servers = servers
+            .into_iter()
+            .map(|(public, address)| {
+                let connector: Connector<TcpStream> = Arc::new(move || {
+                    Box::pin(async move {
+                        tokio::time::timeout(CONNECT_TIMEOUT, LiteClient::connect(public, address))
+                            .await
+                            .map_err(|_| LiteError::Timeout)?
+                    })
+                });
+                Server::new(None, Some(connector))
+            })
+            .collect();
+        let mut balancer = Self::with_servers(servers);
+        balancer.refresh().await;
+        balancer
+    }
+}
+
+impl<T> Server<T>
+where
+    T: AsyncRead + AsyncWrite,
+{
+    fn new(client: Option<LiteClient<T>>, connector: Option<Connector<T>>) -> Self {
+        Self {
+            client,
+            connector,
+            latency: None,
+            last_seqno: None,
+            failures: 0,
+            open_until: None,
+        }
+    }
+}
+
+impl<T> LiteBalancer<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    /// Balance over already connected `clients`, which are not reconnected if their
+    /// connections fail
+    pub fn new(clients: impl IntoIterator<Item = LiteClient<T>>) -> Self {
+        Self::with_servers(
+            clients
+                .into_iter()
+                .map(|client| Server::new(Some(client), None))
+                .collect(),
+        )
+    }
+
+    fn with_servers(servers: Vec<Server<T>>) -> Self {
+        Self {
+            servers,
+            policy: Policy {
+                failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+                cooldown: DEFAULT_COOLDOWN,
+            },
+            max_lag: DEFAULT_MAX_LAG,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            last_refresh: None,
+        }
+    }
+
+    /// Set number of masterchain blocks a server may be behind the most recent one to be
+    /// used, 8 by default
+    pub fn with_max_lag(mut self, max_lag: u32) -> Self {
+        self.max_lag = max_lag;
+        self
+    }
+
+    /// Stop using a server for `cooldown` after `failures` failures in a row, 3 failures and
+    /// 30 seconds by default
+    pub fn with_circuit_breaker(mut self, failures: u32, cooldown: Duration) -> Self {
+        self.policy = Policy {
+            failure_threshold: failures.max(1),
+            cooldown,
+        };
+        self
+    }
+
+    /// Set number of servers to try a query on, 3 by default
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set interval of masterchain seqno polls, 5 seconds by default
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    /// State of servers in the order they were added
+    pub fn stats(&self) -> Vec<LiteServerStats> {
+        let now = Instant::now();
+        self.servers
+            .iter()
+            .map(|server| LiteServerStats {
+                latency: server.latency,
+                last_seqno: server.last_seqno,
+                consecutive_failures: server.failures,
+                circuit: server.circuit(now),
+                connected: server.client.is_some(),
+            })
+            .collect()
+    }
+
+    /// The most recent masterchain seqno reported by the servers
+    pub fn last_seqno(&self) -> Option<i32> {
+        self.servers
+            .iter()
+            .filter_map(|server| server.last_seqno)
+            .max()
+    }
+
+    /// Poll masterchain seqno of all servers which are not in cooldown
+    pub async fn refresh(&mut self) {
+        let now = Instant::now();
+        let policy = self.policy;
+        let probes = self
+            .servers
+            .iter_mut()
+            .filter(|server| server.circuit(now) != CircuitState::Open && server.is_usable())
+            .map(|server| async move {
+                // a server which fails to answer is not known to be up to date anymore
+                server.last_seqno = server
+                    .query(&lite_server::GetMasterchainInfo, policy)
+                    .await
+                    .ok()
+                    .map(|info| info.0.last.seqno);
+            });
+        futures::future::join_all(probes).await;
+        self.last_refresh = Some(Instant::now());
+    }
+
+    /// Send `query` to the best server, retrying on other servers if it fails. Must be used
+    /// only for queries which may be repeated, which are all liteserver queries except
+    /// `sendMessage`.
+    ///
+    /// # Panics
+    ///
+    /// If `query` has `bytes` longer than [`tl::MAX_BYTES_LEN`], see [`tl::TlWrite::write_to`]
+    pub async fn query<Q: TlFunction>(&mut self, query: &Q) -> Result<Q::Reply, LiteError> {
+        self.query_with_attempts(query, self.max_attempts).await
+    }
+
+    /// Send serialized external message BoC to a single server, see
+    /// [`LiteClient::send_message`]
+    pub async fn send_message(&mut self, body: Vec<u8>) -> Result<(), LiteError> {
+        tl::check_bytes_len(&body)?;
+        let query = lite_server::SendMessage { body };
+        check_send_status(self.query_with_attempts(&query, 1).await?.0.status)
+    }
+
+    async fn query_with_attempts<Q: TlFunction>(
+        &mut self,
+        query: &Q,
+        attempts: usize,
+    ) -> Result<Q::Reply, LiteError> {
+        if self
+            .last_refresh
+            .is_none_or(|refresh| refresh.elapsed() >= self.refresh_interval)
+        {
+            self.refresh().await;
+        }
+        let mut tried = vec![false; self.servers.len()];
+        let mut error = LiteError::NoAvailableServers;
+        for _ in 0..attempts {
+            let Some(index) = self.select(&tried) else {
+                break;
+            };
+            tried[index] = true;
+            match self.servers[index].query(query, self.policy).await {
+                Err(e @ LiteError::ServerError(_)) if !e.is_retryable() => return Err(e),
+                Err(e) => error = e,
+                answer => return answer,
+            }
+        }
+        Err(error)
+    }
+
+    /// Index of the server with the lowest latency which is up to date and not in cooldown
+    fn select(&self, tried: &[bool]) -> Option<usize> {
+        let now = Instant::now();
+        let min_seqno = self
+            .last_seqno()
+            .map(|seqno| seqno.saturating_sub(i32::try_from(self.max_lag).unwrap_or(i32::MAX)));
+        self.servers
+            .iter()
+            .enumerate()
+            .filter(|(index, server)| {
+                let up_to_date = match min_seqno {
+                    Some(min_seqno) => server.last_seqno.is_some_and(|seqno| seqno >= min_seqno),
+                    None => true,
+                };
+                !tried[*index]
+                    && up_to_date
+                    && server.is_usable()
+                    && server.circuit(now) != CircuitState::Open
+            })
+            // servers after cooldown are used only if there are no healthy ones
+            .min_by_key(|(_, server)| {
+                (
+                    server.circuit(now) == CircuitState::HalfOpen,
+                    server.latency.unwrap_or(Duration::MAX),
+                )
+            })
+            .map(|(index, _)| index)
+    }
+}
@@ -0,0 +1,145 @@
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::helper_types::AdnlConnectionInfo;
+use crate::tl;
+use crate::tl::ton::adnl::{message, Message};
+use crate::{AdnlCodec, AdnlError, AdnlHandshake};
+
+/// Synchronous ADNL client which does not need any async runtime.
+///
+/// If a read or write fails (including timeouts), the session state is undefined
+/// and the client must be dropped.
+pub struct AdnlBlockingClient<T: Read + Write = TcpStream> {
+    transport: T,
+    codec: AdnlCodec,
+    connection_info: AdnlConnectionInfo,
+}
+
+impl AdnlBlockingClient<TcpStream> {
+    /// Connect ADNL client to specified server over [`TcpStream`]
+    pub fn connect<A: ToSocketAddrs>(
+        server_public: impl AsRef<[u8]>,
+        server_address: A,
+    ) -> Result<Self, AdnlError> {
+        let transport = TcpStream::connect(server_address)?;
+        Self::perform_handshake(transport, server_public)
+    }
+
+    /// Connect ADNL client to specified server, use `timeout` for connecting and as
+    /// initial read and write timeouts
+    pub fn connect_timeout(
+        server_public: impl AsRef<[u8]>,
+        server_address: &SocketAddr,
+        timeout: Duration,
+    ) -> Result<Self, AdnlError> {
+        let transport = TcpStream::connect_timeout(server_address, timeout)?;
+        transport.set_read_timeout(Some(timeout))?;
+        transport.set_write_timeout(Some(timeout))?;
+        Self::perform_handshake(transport, server_public)
+    }
+
+    /// Set timeout of receiving, `None` means blocking indefinitely
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), AdnlError> {
+        Ok(self.transport.set_read_timeout(timeout)?)
+    }
+
+    /// Set timeout of sending, `None` means blocking indefinitely
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> Result<(), AdnlError> {
+        Ok(self.transport.set_write_timeout(timeout)?)
+    }
+}
+
+impl<T: Read + Write> AdnlBlockingClient<T> {
+    /// Perform handshake built from random client keys and `remote_public` over `transport`
+    /// and check that handshake was successful
+    pub fn perform_handshake(
+        transport: T,
+        remote_public: impl AsRef<[u8]>,
+    ) -> Result<Self, AdnlError> {
+        let handshake = AdnlHandshake::random_for(remote_public)?;
+        Self::perform_custom_handshake(transport, &handshake)
+    }
+
+    /// Send `handshake` over `transport` and check that handshake was successful
+    pub fn perform_custom_handshake(
+        mut transport: T,
+        handshake: &AdnlHandshake,
+    ) -> Result<Self, AdnlError> {
+        transport.write_all(&handshake.to_bytes())?;
+        transport.flush()?;
+        let mut client = Self {
+            transport,
+            codec: handshake.make_client_codec(),
+            connection_info: AdnlConnectionInfo::new(
+                handshake.sender().into(),
+                handshake.receiver().clone(),
+            ),
+        };
+
+        // receive empty message to ensure that server knows our AES keys
+        client.receive()?;
+        Ok(client)
+    }
+
+    /// Get information about local and remote addresses of this connection
+    pub fn connection_info(&self) -> &AdnlConnectionInfo {
+        &self.connection_info
+    }
+
+    /// Send datagram
+    pub fn send(&mut self, data: &[u8]) -> Result<(), AdnlError> {
+        let mut packet = vec![0u8; AdnlCodec::packet_length(data.len())];
+        self.codec.encrypt_packet(data, &mut packet)?;
+        self.transport.write_all(&packet).map_err(map_eof)?;
+        self.transport.flush()?;
+        Ok(())
+    }
+
+    /// Receive next datagram
+    pub fn receive(&mut self) -> Result<Vec<u8>, AdnlError> {
+        let mut header = [0u8; 4];
+        self.transport.read_exact(&mut header).map_err(map_eof)?;
+        let length = self.codec.decrypt_length(header)?;
+        let mut packet = vec![0u8; length];
+        self.transport.read_exact(&mut packet).map_err(map_eof)?;
+        Ok(self.codec.decrypt_packet(&mut packet)?.to_vec())
+    }
+
+    /// Send already serialized `query` wrapped into `adnl.message.query` and wait for
+    /// `adnl.message.answer` with the same query id. Returns serialized answer,
+    /// all other datagrams received meanwhile are dropped.
+    pub fn query(&mut self, query: &[u8]) -> Result<Vec<u8>, AdnlError> {
+        if tl::check_bytes_len(query).is_err() {
+            return Err(AdnlError::TooLongPacket);
+        }
+        let query_id = rand::random::<[u8; 32]>();
+        let message = Message::MessageQuery(message::Query {
+            query_id,
+            query: query.to_vec(),
+        });
+        self.send(&tl::serialize(&message))?;
+        loop {
+            let packet = self.receive()?;
+            match tl::deserialize::<Message>(&packet) {
+                Ok(Message::MessageAnswer(answer)) if answer.query_id == query_id => {
+                    return Ok(answer.answer)
+                }
+                _ => log::debug!("dropping unexpected datagram of {} bytes", packet.len()),
+            }
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+}
+
+fn map_eof(e: io::Error) -> AdnlError {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        AdnlError::EndOfStream
+    } else {
+        AdnlError::IoError(e)
+    }
+}
@@ -0,0 +1,195 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use bytes::Bytes;
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use pin_project::pin_project;
+
+use crate::crypto::KeyPair;
+use crate::helper_types::AdnlConnectionInfo;
+use crate::{AdnlAddress, AdnlCodec, AdnlError, AdnlHandshake};
+
+/// Flush written packets once this many bytes are buffered
+const BACKPRESSURE_BOUNDARY: usize = 8 * 1024;
+
+/// ADNL peer over [`futures::io`] transports, for use with runtimes other than tokio
+/// (smol, async-std). Handshake and framing are the same as in tokio-based `AdnlPeer`,
+/// but there is no rate limiting of incoming datagrams and no outbound priority queue:
+/// datagrams are written in the order they are sent.
+#[pin_project]
+pub struct AdnlFuturesPeer<T> {
+    #[pin]
+    transport: T,
+    codec: AdnlCodec,
+    connection_info: AdnlConnectionInfo,
+    read_buffer: Vec<u8>,
+    read_filled: usize,
+    read_header: bool,
+    write_buffer: Vec<u8>,
+    write_offset: usize,
+}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> AdnlFuturesPeer<T> {
+    fn new(transport: T, codec: AdnlCodec, connection_info: AdnlConnectionInfo) -> Self {
+        Self {
+            transport,
+            codec,
+            connection_info,
+            read_buffer: vec![0u8; 4],
+            read_filled: 0,
+            read_header: true,
+            write_buffer: Vec::new(),
+            write_offset: 0,
+        }
+    }
+
+    /// Act as a client: perform handshake built from random client keys and `remote_public` over `transport` and check that handshake was successful.
+    /// Returns client part of ADNL connection
+    pub async fn perform_handshake(
+        transport: T,
+        remote_public: impl AsRef<[u8]>,
+    ) -> Result<Self, AdnlError> {
+        let handshake = AdnlHandshake::random_for(remote_public)?;
+        Self::perform_custom_handshake(transport, &handshake).await
+    }
+
+    /// Act as a client: send `handshake` over `transport` and check that handshake was successful
+    /// Returns client part of ADNL connection
+    pub async fn perform_custom_handshake(
+        mut transport: T,
+        handshake: &AdnlHandshake,
+    ) -> Result<Self, AdnlError> {
+        // send handshake
+        transport.write_all(&handshake.to_bytes()).await?;
+        transport.flush().await?;
+
+        let connection_info =
+            AdnlConnectionInfo::new(handshake.sender().into(), handshake.receiver().clone());
+        let mut client = Self::new(transport, handshake.make_client_codec(), connection_info);
+
+        // receive empty message to ensure that server knows our AES keys
+        match client.next().await {
+            Some(x) => x.map(|_| client),
+            None => Err(AdnlError::EndOfStream),
+        }
+    }
+
+    /// Act as a server: receive handshake over transport using [`KeyPair`] provided by `keypair_selector`.
+    pub async fn handle_handshake<F: Fn(&AdnlAddress) -> Option<KeyPair>>(
+        mut transport: T,
+        keypair_selector: F,
+    ) -> Result<Self, AdnlError> {
+        // receive handshake
+        let mut packet = [0u8; 256];
+        transport.read_exact(&mut packet).await?;
+        let handshake = AdnlHandshake::decrypt_from_raw(&packet, keypair_selector)?;
+        let connection_info =
+            AdnlConnectionInfo::new(handshake.receiver().clone(), handshake.sender().into());
+        let mut server = Self::new(transport, handshake.make_server_codec(), connection_info);
+
+        // send empty packet to proof knowledge of AES keys
+        server.send(Bytes::new()).await?;
+
+        Ok(server)
+    }
+}
+
+impl<T> AdnlFuturesPeer<T> {
+    /// Get information about local and remote addresses of this connection
+    pub fn connection_info(&self) -> &AdnlConnectionInfo {
+        &self.connection_info
+    }
+}
+
+impl<T: AsyncRead> Stream for AdnlFuturesPeer<T> {
+    type Item = Result<Bytes, AdnlError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            while *this.read_filled < this.read_buffer.len() {
+                let buffer = &mut this.read_buffer[*this.read_filled..];
+                let n = ready!(this.transport.as_mut().poll_read(cx, buffer))?;
+                if n == 0 {
+                    // stream may only end between packets
+                    return if *this.read_header && *this.read_filled == 0 {
+                        Poll::Ready(None)
+                    } else {
+                        Poll::Ready(Some(Err(AdnlError::EndOfStream)))
+                    };
+                }
+                *this.read_filled += n;
+            }
+            *this.read_filled = 0;
+
+            if *this.read_header {
+                let header = this.read_buffer[..4].try_into().unwrap();
+                let length = this.codec.decrypt_length(header)?;
+                this.read_buffer.resize(length, 0);
+                *this.read_header = false;
+            } else {
+                let result = this
+                    .codec
+                    .decrypt_packet(this.read_buffer)
+                    .map(Bytes::copy_from_slice);
+                this.read_buffer.resize(4, 0);
+                *this.read_header = true;
+                return Poll::Ready(Some(result));
+            }
+        }
+    }
+}
+
+impl<T: AsyncWrite> AdnlFuturesPeer<T> {
+    fn poll_write_buffer(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+        while *this.write_offset < this.write_buffer.len() {
+            let buffer = &this.write_buffer[*this.write_offset..];
+            let n = ready!(this.transport.as_mut().poll_write(cx, buffer))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            *this.write_offset += n;
+        }
+        this.write_buffer.clear();
+        *this.write_offset = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: AsyncWrite> Sink<Bytes> for AdnlFuturesPeer<T> {
+    type Error = AdnlError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.write_buffer.len() >= BACKPRESSURE_BOUNDARY {
+            ready!(self.poll_write_buffer(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
+        let this = self.project();
+        let start_offset = this.write_buffer.len();
+        this.write_buffer
+            .resize(start_offset + AdnlCodec::packet_length(item.len()), 0);
+        let result = this
+            .codec
+            .encrypt_packet(&item, &mut this.write_buffer[start_offset..]);
+        if result.is_err() {
+            this.write_buffer.truncate(start_offset);
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_write_buffer(cx))?;
+        Poll::Ready(Ok(ready!(self.project().transport.poll_flush(cx))?))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_write_buffer(cx))?;
+        Poll::Ready(Ok(ready!(self.project().transport.poll_close(cx))?))
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
+
+use tokio::time::Sleep;
+use tokio_util::bytes::Bytes;
+
+use crate::{AdnlAddress, AdnlError};
+
+/// What to do with a peer which exceeds its limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitAction {
+    /// Delay delivery of incoming datagrams until the peer is within its limits again
+    #[default]
+    Throttle,
+    /// Fail the stream with [`crate::AdnlError::RateLimited`] and end it, the transport is
+    /// shut down
+    Disconnect,
+}
+
+/// Which connections share the same quota
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateLimitScope {
+    /// Every connection gets its own quota
+    Connection,
+    /// Connections from the same remote ADNL address share the quota
+    Address,
+    /// Connections from the same IP share the quota
+    Ip(IpAddr),
+}
+
+/// Limits applied to incoming datagrams of [`crate::AdnlPeer`]
+#[derive(Debug, Clone, Default)]
+pub struct AdnlRateLimits {
+    frames_per_second: Option<u32>,
+    bytes_per_second: Option<u64>,
+    max_datagram_length: Option<usize>,
+    action: RateLimitAction,
+}
+
+impl AdnlRateLimits {
+    /// Limit number of datagrams per second, bursts of up to one second worth of datagrams are allowed
+    pub fn with_frames_per_second(mut self, frames_per_second: u32) -> Self {
+        self.frames_per_second = Some(frames_per_second.max(1));
+        self
+    }
+
+    /// Limit payload bytes per second, bursts of up to one second worth of bytes are allowed
+    pub fn with_bytes_per_second(mut self, bytes_per_second: u64) -> Self {
+        self.bytes_per_second = Some(bytes_per_second.max(1));
+        self
+    }
+
+    /// Limit length of a single incoming datagram, longer datagrams fail the stream with
+    /// [`crate::AdnlError::TooLongPacket`]
+    pub fn with_max_datagram_length(mut self, max_datagram_length: usize) -> Self {
+        self.max_datagram_length = Some(max_datagram_length);
+        self
+    }
+
+    /// Set action to take when a peer exceeds its rate
+    pub fn with_action(mut self, action: RateLimitAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    pub fn frames_per_second(&self) -> Option<u32> {
+        self.frames_per_second
+    }
+
+    pub fn bytes_per_second(&self) -> Option<u64> {
+        self.bytes_per_second
+    }
+
+    pub fn max_datagram_length(&self) -> Option<usize> {
+        self.max_datagram_length
+    }
+
+    pub fn action(&self) -> RateLimitAction {
+        self.action
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum BucketKey {
+    Address([u8; 32]),
+    Ip(IpAddr),
+}
+
+/// Token bucket which is allowed to go into debt, debt is then paid off by waiting
+pub(crate) struct TokenBucket {
+    frames: f64,
+    bytes: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(limits: &AdnlRateLimits) -> Self {
+        Self {
+            frames: limits.frames_per_second.unwrap_or_default() as f64,
+            bytes: limits.bytes_per_second.unwrap_or_default() as f64,
+            updated_at: Instant::now(),
+        }
+    }
+
+    /// Account a datagram of `length` bytes and return how long the peer must wait
+    /// to get back within its limits
+    pub(crate) fn consume(&mut self, limits: &AdnlRateLimits, length: usize) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.updated_at = now;
+
+        let mut wait = 0f64;
+        if let Some(rate) = limits.frames_per_second.map(|x| x as f64) {
+            self.frames = (self.frames + elapsed * rate).min(rate) - 1.0;
+            if self.frames < 0.0 {
+                wait = wait.max(-self.frames / rate);
+            }
+        }
+        if let Some(rate) = limits.bytes_per_second.map(|x| x as f64) {
+            self.bytes = (self.bytes + elapsed * rate).min(rate) - length as f64;
+            if self.bytes < 0.0 {
+                wait = wait.max(-self.bytes / rate);
+            }
+        }
+        Duration::from_secs_f64(wait)
+    }
+}
+
+/// Shared registry of quotas, clone it to every connection which must be accounted together
+#[derive(Clone)]
+pub struct AdnlRateLimiter {
+    limits: AdnlRateLimits,
+    buckets: Arc<Mutex<HashMap<BucketKey, Weak<Mutex<TokenBucket>>>>>,
+}
+
+impl AdnlRateLimiter {
+    pub fn new(limits: AdnlRateLimits) -> Self {
+        Self {
+            limits,
+            buckets: Default::default(),
+        }
+    }
+
+    pub fn limits(&self) -> &AdnlRateLimits {
+        &self.limits
+    }
+
+    /// Get a bucket for connection with `remote_address` within given scope
+    pub(crate) fn bucket(
+        &self,
+        scope: &RateLimitScope,
+        remote_address: &AdnlAddress,
+    ) -> Arc<Mutex<TokenBucket>> {
+        let key = match scope {
+            RateLimitScope::Connection => {
+                return Arc::new(Mutex::new(TokenBucket::new(&self.limits)))
+            }
+            RateLimitScope::Address => BucketKey::Address(remote_address.to_bytes()),
+            RateLimitScope::Ip(ip) => BucketKey::Ip(*ip),
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        if let Some(bucket) = buckets.get(&key).and_then(Weak::upgrade) {
+            return bucket;
+        }
+        // forget quotas of peers which are not connected anymore
+        buckets.retain(|_, bucket| bucket.strong_count() > 0);
+        let bucket = Arc::new(Mutex::new(TokenBucket::new(&self.limits)));
+        buckets.insert(key, Arc::downgrade(&bucket));
+        bucket
+    }
+}
+
+/// Per-connection state of rate limiting
+pub(crate) struct PeerLimiter {
+    limits: AdnlRateLimits,
+    bucket: Arc<Mutex<TokenBucket>>,
+    sleep: Option<Pin<Box<Sleep>>>,
+    delayed: Option<Bytes>,
+    /// Peer has exceeded its limits with [`RateLimitAction::Disconnect`]
+    disconnected: bool,
+}
+
+impl PeerLimiter {
+    pub(crate) fn limits(&self) -> &AdnlRateLimits {
+        &self.limits
+    }
+
+    pub(crate) fn new(
+        limiter: &AdnlRateLimiter,
+        scope: &RateLimitScope,
+        remote_address: &AdnlAddress,
+    ) -> Self {
+        Self {
+            limits: limiter.limits.clone(),
+            bucket: limiter.bucket(scope, remote_address),
+            sleep: None,
+            delayed: None,
+            disconnected: false,
+        }
+    }
+
+    pub(crate) fn is_disconnected(&self) -> bool {
+        self.disconnected
+    }
+
+    /// Wait for a throttled datagram to be released
+    pub(crate) fn poll_delayed(&mut self, cx: &mut Context<'_>) -> Poll<Option<Bytes>> {
+        if let Some(sleep) = self.sleep.as_mut() {
+            ready!(sleep.as_mut().poll(cx));
+            self.sleep = None;
+        }
+        Poll::Ready(self.delayed.take())
+    }
+
+    /// Account received datagram. Returns it back if it can be delivered immediately,
+    /// otherwise either delays it or fails depending on configured action.
+    pub(crate) fn accept(&mut self, packet: Bytes) -> Result<Option<Bytes>, AdnlError> {
+        let wait = self
+            .bucket
+            .lock()
+            .unwrap()
+            .consume(&self.limits, packet.len());
+        if wait.is_zero() {
+            return Ok(Some(packet));
+        }
+        match self.limits.action {
+            RateLimitAction::Disconnect => {
+                self.disconnected = true;
+                Err(AdnlError::RateLimited)
+            }
+            RateLimitAction::Throttle => {
+                self.sleep = Some(Box::pin(tokio::time::sleep(wait)));
+                self.delayed = Some(packet);
+                Ok(None)
+            }
+        }
+    }
+}
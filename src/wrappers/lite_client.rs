@@ -0,0 +1,646 @@
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpStream, ToSocketAddrs};
+
+use crate::boc::{self, BocError, Cell, CellSlice};
+use crate::config::BlockchainConfig;
+use crate::helper_types::CRC16;
+use crate::proof::{self, ProofError};
+use crate::tl::ton::adnl::{message, Message};
+use crate::tl::ton::lite_server::{self, AccountId, TransactionId3};
+use crate::tl::ton::ton_node::{BlockId, BlockIdExt};
+use crate::tl::{self, TlFunction, TlWrite};
+use crate::tvm::{self, StackValue};
+use crate::{AdnlError, AdnlPeer, LiteError, LiteServerError};
+
+/// Default time to wait for an answer to a single query
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval between polls of account state in [`LiteClient::send_message_and_wait`]
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of transactions requested at once in [`LiteClient::send_message_and_wait`]
+const TRANSACTIONS_BATCH: u32 = 16;
+
+/// `runSmcMethod` mode which requests proofs and result of the method
+const RUN_SMC_METHOD_MODE: u32 = 0b111;
+
+/// `listBlockTransactions` mode which requests account, lt and hash of transactions
+const LIST_BLOCK_TRANSACTIONS_MODE: u32 = 0b111;
+
+/// Compute id of smart-contract get-method by its name, as used in `runSmcMethod`
+pub fn smc_method_id(name: &str) -> i64 {
+    (CRC16.checksum(name.as_bytes()) as i64) | 0x10000
+}
+
+/// Map status of `liteServer.sendMsgStatus` to result, 1 means that the message is accepted
+pub(crate) fn check_send_status(status: i32) -> Result<(), LiteError> {
+    match status {
+        1 => Ok(()),
+        status => Err(LiteError::MessageRejected(status)),
+    }
+}
+
+/// Key to find block by in [`LiteClient::lookup_block`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockLookup {
+    /// Block with this seqno
+    Seqno(i32),
+    /// Block which contains logical time
+    Lt(i64),
+    /// Block generated at unix time
+    Utime(i32),
+}
+
+/// Prefix of liteserver query, serialized into `liteServer.query` before the query itself.
+/// Several prefixes may be combined, they are applied by the server in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiteQueryPrefix {
+    /// `liteServer.waitMasterchainSeqno`: server holds the query for up to `timeout` until it
+    /// has masterchain block `seqno`. `timeout` is added to the client timeout of the query.
+    WaitMasterchainSeqno { seqno: i32, timeout: Duration },
+    /// Any other already serialized prefix
+    Raw(Vec<u8>),
+}
+
+impl LiteQueryPrefix {
+    /// Time which the server may spend on this prefix before processing the query
+    fn wait_time(&self) -> Duration {
+        match self {
+            Self::WaitMasterchainSeqno { timeout, .. } => *timeout,
+            Self::Raw(_) => Duration::ZERO,
+        }
+    }
+}
+
+impl TlWrite for LiteQueryPrefix {
+    fn write_to(&self, packet: &mut Vec<u8>) {
+        match self {
+            Self::WaitMasterchainSeqno { seqno, timeout } => {
+                let prefix = lite_server::WaitMasterchainSeqno {
+                    seqno: *seqno,
+                    timeout_ms: timeout.as_millis().min(i32::MAX as u128) as i32,
+                };
+                prefix.write_to(packet)
+            }
+            Self::Raw(prefix) => packet.extend_from_slice(prefix),
+        }
+    }
+}
+
+/// Liteserver client on top of [`AdnlPeer`], which wraps queries into `liteServer.query` and
+/// `adnl.message.query` and decodes answers.
+///
+/// Queries are sent one by one. Datagrams which are not answers to the current query, including
+/// answers which arrived after a timeout, are dropped.
+///
+/// In verified mode, see [`Self::with_verification`], proofs in answers about blocks, accounts
+/// and transactions are checked with [`crate::proof`].
+pub struct LiteClient<T = TcpStream>
+where
+    T: AsyncRead + AsyncWrite,
+{
+    peer: AdnlPeer<T>,
+    timeout: Duration,
+    prefixes: Vec<LiteQueryPrefix>,
+    verify: bool,
+}
+
+impl LiteClient<TcpStream> {
+    /// Connect to liteserver with specified public key over [`TcpStream`]
+    pub async fn connect<A: ToSocketAddrs>(
+        server_public: impl AsRef<[u8]>,
+        server_address: A,
+    ) -> Result<Self, LiteError> {
+        Ok(Self::new(
+            AdnlPeer::connect(server_public, server_address).await?,
+        ))
+    }
+}
+
+impl<T> LiteClient<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    /// Use already connected `peer`
+    pub fn new(peer: AdnlPeer<T>) -> Self {
+        Self {
+            peer,
+            timeout: DEFAULT_TIMEOUT,
+            prefixes: Vec::new(),
+            verify: false,
+        }
+    }
+
+    /// Set time to wait for an answer to each query, 10 seconds by default
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Check proofs in answers of [`Self::get_account_state`], [`Self::run_smc_method`],
+    /// [`Self::get_block`], [`Self::get_block_header`] and [`Self::get_transactions`] against
+    /// the requested block or transaction, which must be trusted. Answers which fail the checks
+    /// are returned as [`LiteError::ProofError`]. Disabled by default.
+    pub fn with_verification(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Set prefixes to add to every following query, including typed ones
+    pub fn set_prefixes(&mut self, prefixes: Vec<LiteQueryPrefix>) {
+        self.prefixes = prefixes;
+    }
+
+    pub fn into_inner(self) -> AdnlPeer<T> {
+        self.peer
+    }
+
+    /// Send liteserver `query` and decode the answer
+    ///
+    /// # Panics
+    ///
+    /// If `query` has `bytes` longer than [`tl::MAX_BYTES_LEN`], see [`TlWrite::write_to`]
+    pub async fn query<Q: TlFunction>(&mut self, query: &Q) -> Result<Q::Reply, LiteError> {
+        let answer = self.query_raw(tl::serialize(query)).await?;
+        Ok(tl::deserialize(&answer)?)
+    }
+
+    /// Send already serialized liteserver `query` and return serialized answer,
+    /// `liteServer.error` answer is returned as [`LiteError::ServerError`]
+    pub async fn query_raw(&mut self, query: Vec<u8>) -> Result<Vec<u8>, LiteError> {
+        self.query_raw_with_prefixes(&[], &query).await
+    }
+
+    /// Send liteserver `query` with `prefixes` in addition to ones set by
+    /// [`Self::set_prefixes`] and decode the answer
+    pub async fn query_with_prefixes<Q: TlFunction>(
+        &mut self,
+        prefixes: &[LiteQueryPrefix],
+        query: &Q,
+    ) -> Result<Q::Reply, LiteError> {
+        let answer = self
+            .query_raw_with_prefixes(prefixes, &tl::serialize(query))
+            .await?;
+        Ok(tl::deserialize(&answer)?)
+    }
+
+    /// Send already serialized liteserver `query` with `prefixes` in addition to ones set by
+    /// [`Self::set_prefixes`] and return serialized answer
+    pub async fn query_raw_with_prefixes(
+        &mut self,
+        prefixes: &[LiteQueryPrefix],
+        query: &[u8],
+    ) -> Result<Vec<u8>, LiteError> {
+        let mut data = Vec::new();
+        let mut timeout = self.timeout;
+        for prefix in self.prefixes.iter().chain(prefixes) {
+            prefix.write_to(&mut data);
+            timeout += prefix.wait_time();
+        }
+        data.extend_from_slice(query);
+        tl::check_bytes_len(&data)?;
+        let query = tl::serialize(&lite_server::Query { data });
+        tl::check_bytes_len(&query)?;
+
+        let query_id = rand::random::<[u8; 32]>();
+        let message = Message::MessageQuery(message::Query { query_id, query });
+        let exchange = async {
+            self.peer.send(tl::serialize(&message).into()).await?;
+            while let Some(packet) = self.peer.next().await {
+                let packet = packet?;
+                match tl::deserialize::<Message>(&packet) {
+                    Ok(Message::MessageAnswer(answer)) if answer.query_id == query_id => {
+                        return Ok::<_, AdnlError>(answer.answer)
+                    }
+                    _ => log::debug!("dropping unexpected datagram of {} bytes", packet.len()),
+                }
+            }
+            Err(AdnlError::EndOfStream)
+        };
+        let answer = tokio::time::timeout(timeout, exchange)
+            .await
+            .map_err(|_| LiteError::Timeout)??;
+        if let Some(e) = LiteServerError::from_answer(&answer) {
+            return Err(e.into());
+        }
+        Ok(answer)
+    }
+
+    /// Get current unix time of the server
+    pub async fn get_time(&mut self) -> Result<i32, LiteError> {
+        Ok(self.query(&lite_server::GetTime).await?.0.now)
+    }
+
+    /// Get version and capabilities of the server
+    pub async fn get_version(&mut self) -> Result<lite_server::Version, LiteError> {
+        Ok(self.query(&lite_server::GetVersion).await?.0)
+    }
+
+    /// Get the last masterchain block known to the server
+    pub async fn get_masterchain_info(
+        &mut self,
+    ) -> Result<lite_server::MasterchainInfo, LiteError> {
+        Ok(self.query(&lite_server::GetMasterchainInfo).await?.0)
+    }
+
+    /// Get the last masterchain block along with its time and server version
+    pub async fn get_masterchain_info_ext(
+        &mut self,
+        mode: u32,
+    ) -> Result<lite_server::MasterchainInfoExt, LiteError> {
+        Ok(self
+            .query(&lite_server::GetMasterchainInfoExt { mode })
+            .await?
+            .0)
+    }
+
+    /// Get state of `account` at block `id` along with the proofs
+    pub async fn get_account_state(
+        &mut self,
+        id: BlockIdExt,
+        account: AccountId,
+    ) -> Result<lite_server::AccountState, LiteError> {
+        let query = lite_server::GetAccountState { id, account };
+        let answer = self.query(&query).await?.0;
+        if self.verify {
+            proof::check_account_state(&answer, &query.id, &query.account)?;
+        }
+        Ok(answer)
+    }
+
+    /// Same as [`Self::get_account_state`], but the state is pruned by the server to reduce
+    /// its size
+    pub async fn get_account_state_prunned(
+        &mut self,
+        id: BlockIdExt,
+        account: AccountId,
+    ) -> Result<lite_server::AccountState, LiteError> {
+        let query = lite_server::GetAccountStatePrunned { id, account };
+        let answer = self.query(&query).await?.0;
+        if self.verify {
+            proof::check_account_state(&answer, &query.id, &query.account)?;
+        }
+        Ok(answer)
+    }
+
+    /// Run get-method `method` of `account` at block `id`. `params` is serialized TVM stack, see
+    /// [`crate::tvm`], the result contains serialized resulting stack along with the proofs.
+    pub async fn run_smc_method(
+        &mut self,
+        id: BlockIdExt,
+        account: AccountId,
+        method: &str,
+        params: Vec<u8>,
+    ) -> Result<lite_server::RunMethodResult, LiteError> {
+        let query = lite_server::RunSmcMethod {
+            mode: RUN_SMC_METHOD_MODE,
+            id,
+            account,
+            method_id: smc_method_id(method),
+            params,
+        };
+        let answer = self.query(&query).await?.0;
+        if self.verify {
+            proof::check_run_method_result(&answer, &query.id, &query.account)?;
+        }
+        Ok(answer)
+    }
+
+    /// Run get-method `method` of `account` at block `id` with `params` and return the
+    /// resulting stack. Both stacks have the top value last. Exit codes other than 0 and 1 are
+    /// returned as [`LiteError::GetMethodFailed`].
+    pub async fn run_get_method(
+        &mut self,
+        id: BlockIdExt,
+        account: AccountId,
+        method: &str,
+        params: &[StackValue],
+    ) -> Result<Vec<StackValue>, LiteError> {
+        let params = tvm::serialize_stack(params)?;
+        tl::check_bytes_len(&params)?;
+        let answer = self.run_smc_method(id, account, method, params).await?;
+        if !matches!(answer.exit_code, 0 | 1) {
+            return Err(LiteError::GetMethodFailed(answer.exit_code));
+        }
+        Ok(tvm::parse_stack(
+            answer.result.as_deref().unwrap_or_default(),
+        )?)
+    }
+
+    /// Send serialized external message BoC. Statuses other than 1, which means that the
+    /// message is accepted, are returned as [`LiteError::MessageRejected`].
+    pub async fn send_message(&mut self, body: Vec<u8>) -> Result<(), LiteError> {
+        tl::check_bytes_len(&body)?;
+        let answer = self.query(&lite_server::SendMessage { body }).await?.0;
+        check_send_status(answer.status)
+    }
+
+    /// Send external message BoC to `account` and poll transactions of the account until the
+    /// one with this message as inbound appears. Returns id of the shard block whose proven
+    /// state already includes the transaction, which may have been created in an earlier block
+    /// of the shard, and the transaction itself.
+    ///
+    /// The message is not inspected, so `valid_until` must be the same as in the message body
+    /// (e.g. `valid_until` signed by a wallet), [`LiteError::MessageExpired`] is returned once
+    /// masterchain has passed it. Regardless of that, the whole wait is limited by `timeout`,
+    /// after which [`LiteError::Timeout`] is returned.
+    pub async fn send_message_and_wait(
+        &mut self,
+        body: Vec<u8>,
+        account: AccountId,
+        valid_until: i32,
+        timeout: Duration,
+    ) -> Result<(BlockIdExt, Cell), LiteError> {
+        let message_hash = boc::parse_single(&body)?.hash();
+        let wait = async {
+            let last = self.get_masterchain_info().await?.last;
+            let (_, mut known) = self.last_transaction(last, &account).await?;
+            self.send_message(body).await?;
+            loop {
+                let info = self.get_masterchain_info_ext(0).await?;
+                let (shardblk, current) = self.last_transaction(info.last, &account).await?;
+                if current != known {
+                    let found = self
+                        .find_transaction(&account, &shardblk, current, known, message_hash)
+                        .await?;
+                    if let Some(transaction) = found {
+                        return Ok((shardblk, transaction));
+                    }
+                    known = current;
+                }
+                if info.last_utime > valid_until {
+                    return Err(LiteError::MessageExpired);
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .unwrap_or(Err(LiteError::Timeout))
+    }
+
+    /// Logical time and hash of the last transaction of `account` at masterchain block `id`,
+    /// `None` if the account does not exist, together with the proven shard block of the state
+    async fn last_transaction(
+        &mut self,
+        id: BlockIdExt,
+        account: &AccountId,
+    ) -> Result<(BlockIdExt, Option<(u64, [u8; 32])>), LiteError> {
+        let query = lite_server::GetAccountStatePrunned {
+            id,
+            account: account.clone(),
+        };
+        let answer = self.query(&query).await?.0;
+        let shard_account = proof::check_account_state(&answer, &query.id, account)?;
+        let last = shard_account.map(|account| (account.last_trans_lt, account.last_trans_hash));
+        Ok((answer.shardblk, last))
+    }
+
+    /// Walk transactions of `account` back from `from`, the last transaction in the state of
+    /// `shardblk`, to `until` and find the one with inbound message of `message_hash`
+    async fn find_transaction(
+        &mut self,
+        account: &AccountId,
+        shardblk: &BlockIdExt,
+        from: Option<(u64, [u8; 32])>,
+        until: Option<(u64, [u8; 32])>,
+        message_hash: [u8; 32],
+    ) -> Result<Option<Cell>, LiteError> {
+        let until_lt = until.map_or(0, |(lt, _)| lt);
+        let mut next = from;
+        while let Some((lt, hash)) = next.filter(|&(lt, _)| lt > until_lt) {
+            let query = lite_server::GetTransactions {
+                count: TRANSACTIONS_BATCH,
+                account: account.clone(),
+                lt: lt as i64,
+                hash,
+            };
+            let answer = self.query(&query).await?.0;
+            let transactions =
+                proof::check_transactions(&answer, account, lt as i64, hash, Some(shardblk))?;
+            next = None;
+            for transaction in transactions {
+                let links = TransactionLinks::load(&transaction)?;
+                if links.lt <= until_lt {
+                    return Ok(None);
+                }
+                if links.in_msg_hash == Some(message_hash) {
+                    return Ok(Some(transaction));
+                }
+                // the first transaction of the account has no previous one
+                next = Some(links.prev).filter(|&(lt, _)| lt != 0);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get proof of masterchain blocks from `known` block to `target`, or to the last block
+    /// known to the server
+    pub async fn get_block_proof(
+        &mut self,
+        known: BlockIdExt,
+        target: Option<BlockIdExt>,
+    ) -> Result<lite_server::PartialBlockProof, LiteError> {
+        let query = lite_server::GetBlockProof {
+            mode: target.is_some() as u32,
+            known_block: known,
+            target_block: target,
+        };
+        Ok(self.query(&query).await?.0)
+    }
+
+    /// Walk chain of block proofs from trusted masterchain block `init`, such as
+    /// `validator.init_block` of global config, to the last masterchain block known to the
+    /// server. Returns the last block, which is trusted as well.
+    pub async fn sync(&mut self, init: BlockIdExt) -> Result<BlockIdExt, LiteError> {
+        let mut known = init;
+        loop {
+            let proof = self.get_block_proof(known.clone(), None).await?;
+            let last = proof::check_block_proof(&proof, &known)?;
+            if proof.complete {
+                return Ok(last);
+            }
+            if last == known {
+                return Err(ProofError::InvalidProof("block proof does not advance").into());
+            }
+            known = last;
+        }
+    }
+
+    /// Get serialized block `id`
+    pub async fn get_block(&mut self, id: BlockIdExt) -> Result<lite_server::BlockData, LiteError> {
+        let query = lite_server::GetBlock { id };
+        let answer = self.query(&query).await?.0;
+        if self.verify {
+            proof::check_block_data(&answer, &query.id)?;
+        }
+        Ok(answer)
+    }
+
+    /// Get proof of block `id` header, `mode` selects included header fields
+    pub async fn get_block_header(
+        &mut self,
+        id: BlockIdExt,
+        mode: u32,
+    ) -> Result<lite_server::BlockHeader, LiteError> {
+        let query = lite_server::GetBlockHeader { id, mode };
+        let answer = self.query(&query).await?.0;
+        if self.verify {
+            proof::check_block_header(&answer, &query.id)?;
+        }
+        Ok(answer)
+    }
+
+    /// Get config `params` of masterchain block `id`. The config is taken from the proofs of
+    /// the answer, which are always checked against `id`.
+    pub async fn get_config_params(
+        &mut self,
+        id: BlockIdExt,
+        params: Vec<i32>,
+    ) -> Result<BlockchainConfig, LiteError> {
+        let query = lite_server::GetConfigParams {
+            mode: 0,
+            id,
+            param_list: params,
+        };
+        let answer = self.query(&query).await?.0;
+        Ok(proof::check_config_info(&answer, &query.id)?)
+    }
+
+    /// Get the whole config of masterchain block `id`, see [`Self::get_config_params`]
+    pub async fn get_config_all(&mut self, id: BlockIdExt) -> Result<BlockchainConfig, LiteError> {
+        let query = lite_server::GetConfigAll { mode: 0, id };
+        let answer = self.query(&query).await?.0;
+        Ok(proof::check_config_info(&answer, &query.id)?)
+    }
+
+    /// Find block of `workchain` and `shard` by seqno, logical time or unix time
+    pub async fn lookup_block(
+        &mut self,
+        workchain: i32,
+        shard: i64,
+        by: BlockLookup,
+    ) -> Result<lite_server::BlockHeader, LiteError> {
+        let (mode, seqno, lt, utime) = match by {
+            BlockLookup::Seqno(seqno) => (0b001, seqno, None, None),
+            BlockLookup::Lt(lt) => (0b010, 0, Some(lt), None),
+            BlockLookup::Utime(utime) => (0b100, 0, None, Some(utime)),
+        };
+        let query = lite_server::LookupBlock {
+            mode,
+            id: BlockId::new(workchain, shard, seqno),
+            lt,
+            utime,
+        };
+        Ok(self.query(&query).await?.0)
+    }
+
+    /// List up to `count` transactions of block `id`, starting after transaction `after`
+    pub async fn list_block_transactions(
+        &mut self,
+        id: BlockIdExt,
+        count: u32,
+        after: Option<TransactionId3>,
+        reverse_order: bool,
+    ) -> Result<lite_server::BlockTransactions, LiteError> {
+        let query = lite_server::ListBlockTransactions {
+            id,
+            mode: LIST_BLOCK_TRANSACTIONS_MODE,
+            count,
+            after,
+            reverse_order,
+            want_proof: false,
+        };
+        Ok(self.query(&query).await?.0)
+    }
+
+    /// Same as [`Self::list_block_transactions`], but returns serialized transactions instead
+    /// of their ids
+    pub async fn list_block_transactions_ext(
+        &mut self,
+        id: BlockIdExt,
+        count: u32,
+        after: Option<TransactionId3>,
+        reverse_order: bool,
+    ) -> Result<lite_server::BlockTransactionsExt, LiteError> {
+        let query = lite_server::ListBlockTransactionsExt {
+            id,
+            mode: LIST_BLOCK_TRANSACTIONS_MODE,
+            count,
+            after,
+            reverse_order,
+            want_proof: false,
+        };
+        Ok(self.query(&query).await?.0)
+    }
+
+    /// Get up to `count` transactions of `account`, starting from transaction `lt`, `hash` and
+    /// going back in time. Verification checks the chain of transactions, block ids of the
+    /// answer are not proven, see [`proof::check_transactions`].
+    pub async fn get_transactions(
+        &mut self,
+        count: u32,
+        account: AccountId,
+        lt: i64,
+        hash: [u8; 32],
+    ) -> Result<lite_server::TransactionList, LiteError> {
+        let query = lite_server::GetTransactions {
+            count,
+            account,
+            lt,
+            hash,
+        };
+        let answer = self.query(&query).await?.0;
+        if self.verify {
+            proof::check_transactions(&answer, &query.account, lt, hash, None)?;
+        }
+        Ok(answer)
+    }
+
+    /// Get transaction of `account` with logical time `lt` from block `id`
+    pub async fn get_one_transaction(
+        &mut self,
+        id: BlockIdExt,
+        account: AccountId,
+        lt: i64,
+    ) -> Result<lite_server::TransactionInfo, LiteError> {
+        Ok(self
+            .query(&lite_server::GetOneTransaction { id, account, lt })
+            .await?
+            .0)
+    }
+}
+
+/// Fields of `Transaction` needed to walk the chain of transactions of an account
+struct TransactionLinks {
+    lt: u64,
+    /// Logical time and hash of the previous transaction
+    prev: (u64, [u8; 32]),
+    in_msg_hash: Option<[u8; 32]>,
+}
+
+impl TransactionLinks {
+    fn load(transaction: &Cell) -> Result<Self, BocError> {
+        let mut slice = CellSlice::new(transaction)?;
+        // tag and account_addr, checked by `proof::check_transactions`
+        slice.skip_bits(4 + 256)?;
+        let lt = slice.load_uint(64)?;
+        let prev_hash = slice.load_u256()?;
+        let prev_lt = slice.load_uint(64)?;
+        // now, outmsg_cnt, orig_status and end_status
+        slice.skip_bits(32 + 15 + 2 + 2)?;
+        let mut messages = CellSlice::new(slice.load_reference()?)?;
+        let in_msg_hash = match messages.load_bit()? {
+            true => Some(messages.load_reference()?.hash()),
+            false => None,
+        };
+        Ok(Self {
+            lt,
+            prev: (prev_lt, prev_hash),
+            in_msg_hash,
+        })
+    }
+}
@@ -1,2 +1,20 @@
+#[cfg(feature = "tokio")]
+pub mod balancer;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "std")]
 pub mod builder;
+#[cfg(feature = "futures-io")]
+pub mod futures_peer;
+#[cfg(feature = "tokio")]
+pub mod limiter;
+#[cfg(feature = "tokio")]
+pub mod lite_client;
+#[cfg(feature = "tokio")]
 pub mod peer;
+#[cfg(feature = "tokio")]
+pub mod proxy;
+#[cfg(feature = "tokio")]
+pub mod queue;
+#[cfg(feature = "websocket")]
+pub mod websocket;
@@ -1,13 +1,20 @@
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{ready, Context, Poll};
 
-use crate::crypto::{KeyPair, PublicKey};
+use crate::crypto::KeyPair;
 use crate::helper_types::AdnlConnectionInfo;
-use crate::{AdnlAddress, AdnlBuilder, AdnlError, AdnlHandshake};
+use crate::wrappers::limiter::PeerLimiter;
+use crate::wrappers::queue::OutboundQueue;
+use crate::{
+    AdnlAddress, AdnlError, AdnlHandshake, AdnlPriority, AdnlProxy, AdnlQueueStats,
+    AdnlRateLimiter, RateLimitScope,
+};
 use futures::{Sink, SinkExt, Stream, StreamExt};
 use pin_project::pin_project;
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream};
 use tokio::net::{TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
 use tokio_util::bytes::Bytes;
 use tokio_util::codec::{Decoder, Framed};
 
@@ -22,6 +29,8 @@ where
     #[pin]
     stream: Framed<T, AdnlCodec>,
     connection_info: AdnlConnectionInfo,
+    limiter: Option<PeerLimiter>,
+    queue: OutboundQueue,
 }
 
 impl AdnlPeer<TcpStream> {
@@ -34,6 +43,54 @@ impl AdnlPeer<TcpStream> {
         let client = Self::perform_handshake(transport, server_public).await?;
         Ok(client)
     }
+
+    /// Connect ADNL client to specified server (`host:port`) through SOCKS5 or HTTP CONNECT proxy
+    pub async fn connect_via_proxy(
+        server_public: impl AsRef<[u8]>,
+        server_address: &str,
+        proxy: &AdnlProxy,
+    ) -> Result<AdnlPeer<TcpStream>, AdnlError> {
+        let transport = proxy.connect(server_address).await?;
+        let client = Self::perform_handshake(transport, server_public).await?;
+        Ok(client)
+    }
+}
+
+impl AdnlPeer<DuplexStream> {
+    /// Create client and server connected to each other in memory with already performed handshake.
+    /// Returns `(client, server)`.
+    pub async fn pair() -> Result<(Self, Self), AdnlError> {
+        let keypair = KeyPair::generate(&mut rand::rngs::OsRng);
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let (client, server) = futures::join!(
+            Self::perform_handshake(client, keypair.public_key.as_bytes()),
+            Self::handle_handshake(server, |_| Some(keypair))
+        );
+        Ok((client?, server?))
+    }
+}
+
+#[cfg(unix)]
+impl AdnlPeer<UnixStream> {
+    /// Connect ADNL client to specified server over Unix domain socket at `path`
+    pub async fn connect_unix(
+        server_public: impl AsRef<[u8]>,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<AdnlPeer<UnixStream>, AdnlError> {
+        let transport = UnixStream::connect(path).await?;
+        let client = Self::perform_handshake(transport, server_public).await?;
+        Ok(client)
+    }
+
+    /// Act as a server: accept next connection on `listener` and handle its handshake.
+    /// Handshake is performed in place, so spawn the rest of processing to not delay other clients.
+    pub async fn accept_unix<F: Fn(&AdnlAddress) -> Option<KeyPair>>(
+        listener: &UnixListener,
+        keypair_selector: F,
+    ) -> Result<AdnlPeer<UnixStream>, AdnlError> {
+        let (transport, _) = listener.accept().await?;
+        Self::handle_handshake(transport, keypair_selector).await
+    }
 }
 
 impl<T: AsyncReadExt + AsyncWriteExt + Unpin> AdnlPeer<T> {
@@ -44,15 +101,7 @@ impl<T: AsyncReadExt + AsyncWriteExt + Unpin> AdnlPeer<T> {
         transport: T,
         remote_public: impl AsRef<[u8]>,
     ) -> Result<Self, AdnlError> {
-        let local_keypair = KeyPair::generate(&mut rand::rngs::OsRng);
-        let remote_public = remote_public
-            .as_ref()
-            .try_into()
-            .ok()
-            .and_then(PublicKey::from_bytes)
-            .ok_or(AdnlError::InvalidPublicKey)?;
-        let handshake = AdnlBuilder::with_random_aes_params(&mut rand::rngs::OsRng)
-            .perform_ecdh(&local_keypair, &remote_public);
+        let handshake = AdnlHandshake::random_for(remote_public)?;
         Self::perform_custom_handshake(transport, &handshake).await
     }
 
@@ -67,6 +116,7 @@ impl<T: AsyncReadExt + AsyncWriteExt + Unpin> AdnlPeer<T> {
             .write_all(&handshake.to_bytes())
             .await
             .map_err(AdnlError::IoError)?;
+        transport.flush().await.map_err(AdnlError::IoError)?;
 
         let mut stream = handshake.make_client_codec().framed(transport);
 
@@ -78,6 +128,8 @@ impl<T: AsyncReadExt + AsyncWriteExt + Unpin> AdnlPeer<T> {
             Ok(Self {
                 stream,
                 connection_info,
+                limiter: None,
+                queue: OutboundQueue::new(OutboundQueue::DEFAULT_MAX_BYTES),
             })
         } else {
             Err(AdnlError::EndOfStream)
@@ -102,6 +154,8 @@ impl<T: AsyncReadExt + AsyncWriteExt + Unpin> AdnlPeer<T> {
         let mut server = Self {
             stream: handshake.make_server_codec().framed(transport),
             connection_info,
+            limiter: None,
+            queue: OutboundQueue::new(OutboundQueue::DEFAULT_MAX_BYTES),
         };
 
         // send empty packet to proof knowledge of AES keys
@@ -109,6 +163,68 @@ impl<T: AsyncReadExt + AsyncWriteExt + Unpin> AdnlPeer<T> {
 
         Ok(server)
     }
+
+    /// Put `packet` into outbound queue with given priority without flushing it, so that
+    /// datagrams of higher priority fed later still go first
+    pub async fn feed_with_priority(
+        &mut self,
+        packet: Bytes,
+        priority: AdnlPriority,
+    ) -> Result<(), AdnlError> {
+        futures::future::poll_fn(|cx| Pin::new(&mut *self).poll_ready(cx)).await?;
+        self.queue.push(priority, packet);
+        Ok(())
+    }
+
+    /// Send `packet` with given priority and flush outbound queue.
+    ///
+    /// Datagrams stay in the queue until the transport has written out the previous one, so
+    /// a datagram overtakes everything of lower priority which is still queued, but not the
+    /// one being written. Flushing waits for the whole queue to be written, so priorities only
+    /// matter for datagrams fed before the flush. The same holds for plain [`SinkExt::send`],
+    /// which queues with [`AdnlPriority::Normal`]: use [`AdnlPeer::feed_with_priority`] for
+    /// large low-priority datagrams and flush once an urgent one is queued.
+    pub async fn send_with_priority(
+        &mut self,
+        packet: Bytes,
+        priority: AdnlPriority,
+    ) -> Result<(), AdnlError> {
+        self.feed_with_priority(packet, priority).await?;
+        self.flush().await
+    }
+}
+
+impl<T> AdnlPeer<T>
+where
+    T: AsyncRead + AsyncWrite,
+{
+    /// Get information about local and remote addresses of this connection
+    pub fn connection_info(&self) -> &AdnlConnectionInfo {
+        &self.connection_info
+    }
+
+    /// Enforce limits of `limiter` on incoming datagrams. Quota is shared with all other
+    /// connections which use the same `limiter` and fall into the same `scope`.
+    pub fn with_rate_limiter(mut self, limiter: &AdnlRateLimiter, scope: RateLimitScope) -> Self {
+        let limiter = PeerLimiter::new(limiter, &scope, self.connection_info.remote_address());
+        if let Some(max_length) = limiter.limits().max_datagram_length() {
+            self.stream.codec_mut().set_max_length(max_length);
+        }
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Set maximum total size in bytes of datagrams waiting in outbound queue, senders wait
+    /// for free space when the queue is full
+    pub fn with_outbound_queue_bytes(mut self, max_bytes: usize) -> Self {
+        self.queue.set_max_bytes(max_bytes);
+        self
+    }
+
+    /// Get number and size of datagrams waiting in outbound queue
+    pub fn outbound_queue_stats(&self) -> AdnlQueueStats {
+        self.queue.stats()
+    }
 }
 
 impl<T> Stream for AdnlPeer<T>
@@ -118,7 +234,55 @@ where
     type Item = Result<Bytes, AdnlError>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.project().stream.poll_next(cx)
+        let mut this = self.project();
+        let Some(limiter) = this.limiter else {
+            return this.stream.poll_next(cx);
+        };
+        if limiter.is_disconnected() {
+            // peer is not served anymore, errors of shutdown do not matter
+            let _ = ready!(this.stream.as_mut().poll_close(cx));
+            return Poll::Ready(None);
+        }
+        if let Some(packet) = ready!(limiter.poll_delayed(cx)) {
+            return Poll::Ready(Some(Ok(packet)));
+        }
+        match this.stream.as_mut().poll_next(cx) {
+            Poll::Ready(Some(Ok(packet))) => match limiter.accept(packet) {
+                Ok(Some(packet)) => Poll::Ready(Some(Ok(packet))),
+                // datagram is throttled, sleep was just set up so this registers the waker
+                Ok(None) => limiter.poll_delayed(cx).map(|x| x.map(Ok)),
+                Err(e) => Poll::Ready(Some(Err(e))),
+            },
+            result => result,
+        }
+    }
+}
+
+impl<T> AdnlPeer<T>
+where
+    T: AsyncRead + AsyncWrite,
+{
+    /// Wait until the transport has written out the previous datagram and hand it the next
+    /// one of the highest priority. Returns `false` if the queue is empty.
+    fn poll_write_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<bool, AdnlError>> {
+        let mut this = self.project();
+        ready!(this.stream.as_mut().poll_flush(cx))?;
+        match this.queue.pop() {
+            Some(packet) => {
+                this.stream.as_mut().start_send(packet)?;
+                Poll::Ready(Ok(true))
+            }
+            None => Poll::Ready(Ok(false)),
+        }
+    }
+
+    /// Write out all queued datagrams one by one, highest priority first
+    fn poll_drain(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), AdnlError>> {
+        while ready!(self.as_mut().poll_write_next(cx))? {}
+        Poll::Ready(Ok(()))
     }
 }
 
@@ -128,19 +292,25 @@ where
 {
     type Error = AdnlError;
 
-    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.project().stream.poll_ready(cx)
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        while self.queue.is_full() {
+            ready!(self.as_mut().poll_write_next(cx))?;
+        }
+        Poll::Ready(Ok(()))
     }
 
     fn start_send(self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
-        self.project().stream.start_send(item)
+        self.project().queue.push(AdnlPriority::Normal, item);
+        Ok(())
     }
 
-    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_drain(cx))?;
         self.project().stream.poll_flush(cx)
     }
 
-    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_drain(cx))?;
         self.project().stream.poll_close(cx)
     }
 }
@@ -0,0 +1,200 @@
+use std::net::IpAddr;
+
+use base64::Engine as _;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::AdnlError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProxyKind {
+    Socks5,
+    HttpConnect,
+}
+
+/// Egress proxy to tunnel ADNL connections through
+#[derive(Debug, Clone)]
+pub struct AdnlProxy {
+    kind: ProxyKind,
+    address: String,
+    credentials: Option<(String, String)>,
+}
+
+impl AdnlProxy {
+    /// SOCKS5 proxy listening on `address` (`host:port`)
+    pub fn socks5(address: impl Into<String>) -> Self {
+        Self {
+            kind: ProxyKind::Socks5,
+            address: address.into(),
+            credentials: None,
+        }
+    }
+
+    /// HTTP proxy supporting `CONNECT` method listening on `address` (`host:port`)
+    pub fn http_connect(address: impl Into<String>) -> Self {
+        Self {
+            kind: ProxyKind::HttpConnect,
+            address: address.into(),
+            credentials: None,
+        }
+    }
+
+    /// Authenticate with username and password, uses RFC 1929 for SOCKS5 and basic auth for HTTP
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Connect to the proxy and ask it to open a tunnel to `target` (`host:port`)
+    pub async fn connect(&self, target: &str) -> Result<TcpStream, AdnlError> {
+        let (host, port) = split_host_port(target)?;
+        let mut stream = TcpStream::connect(&self.address).await?;
+        match self.kind {
+            ProxyKind::Socks5 => self.socks5_handshake(&mut stream, host, port).await?,
+            ProxyKind::HttpConnect => self.http_handshake(&mut stream, host, port).await?,
+        }
+        Ok(stream)
+    }
+
+    async fn socks5_handshake(
+        &self,
+        stream: &mut TcpStream,
+        host: &str,
+        port: u16,
+    ) -> Result<(), AdnlError> {
+        // offer "no auth" and, if we have credentials, "username/password" methods
+        if self.credentials.is_some() {
+            stream.write_all(&[5, 2, 0, 2]).await?;
+        } else {
+            stream.write_all(&[5, 1, 0]).await?;
+        }
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != 5 {
+            return Err(AdnlError::ProxyError("not a SOCKS5 proxy".to_string()));
+        }
+        match (reply[1], &self.credentials) {
+            (0, _) => {}
+            (2, Some((username, password))) => {
+                if username.len() > 255 || password.len() > 255 {
+                    return Err(AdnlError::ProxyError(
+                        "credentials are too long".to_string(),
+                    ));
+                }
+                let mut request = vec![1, username.len() as u8];
+                request.extend_from_slice(username.as_bytes());
+                request.push(password.len() as u8);
+                request.extend_from_slice(password.as_bytes());
+                stream.write_all(&request).await?;
+                stream.read_exact(&mut reply).await?;
+                if reply[1] != 0 {
+                    return Err(AdnlError::ProxyError("authentication failed".to_string()));
+                }
+            }
+            _ => {
+                return Err(AdnlError::ProxyError(
+                    "no acceptable authentication method".to_string(),
+                ))
+            }
+        }
+
+        let mut request = vec![5, 1, 0];
+        match host.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => {
+                request.push(1);
+                request.extend_from_slice(&ip.octets());
+            }
+            Ok(IpAddr::V6(ip)) => {
+                request.push(4);
+                request.extend_from_slice(&ip.octets());
+            }
+            Err(_) => {
+                if host.len() > 255 {
+                    return Err(AdnlError::ProxyError("host name is too long".to_string()));
+                }
+                request.push(3);
+                request.push(host.len() as u8);
+                request.extend_from_slice(host.as_bytes());
+            }
+        }
+        request.extend_from_slice(&port.to_be_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0 {
+            return Err(AdnlError::ProxyError(format!(
+                "SOCKS5 connect failed with code {}",
+                reply[1]
+            )));
+        }
+        // skip bound address
+        let address_length = match reply[3] {
+            1 => 4,
+            4 => 16,
+            3 => stream.read_u8().await? as usize,
+            _ => return Err(AdnlError::ProxyError("invalid SOCKS5 reply".to_string())),
+        };
+        let mut bound = vec![0u8; address_length + 2];
+        stream.read_exact(&mut bound).await?;
+        Ok(())
+    }
+
+    async fn http_handshake(
+        &self,
+        stream: &mut TcpStream,
+        host: &str,
+        port: u16,
+    ) -> Result<(), AdnlError> {
+        let authority = if host.contains(':') {
+            format!("[{}]:{}", host, port)
+        } else {
+            format!("{}:{}", host, port)
+        };
+        let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", authority);
+        if let Some((username, password)) = &self.credentials {
+            let token = base64::engine::general_purpose::STANDARD
+                .encode(format!("{}:{}", username, password));
+            request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        // read response head byte by byte to not consume any tunneled data
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() > 8192 {
+                return Err(AdnlError::ProxyError("response is too long".to_string()));
+            }
+            response.push(stream.read_u8().await?);
+        }
+        let status_line = response
+            .split(|x| *x == b'\n')
+            .next()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+        match status_line.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            _ => Err(AdnlError::ProxyError(format!(
+                "CONNECT failed: {}",
+                status_line.trim_end()
+            ))),
+        }
+    }
+}
+
+/// Split `host:port` or `[ipv6]:port` into parts
+fn split_host_port(address: &str) -> Result<(&str, u16), AdnlError> {
+    let (host, port) = address
+        .rsplit_once(':')
+        .ok_or_else(|| AdnlError::ProxyError(format!("invalid target address {}", address)))?;
+    let port = port
+        .parse()
+        .map_err(|_| AdnlError::ProxyError(format!("invalid target port {}", port)))?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    Ok((host, port))
+}
@@ -0,0 +1,87 @@
+use std::collections::VecDeque;
+
+use tokio_util::bytes::Bytes;
+
+/// Priority class of outgoing datagram, datagrams of higher classes are sent first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AdnlPriority {
+    /// Keepalives and other control messages
+    Control,
+    /// Regular traffic
+    #[default]
+    Normal,
+    /// Large responses which may wait
+    Bulk,
+}
+
+/// Snapshot of outbound queue depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AdnlQueueStats {
+    pub control: usize,
+    pub normal: usize,
+    pub bulk: usize,
+    /// Total size of queued datagrams in bytes
+    pub bytes: usize,
+}
+
+impl AdnlQueueStats {
+    /// Total number of queued datagrams
+    pub fn len(&self) -> usize {
+        self.control + self.normal + self.bulk
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Queue of datagrams which are not yet handed to the codec, bounded by their total size
+pub(crate) struct OutboundQueue {
+    queues: [VecDeque<Bytes>; 3],
+    max_bytes: usize,
+    bytes: usize,
+}
+
+impl OutboundQueue {
+    pub(crate) const DEFAULT_MAX_BYTES: usize = 1 << 20;
+
+    pub(crate) fn new(max_bytes: usize) -> Self {
+        Self {
+            queues: Default::default(),
+            max_bytes: max_bytes.max(1),
+            bytes: 0,
+        }
+    }
+
+    pub(crate) fn set_max_bytes(&mut self, max_bytes: usize) {
+        self.max_bytes = max_bytes.max(1);
+    }
+
+    /// Queue accepts a datagram as long as it is below its limit, so a single datagram
+    /// may take it over the limit
+    pub(crate) fn is_full(&self) -> bool {
+        self.bytes >= self.max_bytes
+    }
+
+    pub(crate) fn push(&mut self, priority: AdnlPriority, packet: Bytes) {
+        self.bytes += packet.len();
+        self.queues[priority as usize].push_back(packet);
+    }
+
+    /// Take the oldest datagram of the highest priority class
+    pub(crate) fn pop(&mut self) -> Option<Bytes> {
+        let packet = self.queues.iter_mut().find_map(VecDeque::pop_front)?;
+        self.bytes -= packet.len();
+        Some(packet)
+    }
+
+    pub(crate) fn stats(&self) -> AdnlQueueStats {
+        let [control, normal, bulk] = self.queues.each_ref().map(VecDeque::len);
+        AdnlQueueStats {
+            control,
+            normal,
+            bulk,
+            bytes: self.bytes,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures::{Sink, Stream};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_util::bytes::{Buf, Bytes};
+
+use crate::{AdnlError, AdnlPeer};
+
+/// Adapter of WebSocket stream carrying raw ADNL bytes in binary messages into
+/// [`AsyncRead`] + [`AsyncWrite`] transport suitable for [`AdnlPeer`]
+pub struct AdnlWsStream<S> {
+    inner: WebSocketStream<S>,
+    read_buffer: Bytes,
+}
+
+impl<S> AdnlWsStream<S> {
+    pub fn new(inner: WebSocketStream<S>) -> Self {
+        Self {
+            inner,
+            read_buffer: Bytes::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> WebSocketStream<S> {
+        self.inner
+    }
+}
+
+fn to_io_error(error: tungstenite::Error) -> io::Error {
+    match error {
+        tungstenite::Error::Io(e) => e,
+        tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed => {
+            io::ErrorKind::BrokenPipe.into()
+        }
+        e => io::Error::other(e),
+    }
+}
+
+impl<S> AsyncRead for AdnlWsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        while self.read_buffer.is_empty() {
+            match ready!(Pin::new(&mut self.inner).poll_next(cx)) {
+                Some(Ok(Message::Binary(data))) => self.read_buffer = data.into(),
+                // control frames are answered by tungstenite itself
+                Some(Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_))) => {}
+                Some(Ok(Message::Text(_))) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "text messages are not supported",
+                    )))
+                }
+                Some(Ok(Message::Close(_))) | None => return Poll::Ready(Ok(())),
+                Some(Err(e)) => return Poll::Ready(Err(to_io_error(e))),
+            }
+        }
+        let length = self.read_buffer.len().min(buf.remaining());
+        buf.put_slice(&self.read_buffer[..length]);
+        self.read_buffer.advance(length);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S> AsyncWrite for AdnlWsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut inner = Pin::new(&mut self.inner);
+        ready!(inner.as_mut().poll_ready(cx)).map_err(to_io_error)?;
+        inner
+            .start_send(Message::Binary(buf.to_vec()))
+            .map_err(to_io_error)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner)
+            .poll_flush(cx)
+            .map_err(to_io_error)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner)
+            .poll_close(cx)
+            .map_err(to_io_error)
+    }
+}
+
+impl AdnlPeer<AdnlWsStream<MaybeTlsStream<TcpStream>>> {
+    /// Connect ADNL client to specified server through WebSocket bridge at `url`
+    pub async fn connect_ws(server_public: impl AsRef<[u8]>, url: &str) -> Result<Self, AdnlError> {
+        let (stream, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(to_io_error)?;
+        Self::perform_handshake(AdnlWsStream::new(stream), server_public).await
+    }
+}
+
+/// WebSocket server which forwards every accepted client to ADNL TCP backend
+pub struct AdnlWsBridge {
+    listener: TcpListener,
+    backend: SocketAddr,
+}
+
+impl AdnlWsBridge {
+    /// Listen for WebSocket clients on `address`, forward them to `backend`
+    pub async fn bind<A: ToSocketAddrs>(
+        address: A,
+        backend: SocketAddr,
+    ) -> Result<Self, AdnlError> {
+        Ok(Self {
+            listener: TcpListener::bind(address).await?,
+            backend,
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, AdnlError> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Accept clients until listener fails, each client is served in a separate task
+    pub async fn run(self) -> Result<(), AdnlError> {
+        loop {
+            let (socket, remote) = self.listener.accept().await?;
+            let backend = self.backend;
+            tokio::spawn(async move {
+                if let Err(e) = Self::forward(socket, backend).await {
+                    log::debug!("websocket bridge client {} failed: {}", remote, e);
+                }
+            });
+        }
+    }
+
+    async fn forward(socket: TcpStream, backend: SocketAddr) -> io::Result<()> {
+        let client = tokio_tungstenite::accept_async(socket)
+            .await
+            .map_err(to_io_error)?;
+        let mut client = AdnlWsStream::new(client);
+        let mut backend = TcpStream::connect(backend).await?;
+        tokio::io::copy_bidirectional(&mut client, &mut backend).await?;
+        Ok(())
+    }
+}
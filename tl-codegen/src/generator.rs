@@ -0,0 +1,345 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use crate::parser::{Combinator, CombinatorKind, TypeExpr};
+use crate::CodegenError;
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+const DERIVE: &str = "#[derive(Debug, Clone, PartialEq, TlWrite, TlRead)]";
+
+fn escape(ident: String) -> String {
+    if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else {
+        ident
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lower {
+            result.push('_');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        result.extend(c.to_lowercase());
+    }
+    escape(result)
+}
+
+fn pascal_case(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Split TL name into Rust module path and item name
+fn split_name(name: &str) -> (Vec<String>, &str) {
+    match name.rsplit_once('.') {
+        Some((namespace, name)) => (namespace.split('.').map(snake_case).collect(), name),
+        None => (Vec::new(), name),
+    }
+}
+
+/// Path to item `name` in module `to`, relative to module `from`
+fn relative_path(from: &[String], to: &[String], name: &str) -> String {
+    let (mut path, to) = match to.strip_prefix(from) {
+        Some(nested) => (String::new(), nested),
+        None => ("super::".repeat(from.len()), to),
+    };
+    for module in to {
+        path.push_str(module);
+        path.push_str("::");
+    }
+    path.push_str(name);
+    path
+}
+
+enum Item<'a> {
+    Struct(&'a Combinator),
+    Enum {
+        ty: &'a str,
+        name: String,
+        constructors: Vec<&'a Combinator>,
+    },
+}
+
+struct Registry<'a> {
+    combinators: Vec<&'a Combinator>,
+    constructors: HashMap<&'a str, &'a Combinator>,
+    types: Vec<(&'a str, Vec<&'a Combinator>)>,
+    enums: HashMap<&'a str, (Vec<String>, String)>,
+}
+
+impl<'a> Registry<'a> {
+    fn new(combinators: &'a [Combinator]) -> Result<Self, CodegenError> {
+        let mut registry = Self {
+            combinators: Vec::new(),
+            constructors: HashMap::new(),
+            types: Vec::new(),
+            enums: HashMap::new(),
+        };
+        let mut by_name: HashMap<&str, &Combinator> = HashMap::new();
+        for combinator in combinators {
+            match by_name.get(combinator.name.as_str()) {
+                // the same declaration may come from several schemas
+                Some(existing) if *existing == combinator => continue,
+                Some(_) => return Err(CodegenError::Conflict(combinator.name.clone())),
+                None => by_name.insert(&combinator.name, combinator),
+            };
+            registry.combinators.push(combinator);
+            if combinator.kind == CombinatorKind::Function {
+                continue;
+            }
+            registry.constructors.insert(&combinator.name, combinator);
+            let TypeExpr::Named(ty) = &combinator.result else {
+                return Err(CodegenError::UnknownType(combinator.result.to_string()));
+            };
+            match registry.types.iter_mut().find(|(name, _)| name == ty) {
+                Some((_, constructors)) => constructors.push(combinator),
+                None => registry.types.push((ty, vec![combinator])),
+            }
+        }
+
+        let mut struct_names = HashMap::new();
+        for combinator in &registry.combinators {
+            let (module, name) = split_name(&combinator.name);
+            if let Some(other) = struct_names.insert((module, pascal_case(name)), &combinator.name)
+            {
+                return Err(CodegenError::Conflict(format!(
+                    "{other} and {}",
+                    combinator.name
+                )));
+            }
+        }
+        for (ty, constructors) in &registry.types {
+            if constructors.len() < 2 {
+                continue;
+            }
+            let (module, name) = split_name(ty);
+            let mut name = pascal_case(name);
+            if struct_names.contains_key(&(module.clone(), name.clone())) {
+                name.push_str("Boxed");
+            }
+            registry.enums.insert(ty, (module, name));
+        }
+        Ok(registry)
+    }
+
+    /// Rust type of TL type `ty` used in module `from`
+    fn rust_type(&self, ty: &TypeExpr, from: &[String]) -> Result<String, CodegenError> {
+        let name = match ty {
+            TypeExpr::Nat => return Ok("u32".to_owned()),
+            TypeExpr::Vector(ty) => return Ok(format!("Vec<{}>", self.rust_type(ty, from)?)),
+            TypeExpr::Conditional { ty, .. } if **ty == TypeExpr::Named("true".to_owned()) => {
+                return Ok("bool".to_owned())
+            }
+            TypeExpr::Conditional { ty, .. } => {
+                return Ok(format!("Option<{}>", self.rust_type(ty, from)?))
+            }
+            TypeExpr::Named(name) => name.as_str(),
+        };
+        let builtin = match name {
+            "int" => "i32",
+            "long" => "i64",
+            "double" => "f64",
+            "int128" => "[u8; 16]",
+            "int256" => "[u8; 32]",
+            "bytes" => "Vec<u8>",
+            "string" => "String",
+            "Bool" => "bool",
+            _ => "",
+        };
+        if !builtin.is_empty() {
+            return Ok(builtin.to_owned());
+        }
+
+        let (_, short_name) = split_name(name);
+        if short_name.starts_with(char::is_lowercase) {
+            // bare constructor
+            let constructor = self
+                .constructors
+                .get(name)
+                .ok_or_else(|| CodegenError::UnknownType(name.to_owned()))?;
+            return Ok(self.struct_path(constructor, from));
+        }
+        if let Some((module, name)) = self.enums.get(name) {
+            return Ok(relative_path(from, module, name));
+        }
+        match self.types.iter().find(|(ty, _)| *ty == name) {
+            Some((_, constructors)) => Ok(format!(
+                "Boxed<{}>",
+                self.struct_path(constructors[0], from)
+            )),
+            None => Err(CodegenError::UnknownType(name.to_owned())),
+        }
+    }
+
+    fn struct_path(&self, combinator: &Combinator, from: &[String]) -> String {
+        let (module, name) = split_name(&combinator.name);
+        relative_path(from, &module, &pascal_case(name))
+    }
+
+    fn modules(&self) -> BTreeMap<Vec<String>, Vec<Item<'a>>> {
+        let mut modules: BTreeMap<Vec<String>, Vec<Item>> = BTreeMap::new();
+        for combinator in &self.combinators {
+            let (module, _) = split_name(&combinator.name);
+            modules
+                .entry(module)
+                .or_default()
+                .push(Item::Struct(combinator));
+        }
+        for (ty, constructors) in &self.types {
+            if let Some((module, name)) = self.enums.get(ty) {
+                modules.entry(module.clone()).or_default().push(Item::Enum {
+                    ty,
+                    name: name.clone(),
+                    constructors: constructors.clone(),
+                });
+            }
+        }
+        // make sure that every parent module is declared
+        for module in modules.keys().cloned().collect::<Vec<_>>() {
+            for depth in 0..module.len() {
+                modules.entry(module[..depth].to_vec()).or_default();
+            }
+        }
+        modules
+    }
+}
+
+/// Generate Rust module source with types of all `combinators`
+pub fn generate(combinators: &[Combinator]) -> Result<String, CodegenError> {
+    let registry = Registry::new(combinators)?;
+    let modules = registry.modules();
+    let mut output =
+        String::from("// Generated by adnl-tl-codegen from TL schemas, do not edit.\n\n");
+    write_module(&registry, &modules, &[], &mut output)?;
+    Ok(output)
+}
+
+fn write_module(
+    registry: &Registry,
+    modules: &BTreeMap<Vec<String>, Vec<Item>>,
+    path: &[String],
+    output: &mut String,
+) -> Result<(), CodegenError> {
+    let indent = "    ".repeat(path.len());
+    let mut first = true;
+    let mut separate = |output: &mut String| {
+        if !first {
+            output.push('\n');
+        }
+        first = false;
+    };
+
+    if !modules[path].is_empty() {
+        separate(output);
+        writeln!(output, "{indent}use ::adnl::tl::__private::*;").unwrap();
+    }
+    for item in &modules[path] {
+        separate(output);
+        match item {
+            Item::Struct(combinator) => write_struct(registry, combinator, path, &indent, output)?,
+            Item::Enum {
+                ty,
+                name,
+                constructors,
+            } => {
+                writeln!(output, "{indent}/// `{ty}`").unwrap();
+                writeln!(output, "{indent}{DERIVE}").unwrap();
+                writeln!(output, "{indent}pub enum {name} {{").unwrap();
+                for constructor in constructors {
+                    let variant = constructor
+                        .name
+                        .strip_prefix(ty.rsplit_once('.').map_or("", |(namespace, _)| namespace))
+                        .unwrap_or(&constructor.name)
+                        .split('.')
+                        .map(pascal_case)
+                        .collect::<String>();
+                    let ty = registry.struct_path(constructor, path);
+                    writeln!(output, "{indent}    {variant}({ty}),").unwrap();
+                }
+                writeln!(output, "{indent}}}").unwrap();
+            }
+        }
+    }
+
+    for (module, _) in modules.range(path.to_vec()..) {
+        if module.len() != path.len() + 1 || !module.starts_with(path) {
+            continue;
+        }
+        separate(output);
+        writeln!(output, "{indent}pub mod {} {{", module[path.len()]).unwrap();
+        write_module(registry, modules, module, output)?;
+        writeln!(output, "{indent}}}").unwrap();
+    }
+    Ok(())
+}
+
+fn write_struct(
+    registry: &Registry,
+    combinator: &Combinator,
+    path: &[String],
+    indent: &str,
+    output: &mut String,
+) -> Result<(), CodegenError> {
+    let (_, name) = split_name(&combinator.name);
+    let name = pascal_case(name);
+    let boxed = match combinator.kind {
+        CombinatorKind::Type => "",
+        CombinatorKind::Function => "boxed, ",
+    };
+    writeln!(output, "{indent}/// `{}`", combinator.normalized()).unwrap();
+    writeln!(output, "{indent}{DERIVE}").unwrap();
+    writeln!(output, "{indent}#[tl({boxed}id = {:#010x})]", combinator.id).unwrap();
+    if combinator.fields.is_empty() {
+        writeln!(output, "{indent}pub struct {name};").unwrap();
+    } else {
+        writeln!(output, "{indent}pub struct {name} {{").unwrap();
+        for field in &combinator.fields {
+            let is_flags = combinator.fields.iter().any(|other| {
+                matches!(&other.ty, TypeExpr::Conditional { flags, .. } if *flags == field.name)
+            });
+            if is_flags {
+                writeln!(output, "{indent}    #[tl(flags)]").unwrap();
+            }
+            if let TypeExpr::Conditional { flags, bit, .. } = &field.ty {
+                writeln!(output, "{indent}    #[tl(flags_bit = \"{flags}.{bit}\")]").unwrap();
+            }
+            writeln!(
+                output,
+                "{indent}    pub {}: {},",
+                escape(field.name.clone()),
+                registry.rust_type(&field.ty, path)?
+            )
+            .unwrap();
+        }
+        writeln!(output, "{indent}}}").unwrap();
+    }
+
+    if combinator.kind == CombinatorKind::Function {
+        // query prefixes and wrappers do not have typed answer
+        if let TypeExpr::Named(name) = &combinator.result {
+            if name == "Object" || name == "True" {
+                return Ok(());
+            }
+        }
+        let reply = registry.rust_type(&combinator.result, path)?;
+        writeln!(output).unwrap();
+        writeln!(output, "{indent}impl TlFunction for {name} {{").unwrap();
+        writeln!(output, "{indent}    type Reply = {reply};").unwrap();
+        writeln!(output, "{indent}}}").unwrap();
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Generator of Rust types from TL schemas such as `lite_api.tl` and `ton_api.tl`.
+//!
+//! Every constructor and function becomes a struct deriving `adnl::tl::TlWrite` and
+//! `adnl::tl::TlRead`, boxed types with several constructors become enums, functions
+//! implement `adnl::tl::TlFunction` with the type of their answer. Namespaces are mapped to
+//! nested modules, e.g. `liteServer.getTime` is generated as `lite_server::GetTime`.
+//!
+//! Only a part of a large schema can be generated with [`select_namespaces`], which keeps
+//! given namespaces and declarations they refer to.
+//!
+//! Use it from `build.rs`:
+//!
+//! ```no_run
+//! adnl_tl_codegen::compile(&["tl/my_api.tl"], "my_api.rs").unwrap();
+//! ```
+//!
+//! and include the result into a module:
+//!
+//! ```ignore
+//! mod my_api {
+//!     include!(concat!(env!("OUT_DIR"), "/my_api.rs"));
+//! }
+//! ```
+
+mod generator;
+mod parser;
+mod select;
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+pub use generator::generate;
+pub use parser::{parse, Combinator, CombinatorKind, Field, TypeExpr};
+pub use select::select_namespaces;
+
+#[derive(Debug, Error)]
+pub enum CodegenError {
+    #[error("IO error")]
+    IoError(#[from] std::io::Error),
+    #[error("Schema parse error: {0}")]
+    Parse(String),
+    #[error("Unknown TL type `{0}`")]
+    UnknownType(String),
+    #[error("Conflicting TL declarations: {0}")]
+    Conflict(String),
+}
+
+fn parse_files<P: AsRef<Path>>(schemas: &[P]) -> Result<Vec<Combinator>, CodegenError> {
+    let mut combinators = Vec::new();
+    for schema in schemas {
+        combinators.extend(parse(&std::fs::read_to_string(schema)?)?);
+    }
+    Ok(combinators)
+}
+
+/// Parse all `schemas` and generate Rust source with their types
+pub fn generate_files<P: AsRef<Path>>(schemas: &[P]) -> Result<String, CodegenError> {
+    generate(&parse_files(schemas)?)
+}
+
+/// Parse all `schemas` and generate Rust source with types of `namespaces` and types
+/// they refer to
+pub fn generate_namespaces<P: AsRef<Path>>(
+    schemas: &[P],
+    namespaces: &[&str],
+) -> Result<String, CodegenError> {
+    generate(&select_namespaces(&parse_files(schemas)?, namespaces))
+}
+
+/// Helper for `build.rs`: generate types of `schemas` into `$OUT_DIR/<output>`
+pub fn compile<P: AsRef<Path>>(schemas: &[P], output: &str) -> Result<(), CodegenError> {
+    write_output(schemas, output, generate_files(schemas)?)
+}
+
+/// Helper for `build.rs`: generate types of `namespaces` of `schemas` into `$OUT_DIR/<output>`
+pub fn compile_namespaces<P: AsRef<Path>>(
+    schemas: &[P],
+    namespaces: &[&str],
+    output: &str,
+) -> Result<(), CodegenError> {
+    write_output(schemas, output, generate_namespaces(schemas, namespaces)?)
+}
+
+fn write_output<P: AsRef<Path>>(
+    schemas: &[P],
+    output: &str,
+    source: String,
+) -> Result<(), CodegenError> {
+    for schema in schemas {
+        println!("cargo:rerun-if-changed={}", schema.as_ref().display());
+    }
+    let out_dir = std::env::var_os("OUT_DIR")
+        .map(PathBuf::from)
+        .ok_or_else(|| std::io::Error::other("OUT_DIR is not set, not running from build.rs?"))?;
+    std::fs::write(out_dir.join(output), source)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
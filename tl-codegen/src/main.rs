@@ -0,0 +1,40 @@
+//! Generate Rust types from TL schemas:
+//! `adnl-tl-codegen [--namespaces <ns>,...] <schema.tl>... <output.rs>`
+
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: adnl-tl-codegen [--namespaces <ns>,...] <schema.tl>... <output.rs>";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let namespaces = match args.iter().position(|arg| arg == "--namespaces") {
+        Some(index) if index + 1 < args.len() => {
+            let namespaces = args.remove(index + 1);
+            args.remove(index);
+            Some(namespaces)
+        }
+        Some(_) => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+        None => None,
+    };
+    let Some(output) = args.pop().filter(|_| !args.is_empty()) else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let source = match &namespaces {
+        Some(namespaces) => {
+            let namespaces = namespaces.split(',').collect::<Vec<_>>();
+            adnl_tl_codegen::generate_namespaces(&args, &namespaces)
+        }
+        None => adnl_tl_codegen::generate_files(&args),
+    };
+    match source.and_then(|source| Ok(std::fs::write(output, source)?)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
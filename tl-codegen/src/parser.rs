@@ -0,0 +1,255 @@
+use core::fmt;
+
+use crc::{Crc, CRC_32_ISO_HDLC};
+
+use crate::CodegenError;
+
+static CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+/// Built-in types of TL, declared in schemas with special syntax or mapped to Rust types directly
+const BUILTINS: &[&str] = &["bytes", "true", "boolTrue", "boolFalse"];
+
+/// Whether combinator describes data type or function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombinatorKind {
+    Type,
+    Function,
+}
+
+/// Type of field or result of combinator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeExpr {
+    /// `#`
+    Nat,
+    /// Built-in type, bare constructor (`liteServer.accountId`) or boxed type (`liteServer.AccountId`)
+    Named(String),
+    /// `vector t`
+    Vector(Box<TypeExpr>),
+    /// `flags.N?t`
+    Conditional {
+        flags: String,
+        bit: u8,
+        ty: Box<TypeExpr>,
+    },
+}
+
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nat => f.write_str("#"),
+            Self::Named(name) => f.write_str(name),
+            Self::Vector(ty) => write!(f, "vector {ty}"),
+            Self::Conditional { flags, bit, ty } => write!(f, "{flags}.{bit}?{ty}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub ty: TypeExpr,
+}
+
+/// Single constructor or function declaration of TL schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combinator {
+    pub name: String,
+    pub id: u32,
+    pub kind: CombinatorKind,
+    pub fields: Vec<Field>,
+    pub result: TypeExpr,
+}
+
+impl Combinator {
+    /// Declaration in the normalized form used to compute constructor ids
+    pub fn normalized(&self) -> String {
+        let mut result = self.name.clone();
+        for field in &self.fields {
+            result.push_str(&format!(" {}:{}", field.name, field.ty));
+        }
+        result.push_str(&format!(" = {}", self.result));
+        result
+    }
+}
+
+/// Parse TL schema, skipping declarations of built-in types
+pub fn parse(source: &str) -> Result<Vec<Combinator>, CodegenError> {
+    let mut text = String::new();
+    for line in source.lines() {
+        text.push_str(line.split("//").next().unwrap_or_default());
+        text.push('\n');
+    }
+    let text = text
+        .replace("---functions---", ";---functions---;")
+        .replace("---types---", ";---types---;");
+
+    let mut kind = CombinatorKind::Type;
+    let mut result = Vec::new();
+    for declaration in text.split(';').map(str::trim) {
+        match declaration {
+            "" => {}
+            "---functions---" => kind = CombinatorKind::Function,
+            "---types---" => kind = CombinatorKind::Type,
+            declaration if is_builtin(declaration) => {}
+            declaration => result.push(parse_combinator(declaration, kind)?),
+        }
+    }
+    Ok(result)
+}
+
+fn is_builtin(declaration: &str) -> bool {
+    let name = declaration.split_whitespace().next().unwrap_or_default();
+    BUILTINS.contains(&name)
+        || declaration.contains(" ? ")
+        || declaration.contains('{')
+        || declaration.contains('[')
+}
+
+fn tokenize(declaration: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for c in declaration.chars() {
+        if c.is_whitespace() || "()<>=".contains(c) {
+            if !word.is_empty() {
+                tokens.push(core::mem::take(&mut word));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+struct Tokens<'a> {
+    declaration: &'a str,
+    tokens: core::iter::Peekable<std::vec::IntoIter<String>>,
+}
+
+impl Tokens<'_> {
+    fn error(&self, message: &str) -> CodegenError {
+        CodegenError::Parse(format!("{message} in `{}`", self.declaration))
+    }
+
+    fn next(&mut self) -> Result<String, CodegenError> {
+        self.tokens
+            .next()
+            .ok_or_else(|| self.error("unexpected end of declaration"))
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), CodegenError> {
+        if self.next()? != expected {
+            return Err(self.error(&format!("expected `{expected}`")));
+        }
+        Ok(())
+    }
+
+    fn parse_type(&mut self) -> Result<TypeExpr, CodegenError> {
+        let token = self.next()?;
+        self.parse_type_from(&token)
+    }
+
+    /// Parse type starting with already consumed `token`
+    fn parse_type_from(&mut self, token: &str) -> Result<TypeExpr, CodegenError> {
+        match token {
+            "(" => {
+                let ty = self.parse_type()?;
+                self.expect(")")?;
+                Ok(ty)
+            }
+            "#" => Ok(TypeExpr::Nat),
+            "vector" | "Vector" => {
+                let ty = if self.tokens.peek().map(String::as_str) == Some("<") {
+                    self.next()?;
+                    let ty = self.parse_type()?;
+                    self.expect(">")?;
+                    ty
+                } else {
+                    self.parse_type()?
+                };
+                Ok(TypeExpr::Vector(Box::new(ty)))
+            }
+            token if token.contains('?') => {
+                let (condition, ty) = token.split_once('?').unwrap();
+                let (flags, bit) = condition
+                    .split_once('.')
+                    .and_then(|(flags, bit)| Some((flags, bit.parse::<u8>().ok()?)))
+                    .filter(|(_, bit)| *bit < 32)
+                    .ok_or_else(|| self.error(&format!("invalid condition `{condition}`")))?;
+                let ty = if ty.is_empty() {
+                    self.parse_type()?
+                } else {
+                    self.parse_type_from(ty)?
+                };
+                Ok(TypeExpr::Conditional {
+                    flags: flags.to_owned(),
+                    bit,
+                    ty: Box::new(ty),
+                })
+            }
+            token
+                if token
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '.' || c == '_') =>
+            {
+                Ok(TypeExpr::Named(token.to_owned()))
+            }
+            token => Err(self.error(&format!("unexpected `{token}`"))),
+        }
+    }
+}
+
+fn parse_combinator(declaration: &str, kind: CombinatorKind) -> Result<Combinator, CodegenError> {
+    let mut tokens = Tokens {
+        declaration,
+        tokens: tokenize(declaration).into_iter().peekable(),
+    };
+    let name = tokens.next()?;
+    let (name, explicit_id) = match name.split_once('#') {
+        Some((name, id)) => {
+            let id = u32::from_str_radix(id, 16)
+                .map_err(|_| tokens.error(&format!("invalid constructor id `{id}`")))?;
+            (name.to_owned(), Some(id))
+        }
+        None => (name, None),
+    };
+
+    let mut fields = Vec::new();
+    loop {
+        let token = tokens.next()?;
+        if token == "=" {
+            break;
+        }
+        let (field_name, ty) = token
+            .split_once(':')
+            .ok_or_else(|| tokens.error(&format!("expected field, got `{token}`")))?;
+        let ty = if ty.is_empty() {
+            tokens.parse_type()?
+        } else {
+            tokens.parse_type_from(ty)?
+        };
+        fields.push(Field {
+            name: field_name.to_owned(),
+            ty,
+        });
+    }
+    let result = tokens.parse_type()?;
+    if tokens.tokens.peek().is_some() {
+        return Err(tokens.error("unexpected tokens after result type"));
+    }
+
+    let mut combinator = Combinator {
+        name,
+        id: 0,
+        kind,
+        fields,
+        result,
+    };
+    combinator.id = explicit_id.unwrap_or_else(|| CRC.checksum(combinator.normalized().as_bytes()));
+    Ok(combinator)
+}
@@ -0,0 +1,60 @@
+use std::collections::HashSet;
+
+use crate::parser::{Combinator, CombinatorKind, TypeExpr};
+
+/// Namespace of combinator name, e.g. `liteServer` for `liteServer.getTime`
+/// and `adnl` for `adnl.message.query`
+fn namespace(name: &str) -> &str {
+    name.split_once('.').map_or("", |(namespace, _)| namespace)
+}
+
+fn named_types<'a>(ty: &'a TypeExpr, result: &mut Vec<&'a str>) {
+    match ty {
+        TypeExpr::Nat => {}
+        TypeExpr::Named(name) => result.push(name),
+        TypeExpr::Vector(ty) | TypeExpr::Conditional { ty, .. } => named_types(ty, result),
+    }
+}
+
+/// Types which `combinator` refers to
+fn references<'a>(combinator: &'a Combinator, result: &mut Vec<&'a str>) {
+    for field in &combinator.fields {
+        named_types(&field.ty, result);
+    }
+    named_types(&combinator.result, result);
+}
+
+/// Keep combinators of `namespaces` together with declarations of other namespaces they
+/// refer to, so that a part of a large schema such as `ton_api.tl` can be generated without
+/// editing the schema. Order of declarations is preserved.
+pub fn select_namespaces(combinators: &[Combinator], namespaces: &[&str]) -> Vec<Combinator> {
+    let mut selected = HashSet::new();
+    let mut pending = Vec::new();
+    for combinator in combinators {
+        if namespaces.contains(&namespace(&combinator.name)) {
+            selected.insert(combinator.name.as_str());
+            references(combinator, &mut pending);
+        }
+    }
+    let mut visited = HashSet::new();
+    while let Some(ty) = pending.pop() {
+        if !visited.insert(ty) {
+            continue;
+        }
+        // bare constructor or every constructor of boxed type
+        for combinator in combinators.iter().filter(|combinator| {
+            combinator.name == ty
+                || (combinator.kind == CombinatorKind::Type
+                    && matches!(&combinator.result, TypeExpr::Named(name) if name == ty))
+        }) {
+            if selected.insert(combinator.name.as_str()) {
+                references(combinator, &mut pending);
+            }
+        }
+    }
+    combinators
+        .iter()
+        .filter(|combinator| selected.contains(combinator.name.as_str()))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,133 @@
+use super::*;
+
+const SCHEMAS: &[&str] = &["../schemas/lite_api.tl", "../schemas/ton_api.tl"];
+
+/// Namespaces of the bundled `adnl::tl::ton` types
+const NAMESPACES: &[&str] = &[
+    "pk",
+    "pub",
+    "adnl",
+    "tcp",
+    "dht",
+    "overlay",
+    "rldp",
+    "fec",
+    "ton",
+    "liteServer",
+];
+
+fn find_id(combinators: &[Combinator], name: &str) -> u32 {
+    combinators
+        .iter()
+        .find(|combinator| combinator.name == name)
+        .unwrap_or_else(|| panic!("{name} is not declared"))
+        .id
+}
+
+#[test]
+fn constructor_ids() {
+    let mut combinators = Vec::new();
+    for schema in SCHEMAS {
+        combinators.extend(parse(&std::fs::read_to_string(schema).unwrap()).unwrap());
+    }
+    for (name, id) in [
+        ("adnl.message.query", 0xb48bf97a),
+        ("adnl.message.answer", 0x0fac8416),
+        ("liteServer.query", 0x798c06df),
+        ("liteServer.getTime", 0x16ad5a34),
+        ("liteServer.currentTime", 0xe953000d),
+        ("liteServer.getMasterchainInfo", 0x89b5e62e),
+        ("liteServer.masterchainInfo", 0x85832881),
+        ("liteServer.error", 0xbba9e148),
+        ("liteServer.waitMasterchainSeqno", 0xbaeab892),
+        ("liteServer.getAccountState", 0x6b890e25),
+        ("liteServer.accountState", 0x7079c751),
+        ("liteServer.runSmcMethod", 0x5cc65dd2),
+        ("liteServer.runMethodResult", 0xa39a616b),
+        ("liteServer.sendMessage", 0x690ad482),
+        ("liteServer.getTransactions", 0x1c40e7a1),
+        ("liteServer.transactionList", 0x6f26c60b),
+        ("liteServer.getConfigParams", 0x2a111c19),
+        ("liteServer.getBlockProof", 0x8aea9c44),
+        ("liteServer.transactionId", 0xb12f65af),
+        ("liteServer.getValidatorStats", 0x091a58bc),
+        ("tonNode.blockIdExt", 0x6752eb78),
+        ("tonNode.zeroStateIdExt", 0x1d7235ae),
+        ("pub.ed25519", 0x4813b4c6),
+        ("tcp.ping", 0x4d082b9a),
+        ("tcp.pong", 0xdc69fb03),
+        ("adnl.address.udp", 0x670da6e7),
+        ("adnl.packetContents", 0xd142cd89),
+        ("dht.node", 0x84533248),
+        ("overlay.node", 0xb86b8a83),
+        ("rldp.message", 0x7d1bcd1e),
+        ("rldp.query", 0x8a794d69),
+        ("rldp.answer", 0xa3fc5c03),
+    ] {
+        assert_eq!(find_id(&combinators, name), id, "{name}");
+    }
+}
+
+#[test]
+fn parse_syntax() {
+    let combinators = parse(
+        "int ? = Int;\n\
+         vector {t:Type} # [ t ] = Vector t;\n\
+         test.multiline a:int // comment\n  b:vector<long> c:a.0?(vector test.Item) = test.Type;\n\
+         ---functions---\n\
+         test.get#0000abcd = test.Type;",
+    )
+    .unwrap();
+    assert_eq!(combinators.len(), 2);
+    assert_eq!(
+        combinators[0].normalized(),
+        "test.multiline a:int b:vector long c:a.0?vector test.Item = test.Type"
+    );
+    assert_eq!(combinators[1].kind, CombinatorKind::Function);
+    assert_eq!(combinators[1].id, 0xabcd);
+    assert!(parse("test.broken a = test.Type;").is_err());
+}
+
+#[test]
+fn select_namespaces_with_references() {
+    let combinators = parse(
+        "test.item a:int = test.Item;\n\
+         test.other = test.Item;\n\
+         test.unused = test.Unused;\n\
+         engine.secret key:secureString = engine.Secret;\n\
+         main.value item:test.Item bare:test.unused = main.Value;\n\
+         ---functions---\n\
+         main.get = main.Value;",
+    )
+    .unwrap();
+    let names = |combinators: &[Combinator]| {
+        combinators
+            .iter()
+            .map(|combinator| combinator.name.clone())
+            .collect::<Vec<_>>()
+    };
+    let selected = select_namespaces(&combinators, &["main"]);
+    assert_eq!(
+        names(&selected),
+        [
+            "test.item",
+            "test.other",
+            "test.unused",
+            "main.value",
+            "main.get"
+        ]
+    );
+    // type of `engine` namespace is unknown, but it is not generated
+    assert!(generate(&combinators).is_err());
+    assert!(generate(&selected).is_ok());
+}
+
+#[test]
+fn bindings_are_up_to_date() {
+    let generated = generate_namespaces(SCHEMAS, NAMESPACES).unwrap();
+    assert!(
+        generated == include_str!("../../src/tl/ton.rs"),
+        "src/tl/ton.rs is outdated, run `cargo run -p adnl-tl-codegen -- --namespaces {} schemas/lite_api.tl schemas/ton_api.tl src/tl/ton.rs`",
+        NAMESPACES.join(",")
+    );
+}
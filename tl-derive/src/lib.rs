@@ -0,0 +1,407 @@
+//! Derive macros for `adnl::tl::TlWrite` and `adnl::tl::TlRead`.
+//!
+//! Container attributes:
+//! - `#[tl(schema = "...")]` - constructor id is the CRC32 of the normalized schema line
+//! - `#[tl(id = 0x...)]` - explicit constructor id
+//! - `#[tl(boxed)]` - constructor id is written before the fields
+//!
+//! Enums are always boxed, every variant must have its own `schema` or `id`, unless it
+//! is a newtype variant over a type implementing `TlConstructor`.
+//!
+//! Field attributes:
+//! - `#[tl(flags)]` - `#` field holding the presence bits of conditional fields
+//! - `#[tl(flags_bit = "mode.N")]` - conditional `Option<T>` field, or `bool` for `mode.N?true`
+
+use crc::{Crc, CRC_32_ISO_HDLC};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Fields, Ident, LitInt,
+    LitStr, Type,
+};
+
+static CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+#[proc_macro_derive(TlWrite, attributes(tl))]
+pub fn derive_tl_write(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_write(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(TlRead, attributes(tl))]
+pub fn derive_tl_read(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_read(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Compute constructor id of TL schema line, e.g.
+/// `adnl.message.query query_id:int256 query:bytes = adnl.Message;`
+fn schema_id(schema: &str) -> u32 {
+    let schema = schema.split("//").next().unwrap_or_default();
+    let schema = schema
+        .trim()
+        .trim_end_matches(';')
+        .replace(['<', '>', '(', ')'], " ");
+    let mut words = schema.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let (name, id) = match name.split_once('#') {
+        Some((name, id)) => (name, u32::from_str_radix(id, 16).ok()),
+        None => (name, None),
+    };
+    if let Some(id) = id {
+        return id;
+    }
+    let normalized = std::iter::once(name).chain(words).collect::<Vec<_>>();
+    CRC.checksum(normalized.join(" ").as_bytes())
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+    id: Option<u32>,
+    boxed: bool,
+}
+
+fn parse_container_attrs(attrs: &[Attribute]) -> syn::Result<ContainerAttrs> {
+    let mut result = ContainerAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("tl")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("boxed") {
+                result.boxed = true;
+                return Ok(());
+            }
+            let id = if meta.path.is_ident("id") {
+                meta.value()?.parse::<LitInt>()?.base10_parse::<u32>()?
+            } else if meta.path.is_ident("schema") {
+                schema_id(&meta.value()?.parse::<LitStr>()?.value())
+            } else {
+                return Err(meta.error("unknown tl attribute"));
+            };
+            if result.id.replace(id).is_some() {
+                return Err(meta.error("constructor id is specified twice"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(result)
+}
+
+enum FieldKind {
+    Plain,
+    Flags,
+    Conditional {
+        flags: Ident,
+        bit: u8,
+        is_bool: bool,
+    },
+}
+
+struct FieldInfo {
+    binding: Ident,
+    ty: Type,
+    kind: FieldKind,
+}
+
+fn parse_fields(fields: &Fields) -> syn::Result<Vec<FieldInfo>> {
+    let mut result: Vec<FieldInfo> = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let binding = field
+            .ident
+            .clone()
+            .unwrap_or_else(|| format_ident!("__field{}", i));
+        let mut kind = FieldKind::Plain;
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("tl")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("flags") {
+                    kind = FieldKind::Flags;
+                    return Ok(());
+                }
+                if !meta.path.is_ident("flags_bit") {
+                    return Err(meta.error("unknown tl attribute"));
+                }
+                let value = meta.value()?.parse::<LitStr>()?;
+                let (flags, bit) = value
+                    .value()
+                    .split_once('.')
+                    .and_then(|(flags, bit)| Some((flags.to_owned(), bit.parse::<u8>().ok()?)))
+                    .filter(|(_, bit)| *bit < 32)
+                    .ok_or_else(|| syn::Error::new(value.span(), "expected \"flags.N\""))?;
+                let declared = result
+                    .iter()
+                    .any(|field| field.binding == flags && matches!(field.kind, FieldKind::Flags));
+                if !declared {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        format!("`{flags}` must be a preceding #[tl(flags)] field"),
+                    ));
+                }
+                kind = FieldKind::Conditional {
+                    flags: Ident::new(&flags, value.span()),
+                    bit,
+                    is_bool: matches!(&field.ty, Type::Path(ty) if ty.path.is_ident("bool")),
+                };
+                Ok(())
+            })?;
+        }
+        result.push(FieldInfo {
+            binding,
+            ty: field.ty.clone(),
+            kind,
+        });
+    }
+    Ok(result)
+}
+
+/// Destructuring pattern of `path` binding every field to its `binding`
+fn fields_pattern(path: TokenStream2, fields: &Fields, infos: &[FieldInfo]) -> TokenStream2 {
+    let bindings = infos.iter().map(|field| &field.binding);
+    match fields {
+        Fields::Named(_) => quote!(#path { #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(#path ( #(#bindings),* )),
+        Fields::Unit => path,
+    }
+}
+
+/// Write fields bound to references by [`fields_pattern`]
+fn write_fields(fields: &[FieldInfo]) -> TokenStream2 {
+    let flags = fields
+        .iter()
+        .filter(|field| matches!(field.kind, FieldKind::Flags))
+        .map(|flags_field| {
+            let flags = &flags_field.binding;
+            let conditional = fields.iter().filter_map(|field| match &field.kind {
+                FieldKind::Conditional {
+                    flags: name,
+                    bit,
+                    is_bool,
+                } if name == flags => Some((&field.binding, *bit, *is_bool)),
+                _ => None,
+            });
+            let mut mask = 0u32;
+            let set_bits = conditional
+                .map(|(binding, bit, is_bool)| {
+                    mask |= 1 << bit;
+                    let present = if is_bool {
+                        quote!(*#binding)
+                    } else {
+                        quote!(#binding.is_some())
+                    };
+                    quote!(if #present { __flags |= 1u32 << #bit; })
+                })
+                .collect::<Vec<_>>();
+            quote! {
+                let #flags = &{
+                    let mut __flags: u32 = *#flags & !#mask;
+                    #(#set_bits)*
+                    __flags
+                };
+            }
+        });
+    let writes = fields.iter().map(|field| {
+        let binding = &field.binding;
+        match &field.kind {
+            FieldKind::Plain | FieldKind::Flags => {
+                quote!(::adnl::tl::TlWrite::write_to(#binding, packet);)
+            }
+            FieldKind::Conditional { is_bool: true, .. } => quote!(),
+            FieldKind::Conditional { .. } => quote! {
+                if let ::core::option::Option::Some(__value) = #binding {
+                    ::adnl::tl::TlWrite::write_to(__value, packet);
+                }
+            },
+        }
+    });
+    quote! {
+        #(#flags)*
+        #(#writes)*
+    }
+}
+
+/// Read fields into local variables named after their bindings
+fn read_fields(fields: &[FieldInfo]) -> TokenStream2 {
+    let reads = fields.iter().map(|field| {
+        let binding = &field.binding;
+        let ty = &field.ty;
+        match &field.kind {
+            FieldKind::Plain | FieldKind::Flags => {
+                quote!(let #binding: #ty = ::adnl::tl::TlRead::read_from(packet)?;)
+            }
+            FieldKind::Conditional {
+                flags,
+                bit,
+                is_bool: true,
+            } => quote!(let #binding = #flags & (1u32 << #bit) != 0;),
+            FieldKind::Conditional { flags, bit, .. } => quote! {
+                let #binding = if #flags & (1u32 << #bit) != 0 {
+                    ::core::option::Option::Some(::adnl::tl::TlRead::read_from(packet)?)
+                } else {
+                    ::core::option::Option::None
+                };
+            },
+        }
+    });
+    quote!(#(#reads)*)
+}
+
+fn unknown_constructor() -> TokenStream2 {
+    quote!(::core::result::Result::Err(
+        ::adnl::tl::TlError::UnknownConstructor(id)
+    ))
+}
+
+/// Constructor id of enum variant as expression
+fn variant_id(variant: &syn::Variant) -> syn::Result<TokenStream2> {
+    if let Some(id) = parse_container_attrs(&variant.attrs)?.id {
+        return Ok(quote!(#id));
+    }
+    match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let ty = &fields.unnamed[0].ty;
+            Ok(quote!(<#ty as ::adnl::tl::TlConstructor>::TL_ID))
+        }
+        _ => Err(syn::Error::new(
+            variant.span(),
+            "variant must have #[tl(schema = \"...\")] or #[tl(id = ...)]",
+        )),
+    }
+}
+
+fn expand_write(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let attrs = parse_container_attrs(&input.attrs)?;
+
+    let (body, constructor) = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields)?;
+            let pattern = fields_pattern(quote!(Self), &data.fields, &fields);
+            let write_id = match (attrs.boxed, attrs.id) {
+                (true, Some(id)) => quote!(::adnl::tl::TlWrite::write_to(&#id, packet);),
+                (true, None) => {
+                    return Err(syn::Error::new(
+                        input.span(),
+                        "boxed struct must have #[tl(schema = \"...\")] or #[tl(id = ...)]",
+                    ))
+                }
+                (false, _) => quote!(),
+            };
+            let write_fields = write_fields(&fields);
+            let constructor = attrs.id.map(|id| {
+                quote! {
+                    impl #impl_generics ::adnl::tl::TlConstructor for #name #ty_generics #where_clause {
+                        const TL_ID: u32 = #id;
+                    }
+                }
+            });
+            let body = quote! {
+                #write_id
+                #[allow(unused_variables)]
+                let #pattern = self;
+                #write_fields
+            };
+            (body, constructor)
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let id = variant_id(variant)?;
+                    let fields = parse_fields(&variant.fields)?;
+                    let ident = &variant.ident;
+                    let pattern = fields_pattern(quote!(Self::#ident), &variant.fields, &fields);
+                    let write_fields = write_fields(&fields);
+                    Ok(quote! {
+                        #pattern => {
+                            ::adnl::tl::TlWrite::write_to(&#id, packet);
+                            #write_fields
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            (quote!(match self { #(#arms)* }), None)
+        }
+        Data::Union(_) => return Err(syn::Error::new(input.span(), "unions are not supported")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::adnl::tl::TlWrite for #name #ty_generics #where_clause {
+            fn write_to(&self, packet: &mut ::adnl::tl::__private::Vec<u8>) {
+                #body
+            }
+        }
+        #constructor
+    })
+}
+
+fn expand_read(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let attrs = parse_container_attrs(&input.attrs)?;
+    let unknown_constructor = unknown_constructor();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields)?;
+            let construct = fields_pattern(quote!(Self), &data.fields, &fields);
+            let read_id = match (attrs.boxed, attrs.id) {
+                (true, Some(expected)) => quote! {
+                    let id: u32 = ::adnl::tl::TlRead::read_from(packet)?;
+                    if id != #expected {
+                        return #unknown_constructor;
+                    }
+                },
+                (true, None) => {
+                    return Err(syn::Error::new(
+                        input.span(),
+                        "boxed struct must have #[tl(schema = \"...\")] or #[tl(id = ...)]",
+                    ))
+                }
+                (false, _) => quote!(),
+            };
+            let read_fields = read_fields(&fields);
+            quote! {
+                #read_id
+                #read_fields
+                ::core::result::Result::Ok(#construct)
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let expected = variant_id(variant)?;
+                    let fields = parse_fields(&variant.fields)?;
+                    let ident = &variant.ident;
+                    let construct = fields_pattern(quote!(Self::#ident), &variant.fields, &fields);
+                    let read_fields = read_fields(&fields);
+                    Ok(quote! {
+                        if id == #expected {
+                            #read_fields
+                            return ::core::result::Result::Ok(#construct);
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                let id: u32 = ::adnl::tl::TlRead::read_from(packet)?;
+                #(#arms)*
+                #unknown_constructor
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new(input.span(), "unions are not supported")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::adnl::tl::TlRead for #name #ty_generics #where_clause {
+            fn read_from(packet: &mut &[u8]) -> ::adnl::tl::TlResult<Self> {
+                #body
+            }
+        }
+    })
+}